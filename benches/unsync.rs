@@ -0,0 +1,63 @@
+#![cfg_attr(feature = "nightly", feature(test))]
+
+extern crate futures;
+extern crate test;
+
+use futures::*;
+use futures::executor::TestExecutor;
+use futures::stream::{self, Stream};
+use futures::unsync;
+
+use test::Bencher;
+
+const N: i32 = 1000;
+
+#[bench]
+fn sync_oneshot(b: &mut Bencher) {
+    b.iter(|| {
+        let (c, p) = oneshot::<i32>();
+        c.complete(1);
+        test::black_box(p.wait().unwrap());
+    });
+}
+
+#[bench]
+fn unsync_oneshot(b: &mut Bencher) {
+    b.iter(|| {
+        let (c, p) = unsync::oneshot::<i32>();
+        c.complete(1);
+        test::black_box(p.wait().unwrap());
+    });
+}
+
+#[bench]
+fn sync_channel(b: &mut Bencher) {
+    b.iter(|| {
+        let (tx, rx) = stream::channel::<i32, ()>();
+        let mut exec = TestExecutor::new();
+        exec.spawn(stream::iter((0..N).map(Ok))
+                       .fold(tx, |tx, i| tx.send(Ok(i)).map_err(|_| ()))
+                       .map(|_| ()));
+        exec.spawn(rx.for_each(|i| {
+            test::black_box(i);
+            Ok(())
+        }));
+        exec.run();
+    });
+}
+
+#[bench]
+fn unsync_channel(b: &mut Bencher) {
+    b.iter(|| {
+        let (tx, rx) = unsync::channel::<i32, ()>();
+        let mut exec = TestExecutor::new();
+        exec.spawn(stream::iter((0..N).map(Ok))
+                       .fold(tx, |tx, i| tx.send(Ok(i)).map_err(|_| ()))
+                       .map(|_| ()));
+        exec.spawn(rx.for_each(|i| {
+            test::black_box(i);
+            Ok(())
+        }));
+        exec.run();
+    });
+}
@@ -10,8 +10,6 @@
 //! extern crate futures;
 //! extern crate futures_cpupool;
 //!
-//! use std::sync::mpsc::channel;
-//!
 //! use futures::Future;
 //! use futures_cpupool::CpuPool;
 //!
@@ -31,11 +29,7 @@
 //! let c = a.join(b).map(|(a, b)| a + b);
 //!
 //! // Block the current thread to get the result.
-//! let (tx, rx) = channel();
-//! c.then(move |res| {
-//!     tx.send(res)
-//! }).forget();
-//! let res = rx.recv().unwrap();
+//! let res = c.wait();
 //!
 //! // Print out the result
 //! println!("{:?}", res);
@@ -2,32 +2,18 @@ extern crate futures;
 extern crate futures_cpupool;
 
 use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
-use std::sync::mpsc::channel;
 use std::thread;
 use std::time::Duration;
 
 use futures::Future;
 use futures_cpupool::CpuPool;
 
-fn get<F>(f: F) -> Result<F::Item, F::Error>
-    where F: Future + Send,
-          F::Item: Send,
-          F::Error: Send,
-{
-    let (tx, rx) = channel();
-    f.then(move |res| {
-        tx.send(res).unwrap();
-        futures::finished::<(), ()>(())
-    }).forget();
-    rx.recv().unwrap()
-}
-
 #[test]
 fn join() {
     let pool = CpuPool::new(2);
     let a = pool.execute(|| 1);
     let b = pool.execute(|| 2);
-    let res = get(a.join(b).map(|(a, b)| a + b));
+    let res = a.join(b).map(|(a, b)| a + b).wait();
 
     assert_eq!(res.unwrap(), 3);
 }
@@ -37,8 +23,8 @@ fn select() {
     let pool = CpuPool::new(2);
     let a = pool.execute(|| 1);
     let b = pool.execute(|| 2);
-    let (item1, next) = get(a.select(b)).ok().unwrap();
-    let item2 = get(next).unwrap();
+    let (item1, next) = a.select(b).wait().ok().unwrap();
+    let item2 = next.wait().unwrap();
 
     assert!(item1 != item2);
     assert!((item1 == 1 && item2 == 2) || (item1 == 2 && item2 == 1));
@@ -59,9 +45,9 @@ fn threads_go_away() {
     thread_local!(static FOO: A = A);
 
     let pool = CpuPool::new(2);
-    get(pool.execute(|| {
+    pool.execute(|| {
         FOO.with(|_| ())
-    })).unwrap();
+    }).wait().unwrap();
     drop(pool);
 
     for _ in 0..100 {
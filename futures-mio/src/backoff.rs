@@ -0,0 +1,234 @@
+use std::cmp;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A strategy for deciding how long to wait between the attempts of a
+/// `Retry` future, and when to give up.
+///
+/// Strategies can be limited with the `max_attempts` and `max_elapsed`
+/// methods, which give up retrying after some number of attempts or amount
+/// of time.
+pub trait Backoff {
+    /// Returns how long to wait before making another attempt, or `None` to
+    /// give up.
+    ///
+    /// This is called after an attempt fails, where `attempts` is the number
+    /// of attempts made so far (always at least 1) and `elapsed` is the time
+    /// since the first attempt started.
+    fn next_delay(&mut self, attempts: u32, elapsed: Duration)
+                  -> Option<Duration>;
+
+    /// Limits this strategy to making at most `attempts` attempts in total.
+    fn max_attempts(self, attempts: u32) -> MaxAttempts<Self>
+        where Self: Sized,
+    {
+        MaxAttempts {
+            backoff: self,
+            max: attempts,
+        }
+    }
+
+    /// Limits this strategy to retrying for at most `dur`, measured from the
+    /// start of the first attempt.
+    ///
+    /// No attempt is started after `dur` has elapsed, but an attempt which is
+    /// already running isn't interrupted.
+    fn max_elapsed(self, dur: Duration) -> MaxElapsed<Self>
+        where Self: Sized,
+    {
+        MaxElapsed {
+            backoff: self,
+            max: dur,
+        }
+    }
+}
+
+/// A backoff strategy which always waits for the same amount of time.
+///
+/// This never gives up on its own, so it's typically combined with
+/// `Backoff::max_attempts` or `Backoff::max_elapsed`.
+#[derive(Clone, Debug)]
+pub struct FixedBackoff {
+    delay: Duration,
+}
+
+/// A backoff strategy whose delay grows exponentially with each attempt.
+///
+/// The first retry waits for the initial delay, and each one after that waits
+/// `factor` times longer than the previous one, up to an optional maximum.
+/// With jitter enabled, each delay is instead picked at random between half
+/// of the computed delay and the full delay, which keeps many clients that
+/// failed at the same time from all retrying at the same time too.
+///
+/// This never gives up on its own, so it's typically combined with
+/// `Backoff::max_attempts` or `Backoff::max_elapsed`.
+#[derive(Debug)]
+pub struct ExponentialBackoff {
+    initial: Duration,
+    factor: u32,
+    max_delay: Option<Duration>,
+    jitter: bool,
+    // State of the xorshift generator used for jitter.
+    rng: u64,
+}
+
+/// A backoff strategy which gives up after a number of attempts.
+///
+/// This is created by the `Backoff::max_attempts` method.
+#[derive(Clone, Debug)]
+pub struct MaxAttempts<B> {
+    backoff: B,
+    max: u32,
+}
+
+/// A backoff strategy which gives up after some amount of time.
+///
+/// This is created by the `Backoff::max_elapsed` method.
+#[derive(Clone, Debug)]
+pub struct MaxElapsed<B> {
+    backoff: B,
+    max: Duration,
+}
+
+impl FixedBackoff {
+    /// Creates a strategy which waits for `delay` before every retry.
+    pub fn new(delay: Duration) -> FixedBackoff {
+        FixedBackoff { delay: delay }
+    }
+}
+
+impl Backoff for FixedBackoff {
+    fn next_delay(&mut self, _attempts: u32, _elapsed: Duration)
+                  -> Option<Duration> {
+        Some(self.delay)
+    }
+}
+
+impl ExponentialBackoff {
+    /// Creates a strategy which waits for `initial` before the first retry and
+    /// doubles the delay for each retry after that.
+    ///
+    /// The delay isn't limited and no jitter is applied by default.
+    pub fn new(initial: Duration) -> ExponentialBackoff {
+        ExponentialBackoff {
+            initial: initial,
+            factor: 2,
+            max_delay: None,
+            jitter: false,
+            rng: seed(),
+        }
+    }
+
+    /// Sets the factor by which the delay grows with each retry.
+    pub fn factor(mut self, factor: u32) -> ExponentialBackoff {
+        self.factor = factor;
+        self
+    }
+
+    /// Limits the delay between two attempts to `max`.
+    pub fn max_delay(mut self, max: Duration) -> ExponentialBackoff {
+        self.max_delay = Some(max);
+        self
+    }
+
+    /// Enables or disables randomizing the delays.
+    pub fn jitter(mut self, jitter: bool) -> ExponentialBackoff {
+        self.jitter = jitter;
+        self
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+}
+
+impl Backoff for ExponentialBackoff {
+    fn next_delay(&mut self, attempts: u32, _elapsed: Duration)
+                  -> Option<Duration> {
+        let max = self.max_delay.unwrap_or(Duration::new(u64::max_value(), 0));
+        let mut delay = cmp::min(self.initial, max);
+        if self.factor > 1 {
+            for _ in 1..attempts {
+                match delay.checked_mul(self.factor) {
+                    Some(d) if d < max => delay = d,
+                    _ => {
+                        delay = max;
+                        break
+                    }
+                }
+            }
+        }
+
+        if self.jitter {
+            let nanos = delay.as_secs()
+                             .saturating_mul(1_000_000_000)
+                             .saturating_add(delay.subsec_nanos() as u64);
+            let half = nanos / 2;
+            let extra = self.next_random() % (nanos - half + 1);
+            let nanos = half + extra;
+            delay = Duration::new(nanos / 1_000_000_000,
+                                  (nanos % 1_000_000_000) as u32);
+        }
+        Some(delay)
+    }
+}
+
+impl<B: Backoff> Backoff for MaxAttempts<B> {
+    fn next_delay(&mut self, attempts: u32, elapsed: Duration)
+                  -> Option<Duration> {
+        if attempts >= self.max {
+            None
+        } else {
+            self.backoff.next_delay(attempts, elapsed)
+        }
+    }
+}
+
+impl<B: Backoff> Backoff for MaxElapsed<B> {
+    fn next_delay(&mut self, attempts: u32, elapsed: Duration)
+                  -> Option<Duration> {
+        let max = self.max;
+        self.backoff.next_delay(attempts, elapsed).and_then(|delay| {
+            match elapsed.checked_add(delay) {
+                Some(end) if end <= max => Some(delay),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl<'a, B: Backoff + ?Sized> Backoff for &'a mut B {
+    fn next_delay(&mut self, attempts: u32, elapsed: Duration)
+                  -> Option<Duration> {
+        (**self).next_delay(attempts, elapsed)
+    }
+}
+
+impl<B: Backoff + ?Sized> Backoff for Box<B> {
+    fn next_delay(&mut self, attempts: u32, elapsed: Duration)
+                  -> Option<Duration> {
+        (**self).next_delay(attempts, elapsed)
+    }
+}
+
+// Jitter doesn't need good randomness, just enough that clients which were
+// started together don't stay in lockstep, so the clock and a counter will do
+// as a seed.
+fn seed() -> u64 {
+    static COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)
+                               .unwrap_or(Duration::new(0, 0));
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed) as u64;
+    let seed = now.as_secs() ^ ((now.subsec_nanos() as u64) << 32) ^
+               count.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    // Xorshift gets stuck on 0, so make sure we never start there.
+    if seed == 0 {
+        0x2545_f491_4f6c_dd1d
+    } else {
+        seed
+    }
+}
@@ -0,0 +1,136 @@
+use std::io;
+use std::mem;
+use std::time::Duration;
+
+use futures::{Task, Poll};
+use futures::stream::{Stream, Fuse};
+
+use LoopHandle;
+use timeout::Delay;
+
+/// A stream combinator which batches up the elements of a stream into
+/// vectors, flushing a batch early if it takes too long to fill up.
+///
+/// This is created by the `LoopHandle::chunks_timeout` method.
+pub struct ChunksTimeout<S: Stream> {
+    stream: Fuse<S>,
+    handle: LoopHandle,
+    items: Vec<S::Item>,
+    cap: usize,
+    dur: Duration,
+    // Armed when the first item of a batch is received.
+    delay: Option<Delay>,
+    // An error which is held back until the elements buffered before it have
+    // been yielded.
+    err: Option<S::Error>,
+}
+
+impl LoopHandle {
+    /// Batches up the elements of `stream` into vectors of at most `capacity`
+    /// elements.
+    ///
+    /// A batch is yielded as soon as it's full, or once `dur` has elapsed
+    /// since its first element was received, whichever comes first. The last
+    /// batch is yielded when the stream ends. If an error happens then the
+    /// elements buffered so far are yielded first, followed by the error.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is 0.
+    pub fn chunks_timeout<S>(self,
+                             stream: S,
+                             capacity: usize,
+                             dur: Duration) -> ChunksTimeout<S>
+        where S: Stream,
+              S::Error: From<io::Error>,
+    {
+        assert!(capacity > 0, "the capacity of chunks_timeout must not be 0");
+
+        ChunksTimeout {
+            stream: stream.fuse(),
+            handle: self,
+            items: Vec::with_capacity(capacity),
+            cap: capacity,
+            dur: dur,
+            delay: None,
+            err: None,
+        }
+    }
+}
+
+impl<S: Stream> ChunksTimeout<S> {
+    fn take(&mut self) -> Vec<S::Item> {
+        self.delay = None;
+        mem::replace(&mut self.items, Vec::with_capacity(self.cap))
+    }
+}
+
+impl<S> Stream for ChunksTimeout<S>
+    where S: Stream,
+          S::Error: From<io::Error>,
+{
+    type Item = Vec<S::Item>;
+    type Error = S::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<Vec<S::Item>>, S::Error> {
+        if let Some(e) = self.err.take() {
+            return Poll::Err(e)
+        }
+
+        loop {
+            match self.stream.poll(task) {
+                Poll::Ok(Some(e)) => {
+                    if self.items.is_empty() {
+                        let at = self.handle.now() + self.dur;
+                        self.delay = Some(Delay::new(self.handle.clone(), at));
+                    }
+                    self.items.push(e);
+                    if self.items.len() >= self.cap {
+                        return Poll::Ok(Some(self.take()))
+                    }
+                }
+                Poll::Ok(None) => {
+                    return if self.items.is_empty() {
+                        Poll::Ok(None)
+                    } else {
+                        Poll::Ok(Some(self.take()))
+                    }
+                }
+                Poll::Err(e) => {
+                    if self.items.is_empty() {
+                        return Poll::Err(e)
+                    }
+                    self.err = Some(e);
+                    return Poll::Ok(Some(self.take()))
+                }
+                Poll::NotReady => break,
+            }
+        }
+
+        let fired = match self.delay {
+            Some(ref mut delay) => {
+                match delay.poll(task) {
+                    Poll::Ok(()) => true,
+                    Poll::Err(e) => return Poll::Err(e.into()),
+                    Poll::NotReady => false,
+                }
+            }
+            None => false,
+        };
+        if fired {
+            Poll::Ok(Some(self.take()))
+        } else {
+            Poll::NotReady
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if self.err.is_some() {
+            return task.notify()
+        }
+        self.stream.schedule(task);
+        if let Some(ref mut delay) = self.delay {
+            delay.schedule(task);
+        }
+    }
+}
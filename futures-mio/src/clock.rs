@@ -0,0 +1,116 @@
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+/// The source of time for an event loop and all of the timers running on it.
+///
+/// By default an event loop uses the system clock, in which case `now` is just
+/// `Instant::now()`. Tests, however, can create an event loop with a paused
+/// clock through `Loop::with_clock`. A paused clock starts at the instant it
+/// was created and only moves forward when `advance` is called, or when an
+/// event loop using it is idle other than waiting for a timer. In that case
+/// the clock jumps straight to the timer's deadline instead of sleeping, so
+/// tests of timeouts and retries run instantly.
+///
+/// An event loop never moves a paused clock forward while any task is waiting
+/// on I/O, such as a TCP connection being established or a listener waiting
+/// for connections. It waits for the I/O instead, however long that takes, so
+/// tests which keep a source waiting for the whole run, like a listener or a
+/// `Signal` stream, need to move the clock with `advance` themselves.
+/// Messages sent to the event loop from other threads aren't waited for, so
+/// tests which do work on other threads should make sure it's done before
+/// relying on the clock to jump.
+///
+/// Clocks can be cloned, and clones refer to the same source of time.
+#[derive(Clone)]
+pub struct Clock {
+    paused: Option<Arc<Paused>>,
+}
+
+struct Paused {
+    now: Mutex<Instant>,
+    // Event loops using this clock, which are woken up whenever the time
+    // changes so they can fire any timeouts which are now due.
+    loops: Mutex<Vec<Weak<Fn() + Send + Sync>>>,
+}
+
+impl Clock {
+    /// Creates a clock which reads the system's time.
+    pub fn system() -> Clock {
+        Clock { paused: None }
+    }
+
+    /// Creates a clock which is paused at the current instant.
+    pub fn paused() -> Clock {
+        Clock {
+            paused: Some(Arc::new(Paused {
+                now: Mutex::new(Instant::now()),
+                loops: Mutex::new(Vec::new()),
+            })),
+        }
+    }
+
+    /// Returns whether this clock is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    /// Returns the current instant according to this clock.
+    pub fn now(&self) -> Instant {
+        match self.paused {
+            Some(ref paused) => *paused.now.lock().unwrap(),
+            None => Instant::now(),
+        }
+    }
+
+    /// Moves a paused clock forward by `dur`.
+    ///
+    /// Every event loop using this clock will fire all of the timeouts which
+    /// are due at the new time.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the clock isn't paused.
+    pub fn advance(&self, dur: Duration) {
+        let now = self.now();
+        self.advance_to(now + dur);
+    }
+
+    /// Moves a paused clock forward to `at`, doing nothing if the clock has
+    /// already passed it.
+    ///
+    /// Every event loop using this clock will fire all of the timeouts which
+    /// are due at the new time.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the clock isn't paused.
+    pub fn advance_to(&self, at: Instant) {
+        let paused = self.paused.as_ref().expect("advancing an unpaused clock");
+        {
+            let mut now = paused.now.lock().unwrap();
+            if at <= *now {
+                return
+            }
+            *now = at;
+        }
+        let mut loops = paused.loops.lock().unwrap();
+        loops.retain(|lp| {
+            match lp.upgrade() {
+                Some(wake) => {
+                    wake();
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+}
+
+/// Registers an event loop to get woken up whenever `clock` is advanced.
+///
+/// The event loop stays registered until `wake` is dropped.
+pub fn add_loop(clock: &Clock, wake: &Arc<Fn() + Send + Sync>) {
+    if let Some(ref paused) = clock.paused {
+        paused.loops.lock().unwrap().push(Arc::downgrade(wake));
+    }
+}
@@ -0,0 +1,153 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::time::{Duration, Instant};
+
+use futures::{Future, Task, Poll};
+
+use LoopHandle;
+use timeout::Delay;
+
+/// A future which resolves to an error if its inner future doesn't resolve
+/// before a deadline.
+///
+/// If the inner future resolves first, the timeout registered with the event
+/// loop is canceled right away. If the deadline passes first, the inner
+/// future is simply dropped along with this one, which cancels any work it
+/// was doing.
+///
+/// This is created by the `LoopHandle::deadline` and
+/// `LoopHandle::deadline_at` methods.
+pub struct Deadline<F> {
+    future: F,
+    delay: Delay,
+}
+
+/// The error produced by `Deadline` and `TimeoutStream`.
+#[derive(Debug)]
+pub enum DeadlineError<E> {
+    /// The deadline passed before the inner future or stream was ready.
+    TimedOut,
+
+    /// The inner future or stream produced an error.
+    Inner(E),
+
+    /// The timeout couldn't be registered with the event loop.
+    Timer(io::Error),
+}
+
+impl LoopHandle {
+    /// Creates a future which resolves to the same value as `future`, or to
+    /// `DeadlineError::TimedOut` if `future` doesn't resolve within `dur`.
+    pub fn deadline<F: Future>(self, future: F, dur: Duration) -> Deadline<F> {
+        let now = self.now();
+        self.deadline_at(future, now + dur)
+    }
+
+    /// Creates a future which resolves to the same value as `future`, or to
+    /// `DeadlineError::TimedOut` if `future` hasn't resolved by `at`.
+    pub fn deadline_at<F: Future>(self, future: F, at: Instant) -> Deadline<F> {
+        Deadline {
+            future: future,
+            delay: Delay::new(self, at),
+        }
+    }
+}
+
+impl<F> Deadline<F> {
+    /// Consumes this deadline, returning the underlying future.
+    pub fn into_inner(self) -> F {
+        self.future
+    }
+}
+
+impl<F: Future> Future for Deadline<F> {
+    type Item = F::Item;
+    type Error = DeadlineError<F::Error>;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<F::Item, DeadlineError<F::Error>> {
+        match self.future.poll(task) {
+            Poll::Ok(item) => {
+                self.delay.cancel();
+                return Poll::Ok(item)
+            }
+            Poll::Err(e) => {
+                self.delay.cancel();
+                return Poll::Err(DeadlineError::Inner(e))
+            }
+            Poll::NotReady => {}
+        }
+
+        match self.delay.poll(task) {
+            Poll::Ok(()) => Poll::Err(DeadlineError::TimedOut),
+            Poll::Err(e) => Poll::Err(DeadlineError::Timer(e)),
+            Poll::NotReady => Poll::NotReady,
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.future.schedule(task);
+        self.delay.schedule(task);
+    }
+}
+
+impl<E> DeadlineError<E> {
+    /// Returns whether this error is because the deadline passed.
+    pub fn is_timed_out(&self) -> bool {
+        match *self {
+            DeadlineError::TimedOut => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the error of the inner future or stream, if that's what this
+    /// error is.
+    pub fn into_inner(self) -> Option<E> {
+        match self {
+            DeadlineError::Inner(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for DeadlineError<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DeadlineError::TimedOut => fmt.write_str("deadline has elapsed"),
+            DeadlineError::Inner(ref e) => e.fmt(fmt),
+            DeadlineError::Timer(ref e) => {
+                write!(fmt, "failed to register timeout: {}", e)
+            }
+        }
+    }
+}
+
+impl<E: Error> Error for DeadlineError<E> {
+    fn description(&self) -> &str {
+        match *self {
+            DeadlineError::TimedOut => "deadline has elapsed",
+            DeadlineError::Inner(ref e) => e.description(),
+            DeadlineError::Timer(ref e) => e.description(),
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            DeadlineError::TimedOut => None,
+            DeadlineError::Inner(ref e) => Some(e),
+            DeadlineError::Timer(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<DeadlineError<io::Error>> for io::Error {
+    fn from(err: DeadlineError<io::Error>) -> io::Error {
+        match err {
+            DeadlineError::TimedOut => {
+                io::Error::new(io::ErrorKind::TimedOut, "deadline has elapsed")
+            }
+            DeadlineError::Inner(e) => e,
+            DeadlineError::Timer(e) => e,
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::io;
+use std::time::Duration;
+
+use futures::{Task, Poll};
+use futures::stream::{Stream, Fuse};
+
+use LoopHandle;
+use timeout::Delay;
+
+/// A stream combinator which only yields an item once its underlying stream
+/// has been quiet for some time.
+///
+/// This is created by the `LoopHandle::debounce` method.
+pub struct Debounce<S: Stream> {
+    stream: Fuse<S>,
+    handle: LoopHandle,
+    dur: Duration,
+    // The most recent item received along with the delay until it's yielded.
+    pending: Option<(S::Item, Delay)>,
+}
+
+impl LoopHandle {
+    /// Yields an item from `stream` only once no other item has followed it
+    /// for `dur`.
+    ///
+    /// Each item received restarts the timer, and any item which was waiting
+    /// is discarded in favor of the new one. When the stream ends, the item
+    /// which was waiting, if any, is yielded immediately. Errors are passed
+    /// through as soon as they're received.
+    pub fn debounce<S>(self, stream: S, dur: Duration) -> Debounce<S>
+        where S: Stream,
+              S::Error: From<io::Error>,
+    {
+        Debounce {
+            stream: stream.fuse(),
+            handle: self,
+            dur: dur,
+            pending: None,
+        }
+    }
+}
+
+impl<S> Stream for Debounce<S>
+    where S: Stream,
+          S::Error: From<io::Error>,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<S::Item>, S::Error> {
+        loop {
+            match self.stream.poll(task) {
+                Poll::Ok(Some(item)) => {
+                    let at = self.handle.now() + self.dur;
+                    let delay = match self.pending.take() {
+                        Some((_, mut delay)) => {
+                            delay.reset(at);
+                            delay
+                        }
+                        None => Delay::new(self.handle.clone(), at),
+                    };
+                    self.pending = Some((item, delay));
+                }
+                Poll::Ok(None) => {
+                    return Poll::Ok(self.pending.take().map(|(item, _)| item))
+                }
+                Poll::Err(e) => return Poll::Err(e),
+                Poll::NotReady => break,
+            }
+        }
+
+        let fired = match self.pending {
+            Some((_, ref mut delay)) => {
+                match delay.poll(task) {
+                    Poll::Ok(()) => true,
+                    Poll::Err(e) => return Poll::Err(e.into()),
+                    Poll::NotReady => false,
+                }
+            }
+            None => false,
+        };
+        if fired {
+            Poll::Ok(self.pending.take().map(|(item, _)| item))
+        } else {
+            Poll::NotReady
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.stream.schedule(task);
+        if let Some((_, ref mut delay)) = self.pending {
+            delay.schedule(task);
+        }
+    }
+}
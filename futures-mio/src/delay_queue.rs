@@ -0,0 +1,333 @@
+use std::cmp;
+use std::io;
+use std::time::{Duration, Instant};
+
+use futures::{Task, TaskHandle, Poll};
+use futures::stream::Stream;
+use slab::Slab;
+
+use LoopHandle;
+use timeout::Delay;
+use timer_wheel::{TimerWheel, Timeout};
+
+/// A queue of values which are yielded as a stream once their deadlines
+/// expire.
+///
+/// Values are inserted with a deadline and identified by the `DelayKey`
+/// returned on insertion, which can later be used to reset the deadline of a
+/// value or to remove it from the queue entirely. This makes it handy for
+/// things like timing out idle connections or expiring cache entries, where
+/// deadlines are frequently pushed back.
+///
+/// The queue keeps its own timer wheel, and only a single timeout for the
+/// earliest deadline is registered with the event loop at any time. Like
+/// other timers values are yielded with some granularity after their
+/// deadline, but never before it.
+///
+/// The stream never ends on its own. When the queue is empty it's simply not
+/// ready, and the task polling it is woken up again once a value is inserted.
+pub struct DelayQueue<T> {
+    handle: LoopHandle,
+    wheel: TimerWheel<usize>,
+    entries: Slab<Entry<T>, usize>,
+    // The timer wheel can't take deadlines from before it was created, so
+    // earlier ones are moved up to this instant.
+    created: Instant,
+    next_gen: u64,
+    // Values taken out of the wheel which have expired, as (deadline,
+    // generation, index) triples sorted so the earliest one is at the end.
+    ready: Vec<(Instant, u64, usize)>,
+    // A timeout for the earliest deadline in the wheel, if there is one.
+    delay: Option<Delay>,
+    // The task polling the queue, to wake up when a value is inserted with a
+    // deadline earlier than `delay`.
+    task: Option<TaskHandle>,
+}
+
+/// A key identifying a value in a `DelayQueue`.
+///
+/// Keys are invalidated once their value is yielded or removed from the
+/// queue, after which they aren't found in the queue anymore, even if the
+/// space for their value is reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DelayKey {
+    index: usize,
+    gen: u64,
+}
+
+/// A value yielded from a `DelayQueue` once its deadline expired.
+#[derive(Debug)]
+pub struct Expired<T> {
+    data: T,
+    deadline: Instant,
+    key: DelayKey,
+}
+
+struct Entry<T> {
+    data: T,
+    when: Instant,
+    gen: u64,
+    // The entry in the wheel, or `None` if this value is in `ready`.
+    timeout: Option<Timeout>,
+}
+
+const SLAB_CAPACITY: usize = 64;
+
+impl<T> DelayQueue<T> {
+    /// Creates a new, empty queue whose timeouts run on the event loop of
+    /// `handle`.
+    pub fn new(handle: LoopHandle) -> DelayQueue<T> {
+        let now = handle.now();
+        DelayQueue {
+            handle: handle,
+            wheel: TimerWheel::starting_at(now),
+            entries: Slab::new_starting_at(0, SLAB_CAPACITY),
+            created: now,
+            next_gen: 0,
+            ready: Vec::new(),
+            delay: None,
+            task: None,
+        }
+    }
+
+    /// Inserts `value` into the queue to be yielded after `dur` has elapsed.
+    pub fn insert(&mut self, value: T, dur: Duration) -> DelayKey {
+        let at = self.handle.now() + dur;
+        self.insert_at(value, at)
+    }
+
+    /// Inserts `value` into the queue to be yielded once `at` has passed.
+    pub fn insert_at(&mut self, value: T, at: Instant) -> DelayKey {
+        if self.entries.vacant_entry().is_none() {
+            let amt = self.entries.count();
+            self.entries.grow(amt);
+        }
+        let gen = self.next_gen;
+        self.next_gen += 1;
+
+        let entry = self.entries.vacant_entry().unwrap();
+        let index = entry.index();
+        let timeout = self.wheel.insert(cmp::max(at, self.created), index);
+        entry.insert(Entry {
+            data: value,
+            when: at,
+            gen: gen,
+            timeout: Some(timeout),
+        });
+        self.notify_if_earlier(at);
+        DelayKey { index: index, gen: gen }
+    }
+
+    /// Removes the value identified by `key` from the queue, returning it.
+    ///
+    /// Returns `None` if `key` is no longer in the queue.
+    pub fn remove(&mut self, key: &DelayKey) -> Option<T> {
+        if !self.contains(key) {
+            return None
+        }
+        let entry = self.entries.remove(key.index).unwrap();
+        match entry.timeout {
+            Some(ref timeout) => {
+                self.wheel.cancel(timeout);
+            }
+            None => self.ready.retain(|&(_, gen, _)| gen != key.gen),
+        }
+        Some(entry.data)
+    }
+
+    /// Changes the deadline of the value identified by `key` to `dur` from
+    /// now.
+    ///
+    /// Returns whether `key` was found in the queue.
+    pub fn reset(&mut self, key: &DelayKey, dur: Duration) -> bool {
+        let at = self.handle.now() + dur;
+        self.reset_at(key, at)
+    }
+
+    /// Changes the deadline of the value identified by `key` to `at`.
+    ///
+    /// Returns whether `key` was found in the queue.
+    pub fn reset_at(&mut self, key: &DelayKey, at: Instant) -> bool {
+        if !self.contains(key) {
+            return false
+        }
+        {
+            let created = self.created;
+            let entry = self.entries.get_mut(key.index).unwrap();
+            match entry.timeout {
+                Some(ref timeout) => {
+                    self.wheel.cancel(timeout);
+                }
+                None => self.ready.retain(|&(_, gen, _)| gen != key.gen),
+            }
+            let at_or_created = cmp::max(at, created);
+            entry.timeout = Some(self.wheel.insert(at_or_created, key.index));
+            entry.when = at;
+        }
+        self.notify_if_earlier(at);
+        true
+    }
+
+    /// Returns whether the value identified by `key` is still in the queue.
+    pub fn contains(&self, key: &DelayKey) -> bool {
+        match self.entries.get(key.index) {
+            Some(entry) => entry.gen == key.gen,
+            None => false,
+        }
+    }
+
+    /// Returns a reference to the value identified by `key`, if it's still in
+    /// the queue.
+    pub fn get(&self, key: &DelayKey) -> Option<&T> {
+        match self.entries.get(key.index) {
+            Some(entry) if entry.gen == key.gen => Some(&entry.data),
+            _ => None,
+        }
+    }
+
+    /// Returns the deadline of the value identified by `key`, if it's still
+    /// in the queue.
+    pub fn deadline(&self, key: &DelayKey) -> Option<Instant> {
+        match self.entries.get(key.index) {
+            Some(entry) if entry.gen == key.gen => Some(entry.when),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of values in the queue.
+    pub fn len(&self) -> usize {
+        self.entries.count()
+    }
+
+    /// Returns whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every value from the queue.
+    pub fn clear(&mut self) {
+        let now = self.handle.now();
+        self.wheel = TimerWheel::starting_at(now);
+        self.entries = Slab::new_starting_at(0, SLAB_CAPACITY);
+        self.created = now;
+        self.ready.clear();
+        self.delay = None;
+    }
+
+    // Wakes up the task polling the queue if `at` is earlier than the timeout
+    // it's waiting on, so it can register a new one.
+    fn notify_if_earlier(&self, at: Instant) {
+        let earlier = match self.delay {
+            Some(ref delay) => at < delay.at(),
+            None => true,
+        };
+        if earlier {
+            if let Some(ref task) = self.task {
+                task.notify();
+            }
+        }
+    }
+
+    // Returns whether `poll` has left something to do, because values were
+    // inserted or reset since then.
+    fn needs_poll(&self) -> bool {
+        if !self.ready.is_empty() {
+            return true
+        }
+        match (self.wheel.next_timeout(), &self.delay) {
+            (Some(next), &Some(ref delay)) => delay.at() != next,
+            (Some(_), &None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+impl<T: 'static> Stream for DelayQueue<T> {
+    type Item = Expired<T>;
+    type Error = io::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<Expired<T>>, io::Error> {
+        loop {
+            // The wheel only knows deadlines from when the queue was created
+            // onwards, so values with earlier ones come out of it in no
+            // particular order. Collect everything which has expired and
+            // sort it to yield values in order of their deadlines.
+            let now = self.handle.now();
+            let before = self.ready.len();
+            while let Some(index) = self.wheel.poll(now) {
+                let entry = &mut self.entries[index];
+                entry.timeout = None;
+                self.ready.push((entry.when, entry.gen, index));
+            }
+            if self.ready.len() > before {
+                self.ready.sort_by(|a, b| b.cmp(a));
+            }
+
+            if let Some((_, gen, index)) = self.ready.pop() {
+                let entry = self.entries.remove(index).unwrap();
+                return Poll::Ok(Some(Expired {
+                    data: entry.data,
+                    deadline: entry.when,
+                    key: DelayKey { index: index, gen: gen },
+                }))
+            }
+
+            // Nothing has expired yet, so make sure there's a timeout on the
+            // event loop to wake us up when the next value does.
+            let next = match self.wheel.next_timeout() {
+                Some(next) => next,
+                None => {
+                    self.delay = None;
+                    return Poll::NotReady
+                }
+            };
+            let stale = match self.delay {
+                Some(ref delay) => delay.at() != next,
+                None => true,
+            };
+            if stale {
+                self.delay = Some(Delay::new(self.handle.clone(), next));
+            }
+            match self.delay.as_mut().unwrap().poll(task) {
+                Poll::Ok(()) => self.delay = None,
+                Poll::Err(e) => return Poll::Err(e),
+                Poll::NotReady => return Poll::NotReady,
+            }
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.task = Some(task.handle().clone());
+        if self.needs_poll() {
+            return task.notify()
+        }
+        if let Some(ref mut delay) = self.delay {
+            delay.schedule(task);
+        }
+    }
+}
+
+impl<T> Expired<T> {
+    /// Returns a reference to the value which expired.
+    pub fn get_ref(&self) -> &T {
+        &self.data
+    }
+
+    /// Consumes this, returning the value which expired.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    /// Returns the deadline the value had.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Returns the key the value had in the queue.
+    ///
+    /// The key is no longer in the queue, but this is useful to clean up
+    /// any other bookkeeping done with it.
+    pub fn key(&self) -> DelayKey {
+        self.key
+    }
+}
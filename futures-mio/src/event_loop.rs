@@ -15,6 +15,7 @@ use mio;
 use slab::Slab;
 
 use channel::{Sender, Receiver, channel};
+use clock::{self, Clock};
 use event_loop::dropbox::DropBox;
 use slot::{self, Slot};
 use timer_wheel::{TimerWheel, Timeout};
@@ -39,6 +40,11 @@ pub struct Loop {
     rx: Receiver<Message>,
     dispatch: RefCell<Slab<Scheduled, usize>>,
 
+    // The source of time for all timeouts, along with the callback that wakes
+    // up this loop when a paused clock is advanced.
+    clock: Clock,
+    _clock_wake: Arc<Fn() + Send + Sync>,
+
     // Timer wheel keeping track of all timeouts. The `usize` stored in the
     // timer wheel is an index into the slab below.
     //
@@ -68,6 +74,7 @@ struct MioSender {
 pub struct LoopHandle {
     id: usize,
     tx: Arc<MioSender>,
+    clock: Clock,
 }
 
 /// A non-sendable handle to an event loop, useful for manufacturing instances
@@ -100,6 +107,7 @@ enum Message {
     Run(Box<ExecuteCallback>),
     Drop(DropBox<dropbox::MyDrop>),
     Shutdown,
+    Wakeup,
 }
 
 /// Type of I/O objects inserted into the event loop, created by `Source::new`.
@@ -129,21 +137,41 @@ impl Loop {
     /// Creates a new event loop, returning any error that happened during the
     /// creation.
     pub fn new() -> io::Result<Loop> {
+        Loop::with_clock(Clock::system())
+    }
+
+    /// Creates a new event loop which gets the current time from `clock`,
+    /// returning any error that happened during the creation.
+    ///
+    /// This is primarily useful for tests, which can pass a paused clock to
+    /// control the passage of time on the event loop by hand.
+    pub fn with_clock(clock: Clock) -> io::Result<Loop> {
         let (tx, rx) = channel();
         let io = try!(mio::Poll::new());
         try!(io.register(&rx,
                          mio::Token(0),
                          mio::EventSet::readable(),
                          mio::PollOpt::edge()));
+        let tx = Arc::new(MioSender { inner: tx });
+        let wake_tx = tx.clone();
+        let wake: Arc<Fn() + Send + Sync> = Arc::new(move || {
+            // The loop may have already gone away, in which case there's
+            // nothing to wake up.
+            drop(wake_tx.inner.send(Message::Wakeup));
+        });
+        clock::add_loop(&clock, &wake);
+        let start = clock.now();
         Ok(Loop {
             id: NEXT_LOOP_ID.fetch_add(1, Ordering::Relaxed),
             active: Cell::new(true),
             io: io,
-            tx: Arc::new(MioSender { inner: tx }),
+            tx: tx,
             rx: rx,
             dispatch: RefCell::new(Slab::new_starting_at(1, SLAB_CAPACITY)),
+            clock: clock,
+            _clock_wake: wake,
             timeouts: RefCell::new(Slab::new_starting_at(0, SLAB_CAPACITY)),
-            timer_wheel: RefCell::new(TimerWheel::new()),
+            timer_wheel: RefCell::new(TimerWheel::starting_at(start)),
             _marker: marker::PhantomData,
         })
     }
@@ -157,6 +185,7 @@ impl Loop {
         LoopHandle {
             id: self.id,
             tx: self.tx.clone(),
+            clock: self.clock.clone(),
         }
     }
 
@@ -211,15 +240,29 @@ impl Loop {
             // On Linux, Poll::poll is epoll_wait, which may return EINTR if a
             // ptracer attaches. This retry loop prevents crashing when
             // attaching strace, or similar.
-            let start = Instant::now();
+            let start = self.clock.now();
             loop {
-                let timeout = self.timer_wheel.borrow().next_timeout().map(|t| {
-                    if t < start {
-                        Duration::new(0, 0)
+                let timeout = if self.clock.is_paused() {
+                    // A paused clock doesn't move on its own, so just check
+                    // for events and otherwise jump to the timeout below.
+                    // While a task is waiting on I/O, though, the clock stays
+                    // put until something happens.
+                    if self.waiting_on_io() {
+                        None
                     } else {
-                        t - start
+                        self.timer_wheel.borrow().next_timeout().map(|_| {
+                            Duration::new(0, 0)
+                        })
                     }
-                });
+                } else {
+                    self.timer_wheel.borrow().next_timeout().map(|t| {
+                        if t < start {
+                            Duration::new(0, 0)
+                        } else {
+                            t - start
+                        }
+                    })
+                };
                 match self.io.poll(&mut events, timeout) {
                     Ok(a) => {
                         amt = a;
@@ -231,10 +274,23 @@ impl Loop {
                     }
                 }
             }
-            debug!("loop poll - {:?}", start.elapsed());
+            debug!("loop poll - {:?}", self.clock.now() - start);
+
+            // If there's nothing to do but wait for a timeout on a paused
+            // clock, then skip the wait and move time forward to it. Once the
+            // earliest timeout is near the wheel reports its exact instant, so
+            // timers fire at the simulated instant they were scheduled for.
+            // Any messages already sent to the loop would've shown up as an
+            // event on the queue above.
+            if amt == 0 && self.clock.is_paused() && !self.waiting_on_io() {
+                let next = self.timer_wheel.borrow().next_timeout();
+                if let Some(next) = next {
+                    self.clock.advance_to(next);
+                }
+            }
 
             // First up, process all timeouts that may have just occurred.
-            let start = Instant::now();
+            let start = self.clock.now();
             self.consume_timeouts(start);
 
             // Next, process all the events that came in.
@@ -277,7 +333,8 @@ impl Loop {
                 }
             }
 
-            debug!("loop process - {} events, {:?}", amt, start.elapsed());
+            debug!("loop process - {} events, {:?}", amt,
+                   self.clock.now() - start);
         }
 
         debug!("loop is done!");
@@ -320,6 +377,11 @@ impl Loop {
         Ok(entry.insert(sched).index())
     }
 
+    // Returns whether any task is blocked on a source becoming ready.
+    fn waiting_on_io(&self) -> bool {
+        self.dispatch.borrow().iter().any(|sched| sched.waiter.is_some())
+    }
+
     fn drop_source(&self, token: usize) {
         let sched = self.dispatch.borrow_mut().remove(token).unwrap();
         deregister(&self.io, &sched);
@@ -393,6 +455,7 @@ impl Loop {
             Message::Schedule(tok, wake) => self.schedule(tok, wake),
             Message::Deschedule(tok) => self.deschedule(tok),
             Message::Shutdown => self.active.set(false),
+            Message::Wakeup => {}
 
             Message::AddTimeout(at, slot) => {
                 slot.try_produce(self.add_timeout(at))
@@ -536,6 +599,21 @@ impl LoopHandle {
         self.send(Message::DropSource(tok));
     }
 
+    /// Returns the current instant according to the clock of the associated
+    /// event loop.
+    ///
+    /// All timers created through this handle measure time with this clock,
+    /// and this is just `Instant::now()` unless the event loop was created
+    /// with a paused clock.
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    /// Returns the clock of the associated event loop.
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
     /// Adds a new timeout to get fired at the specified instant, notifying the
     /// specified task.
     pub fn add_timeout(&self, at: Instant) -> AddTimeout {
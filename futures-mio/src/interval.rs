@@ -0,0 +1,130 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use futures::{Task, Poll};
+use futures::stream::Stream;
+
+use LoopHandle;
+use timeout::Delay;
+
+/// A stream representing notifications at a fixed interval.
+///
+/// Intervals are created through the `LoopHandle::interval` or
+/// `LoopHandle::interval_at` methods. Each tick is driven by a timeout on the
+/// event loop, so like timeouts these ticks are not intended to be high
+/// resolution and will likely fire some granularity after they're due.
+///
+/// Ticks are scheduled relative to when the previous tick was due, rather than
+/// when it was actually yielded, so an interval doesn't drift over time. What
+/// happens when ticks are missed, for example because the event loop was busy
+/// or the stream wasn't polled for a while, is configured with
+/// `set_missed_ticks`.
+pub struct Interval {
+    delay: Delay,
+    dur: Duration,
+    missed_ticks: MissedTicks,
+}
+
+/// The ways in which an `Interval` can catch up after missing ticks.
+///
+/// A tick counts as missed if it's yielded so late that the tick after it is
+/// already due as well.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MissedTicks {
+    /// Missed ticks are yielded right away, one after another, until the
+    /// interval has caught up with its original schedule.
+    ///
+    /// This is the default.
+    Burst,
+
+    /// The next tick is scheduled a full period after the late tick was
+    /// yielded, shifting the schedule back by however late it was.
+    Delay,
+
+    /// Missed ticks are skipped, and the next tick happens at the next point
+    /// in time on the original schedule.
+    Skip,
+}
+
+impl LoopHandle {
+    /// Creates a new interval which will fire every `dur`, starting `dur` time
+    /// into the future.
+    pub fn interval(self, dur: Duration) -> Interval {
+        let now = self.now();
+        self.interval_at(now + dur, dur)
+    }
+
+    /// Creates a new interval which will first fire at `at` and then every
+    /// `dur` afterwards.
+    pub fn interval_at(self, at: Instant, dur: Duration) -> Interval {
+        Interval {
+            delay: Delay::new(self, at),
+            dur: dur,
+            missed_ticks: MissedTicks::Burst,
+        }
+    }
+}
+
+impl Interval {
+    /// Configures how this interval catches up after missing ticks.
+    pub fn set_missed_ticks(&mut self, missed_ticks: MissedTicks) {
+        self.missed_ticks = missed_ticks;
+    }
+
+    /// Returns how this interval catches up after missing ticks.
+    pub fn missed_ticks(&self) -> MissedTicks {
+        self.missed_ticks
+    }
+
+    // Figures out when the tick after the one which was due at `due` should
+    // happen.
+    fn next_tick(&self, due: Instant) -> Instant {
+        let next = due + self.dur;
+        let now = self.delay.handle().now();
+        if next > now {
+            return next
+        }
+        match self.missed_ticks {
+            MissedTicks::Burst => next,
+            MissedTicks::Delay => now + self.dur,
+            MissedTicks::Skip => {
+                let period = nanos(self.dur);
+                if period == 0 {
+                    return now
+                }
+                // Move forward by however many whole periods have passed
+                // since `next`, plus one more to land in the future.
+                let behind = nanos(now - next) / period + 1;
+                let skip = behind.saturating_mul(period);
+                next + Duration::new(skip / 1_000_000_000,
+                                     (skip % 1_000_000_000) as u32)
+            }
+        }
+    }
+}
+
+fn nanos(dur: Duration) -> u64 {
+    dur.as_secs()
+       .saturating_mul(1_000_000_000)
+       .saturating_add(dur.subsec_nanos() as u64)
+}
+
+impl Stream for Interval {
+    type Item = ();
+    type Error = io::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<()>, io::Error> {
+        match self.delay.poll(task) {
+            Poll::Ok(()) => {}
+            Poll::Err(e) => return Poll::Err(e),
+            Poll::NotReady => return Poll::NotReady,
+        }
+        let next = self.next_tick(self.delay.at());
+        self.delay.reset(next);
+        Poll::Ok(Some(()))
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.delay.schedule(task)
+    }
+}
@@ -2,6 +2,17 @@
 //!
 //! This crate uses the `futures_io` and `futures` crates to provide a thin
 //! binding on top of mio of TCP and UDP sockets.
+//!
+//! Along with I/O, the event loop also drives timers: one-off timeouts,
+//! periodic intervals, deadlines, retries with backoff, a `DelayQueue`, and a
+//! few stream combinators which work with time. These are all built on the
+//! event loop's timer wheel and created through a `LoopHandle`. An event loop
+//! created with a paused `Clock` runs all of these in simulated time, which
+//! is useful for testing them without actually waiting.
+//!
+//! On Unix, the event loop can also deliver signals sent to the process
+//! through a `Signal` stream, and spawn child processes whose standard I/O and
+//! exit are driven by the event loop through `CommandExt::spawn_async`.
 
 #![deny(missing_docs)]
 
@@ -16,22 +27,54 @@ extern crate scoped_tls;
 #[macro_use]
 extern crate log;
 
+#[cfg(unix)]
+extern crate libc;
+
 mod readiness_stream;
 mod event_loop;
+mod clock;
 mod tcp;
 mod udp;
 mod timeout;
 mod timer_wheel;
+mod interval;
+mod throttle;
+mod debounce;
+mod timeout_stream;
+mod chunks_timeout;
+mod deadline;
+mod backoff;
+mod retry;
+mod delay_queue;
 #[path = "../../src/slot.rs"]
 mod slot;
 #[path = "../../src/lock.rs"]
 mod lock;
 mod mpsc_queue;
 mod channel;
+#[cfg(unix)]
+mod signal;
+#[cfg(unix)]
+mod process;
 
 pub use event_loop::{Loop, LoopPin, LoopHandle, AddSource, AddTimeout};
 pub use event_loop::{LoopData, AddLoopData, TimeoutToken, IoSource, Source};
 pub use readiness_stream::ReadinessStream;
+pub use clock::Clock;
 pub use tcp::{TcpListener, TcpStream};
 pub use timeout::Timeout;
+pub use interval::{Interval, MissedTicks};
+pub use throttle::Throttle;
+pub use debounce::Debounce;
+pub use timeout_stream::TimeoutStream;
+pub use chunks_timeout::ChunksTimeout;
+pub use deadline::{Deadline, DeadlineError};
+pub use backoff::{Backoff, FixedBackoff, ExponentialBackoff};
+pub use backoff::{MaxAttempts, MaxElapsed};
+pub use retry::{Retry, RetryError};
+pub use delay_queue::{DelayQueue, DelayKey, Expired};
 pub use udp::UdpSocket;
+#[cfg(unix)]
+pub use signal::Signal;
+#[cfg(unix)]
+pub use process::{CommandExt, Child, ChildStdin, ChildStdout, ChildStderr};
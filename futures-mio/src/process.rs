@@ -0,0 +1,295 @@
+use std::io::{self, Read, Write};
+use std::os::unix::prelude::*;
+use std::process::{self, Command, ExitStatus};
+use std::sync::Arc;
+
+use futures::stream::Stream;
+use futures::{Future, failed, finished, Task, Poll};
+use futures_io::{Ready, IoFuture};
+use libc;
+use mio;
+use mio::unix::EventedFd;
+
+use {ReadinessStream, LoopHandle, Signal};
+use event_loop::Source;
+
+/// Extension methods for `std::process::Command` to spawn children whose
+/// I/O and exit are driven by an event loop.
+pub trait CommandExt {
+    /// Spawns the command as a child process bound to the event loop of
+    /// `handle`.
+    ///
+    /// This returns a future resolving to the `Child`, once its standard I/O
+    /// handles have been registered with the event loop. Any handles which
+    /// were configured with `Stdio::piped` become nonblocking `ChildStdin`,
+    /// `ChildStdout`, and `ChildStderr` objects, which implement `WriteTask`
+    /// and `ReadTask` respectively.
+    ///
+    /// The returned future will resolve to an error if the child couldn't be
+    /// spawned.
+    fn spawn_async(&mut self, handle: LoopHandle) -> IoFuture<Child>;
+}
+
+/// A child process spawned through `CommandExt::spawn_async`.
+///
+/// The child itself is a future which resolves to its exit status once it
+/// exits. This is driven by `SIGCHLD` rather than blocking the event loop in
+/// `wait`, so any number of children can be waited on by one event loop.
+///
+/// Like `std::process::Child`, dropping a `Child` doesn't kill the process,
+/// and the process lingers as a zombie until the child is waited on.
+pub struct Child {
+    child: process::Child,
+    sigchld: Signal,
+    stdin: Option<ChildStdin>,
+    stdout: Option<ChildStdout>,
+    stderr: Option<ChildStderr>,
+}
+
+/// The standard input of a child process, which implements `WriteTask`.
+///
+/// Dropping the handle closes the child's standard input.
+pub struct ChildStdin {
+    source: Arc<Source<Pipe<process::ChildStdin>>>,
+    ready: ReadinessStream,
+}
+
+/// The standard output of a child process, which implements `ReadTask`.
+pub struct ChildStdout {
+    source: Arc<Source<Pipe<process::ChildStdout>>>,
+    ready: ReadinessStream,
+}
+
+/// The standard error of a child process, which implements `ReadTask`.
+pub struct ChildStderr {
+    source: Arc<Source<Pipe<process::ChildStderr>>>,
+    ready: ReadinessStream,
+}
+
+// One of the standard I/O pipes of a child, set to nonblocking mode.
+struct Pipe<T> {
+    io: T,
+}
+
+type Registered<T> = (Arc<Source<Pipe<T>>>, ReadinessStream);
+
+impl CommandExt for Command {
+    fn spawn_async(&mut self, handle: LoopHandle) -> IoFuture<Child> {
+        // Start listening for SIGCHLD before the child is spawned so its exit
+        // can't go unnoticed.
+        let sigchld = handle.clone().signal(libc::SIGCHLD);
+        let mut child = match self.spawn() {
+            Ok(child) => child,
+            Err(e) => return failed(e).boxed(),
+        };
+        let stdin = register(child.stdin.take(), &handle);
+        let stdout = register(child.stdout.take(), &handle);
+        let stderr = register(child.stderr.take(), &handle);
+
+        sigchld.join(stdin).join(stdout).join(stderr).map(move |parts| {
+            let (((sigchld, stdin), stdout), stderr) = parts;
+            Child {
+                child: child,
+                sigchld: sigchld,
+                stdin: stdin.map(|(source, ready)| {
+                    ChildStdin { source: source, ready: ready }
+                }),
+                stdout: stdout.map(|(source, ready)| {
+                    ChildStdout { source: source, ready: ready }
+                }),
+                stderr: stderr.map(|(source, ready)| {
+                    ChildStderr { source: source, ready: ready }
+                }),
+            }
+        }).boxed()
+    }
+}
+
+// Registers one of the standard I/O pipes of a child with the event loop, if
+// it was piped.
+fn register<T>(io: Option<T>, handle: &LoopHandle)
+               -> IoFuture<Option<Registered<T>>>
+    where T: AsRawFd + Send + Sync + 'static,
+{
+    let io = match io {
+        Some(io) => io,
+        None => return finished(None).boxed(),
+    };
+    let fd = io.as_raw_fd();
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags < 0 ||
+           libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) < 0 {
+            return failed(io::Error::last_os_error()).boxed()
+        }
+    }
+    let source = Arc::new(Source::new(Pipe { io: io }));
+    ReadinessStream::new(handle.clone(), source.clone()).map(|ready| {
+        Some((source, ready))
+    }).boxed()
+}
+
+impl Child {
+    /// Returns the OS-assigned process identifier of the child.
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Forces the child to exit.
+    ///
+    /// This is equivalent to sending a `SIGKILL` to the child. The child still
+    /// needs to be waited on by polling it afterwards.
+    pub fn kill(&mut self) -> io::Result<()> {
+        self.child.kill()
+    }
+
+    /// Returns the handle for writing to the child's standard input, if it
+    /// was piped and hasn't been taken.
+    pub fn stdin(&mut self) -> &mut Option<ChildStdin> {
+        &mut self.stdin
+    }
+
+    /// Returns the handle for reading from the child's standard output, if it
+    /// was piped and hasn't been taken.
+    pub fn stdout(&mut self) -> &mut Option<ChildStdout> {
+        &mut self.stdout
+    }
+
+    /// Returns the handle for reading from the child's standard error, if it
+    /// was piped and hasn't been taken.
+    pub fn stderr(&mut self) -> &mut Option<ChildStderr> {
+        &mut self.stderr
+    }
+}
+
+impl Future for Child {
+    type Item = ExitStatus;
+    type Error = io::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<ExitStatus, io::Error> {
+        // A SIGCHLD may be for any child of this process, so it only tells us
+        // that it's worth checking on this child again. Consume it first so
+        // that one arriving after the check below wakes us up again.
+        if let Poll::Err(e) = self.sigchld.poll(task) {
+            return Poll::Err(e)
+        }
+        match self.child.try_wait() {
+            Ok(Some(status)) => Poll::Ok(status),
+            Ok(None) => Poll::NotReady,
+            Err(e) => Poll::Err(e),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.sigchld.schedule(task)
+    }
+}
+
+impl<T: AsRawFd> Pipe<T> {
+    fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        let fd = self.io.as_raw_fd();
+        let n = unsafe {
+            libc::read(fd, buf.as_mut_ptr() as *mut _, buf.len())
+        };
+        if n < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(n as usize)
+        }
+    }
+
+    fn write(&self, buf: &[u8]) -> io::Result<usize> {
+        let fd = self.io.as_raw_fd();
+        let n = unsafe {
+            libc::write(fd, buf.as_ptr() as *const _, buf.len())
+        };
+        if n < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(n as usize)
+        }
+    }
+}
+
+impl<T: AsRawFd> mio::Evented for Pipe<T> {
+    fn register(&self,
+                poll: &mio::Poll,
+                token: mio::Token,
+                interest: mio::EventSet,
+                opts: mio::PollOpt) -> io::Result<()> {
+        EventedFd(&self.io.as_raw_fd()).register(poll, token, interest, opts)
+    }
+
+    fn reregister(&self,
+                  poll: &mio::Poll,
+                  token: mio::Token,
+                  interest: mio::EventSet,
+                  opts: mio::PollOpt) -> io::Result<()> {
+        EventedFd(&self.io.as_raw_fd()).reregister(poll, token, interest, opts)
+    }
+
+    fn deregister(&self, poll: &mio::Poll) -> io::Result<()> {
+        EventedFd(&self.io.as_raw_fd()).deregister(poll)
+    }
+}
+
+impl Write for ChildStdin {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.source.io().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Read for ChildStdout {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.source.io().read(buf)
+    }
+}
+
+impl Read for ChildStderr {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.source.io().read(buf)
+    }
+}
+
+impl Stream for ChildStdin {
+    type Item = Ready;
+    type Error = io::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<Ready>, io::Error> {
+        self.ready.poll(task)
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.ready.schedule(task)
+    }
+}
+
+impl Stream for ChildStdout {
+    type Item = Ready;
+    type Error = io::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<Ready>, io::Error> {
+        self.ready.poll(task)
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.ready.schedule(task)
+    }
+}
+
+impl Stream for ChildStderr {
+    type Item = Ready;
+    type Error = io::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<Ready>, io::Error> {
+        self.ready.poll(task)
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.ready.schedule(task)
+    }
+}
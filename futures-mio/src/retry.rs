@@ -0,0 +1,196 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::time::Instant;
+
+use futures::{Future, IntoFuture, Task, Poll};
+
+use LoopHandle;
+use backoff::Backoff;
+use timeout::Delay;
+
+/// A future which retries an operation until it succeeds or its backoff
+/// strategy gives up.
+///
+/// Each attempt is a fresh future produced by a closure, and the waits
+/// between attempts are driven by the event loop's timers. If the operation
+/// never succeeds, this resolves to the error of the last attempt, wrapped
+/// in `RetryError::Inner`.
+///
+/// This is created by the `LoopHandle::retry` and `LoopHandle::retry_if`
+/// methods.
+pub struct Retry<B, F, R, P> where R: IntoFuture {
+    handle: LoopHandle,
+    backoff: B,
+    action: F,
+    retry_if: P,
+    attempts: u32,
+    start: Option<Instant>,
+    state: State<R::Future>,
+}
+
+/// The error produced by `Retry`.
+#[derive(Debug)]
+pub enum RetryError<E> {
+    /// The last attempt of the operation failed with this error.
+    Inner(E),
+
+    /// The timeout for waiting between attempts couldn't be registered with
+    /// the event loop.
+    Timer(io::Error),
+}
+
+enum State<F> {
+    Start,
+    Running(F),
+    Sleeping(Delay),
+}
+
+impl LoopHandle {
+    /// Runs the future returned by `action`, calling `action` again for a new
+    /// future each time the previous one fails, according to `backoff`.
+    ///
+    /// Every error is retried, see `retry_if` to only retry some of them.
+    pub fn retry<B, F, R>(self, backoff: B, action: F)
+                          -> Retry<B, F, R, fn(&R::Error) -> bool>
+        where B: Backoff,
+              F: FnMut() -> R,
+              R: IntoFuture,
+    {
+        self.retry_if(backoff, action, always::<R::Error>)
+    }
+
+    /// Like `retry`, except that an error is only retried if `retry_if`
+    /// returns `true` for it.
+    ///
+    /// If `retry_if` returns `false` the returned future resolves to that
+    /// error right away.
+    pub fn retry_if<B, F, R, P>(self, backoff: B, action: F, retry_if: P)
+                                -> Retry<B, F, R, P>
+        where B: Backoff,
+              F: FnMut() -> R,
+              R: IntoFuture,
+              P: FnMut(&R::Error) -> bool,
+    {
+        Retry {
+            handle: self,
+            backoff: backoff,
+            action: action,
+            retry_if: retry_if,
+            attempts: 0,
+            start: None,
+            state: State::Start,
+        }
+    }
+}
+
+fn always<E>(_err: &E) -> bool {
+    true
+}
+
+impl<B, F, R, P> Future for Retry<B, F, R, P>
+    where B: Backoff + 'static,
+          F: FnMut() -> R + 'static,
+          R: IntoFuture + 'static,
+          P: FnMut(&R::Error) -> bool + 'static,
+{
+    type Item = R::Item;
+    type Error = RetryError<R::Error>;
+
+    fn poll(&mut self, task: &mut Task)
+            -> Poll<R::Item, RetryError<R::Error>> {
+        loop {
+            let next = match self.state {
+                State::Start => {
+                    if self.start.is_none() {
+                        self.start = Some(self.handle.now());
+                    }
+                    self.attempts += 1;
+                    State::Running((self.action)().into_future())
+                }
+                State::Running(ref mut future) => {
+                    let err = match future.poll(task) {
+                        Poll::Ok(item) => return Poll::Ok(item),
+                        Poll::Err(e) => e,
+                        Poll::NotReady => return Poll::NotReady,
+                    };
+                    if !(self.retry_if)(&err) {
+                        return Poll::Err(RetryError::Inner(err))
+                    }
+                    let elapsed = self.handle.now() - self.start.unwrap();
+                    match self.backoff.next_delay(self.attempts, elapsed) {
+                        Some(delay) => {
+                            let at = self.handle.now() + delay;
+                            State::Sleeping(Delay::new(self.handle.clone(), at))
+                        }
+                        None => return Poll::Err(RetryError::Inner(err)),
+                    }
+                }
+                State::Sleeping(ref mut delay) => {
+                    match delay.poll(task) {
+                        Poll::Ok(()) => State::Start,
+                        Poll::Err(e) => {
+                            return Poll::Err(RetryError::Timer(e))
+                        }
+                        Poll::NotReady => return Poll::NotReady,
+                    }
+                }
+            };
+            self.state = next;
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match self.state {
+            State::Start => task.notify(),
+            State::Running(ref mut future) => future.schedule(task),
+            State::Sleeping(ref mut delay) => delay.schedule(task),
+        }
+    }
+}
+
+impl<E> RetryError<E> {
+    /// Returns the error of the last attempt, if that's what this error is.
+    pub fn into_inner(self) -> Option<E> {
+        match self {
+            RetryError::Inner(e) => Some(e),
+            RetryError::Timer(_) => None,
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for RetryError<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RetryError::Inner(ref e) => e.fmt(fmt),
+            RetryError::Timer(ref e) => {
+                write!(fmt, "failed to register timeout: {}", e)
+            }
+        }
+    }
+}
+
+impl<E: Error> Error for RetryError<E> {
+    fn description(&self) -> &str {
+        match *self {
+            RetryError::Inner(ref e) => e.description(),
+            RetryError::Timer(ref e) => e.description(),
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            RetryError::Inner(ref e) => Some(e),
+            RetryError::Timer(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<RetryError<io::Error>> for io::Error {
+    fn from(err: RetryError<io::Error>) -> io::Error {
+        match err {
+            RetryError::Inner(e) => e,
+            RetryError::Timer(e) => e,
+        }
+    }
+}
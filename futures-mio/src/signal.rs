@@ -0,0 +1,405 @@
+use std::io;
+use std::mem;
+use std::os::unix::prelude::*;
+use std::sync::{Arc, Mutex, Once, ONCE_INIT};
+use std::sync::atomic::{AtomicBool, AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+use std::thread;
+
+use futures::stream::Stream;
+use futures::{Future, failed, Task, Poll};
+use futures_io::IoFuture;
+use libc::{self, c_int};
+use mio;
+use mio::unix::EventedFd;
+
+use {ReadinessStream, LoopHandle};
+use event_loop::Source;
+
+/// A stream of notifications that a Unix signal has been delivered to this
+/// process.
+///
+/// Created through the `LoopHandle::signal` method, this stream yields the
+/// signal number each time the signal arrives. Like signals themselves,
+/// deliveries which happen before the stream is polled again are coalesced
+/// into one item. The stream never ends.
+///
+/// Any number of `Signal` streams can listen for the same signal, on the same
+/// or on different event loops, and each of them sees every delivery.
+///
+/// Note that the first `Signal` created for a signal number installs a handler
+/// for it which stays in place for the rest of the process, so the default
+/// action for that signal, such as terminating the process on `SIGINT`, won't
+/// take place anymore.
+pub struct Signal {
+    signum: c_int,
+    pipe: Arc<Source<Pipe>>,
+    ready: ReadinessStream,
+}
+
+// The pipe that deliveries of a signal are forwarded to for one `Signal`.
+//
+// The write half is owned by the listener registered for this pipe in the
+// global `Listeners`, and the read half is registered with an event loop.
+struct Pipe {
+    id: usize,
+    read: RawFd,
+    write: RawFd,
+}
+
+// The state shared between the signal handler, the dispatch thread, and the
+// `Signal` streams.
+struct Globals {
+    // A flag for each signal number, set by the handler when that signal is
+    // delivered and cleared by the dispatch thread once it's forwarded.
+    pending: Vec<AtomicBool>,
+    listeners: Mutex<Listeners>,
+}
+
+// All of the pipes deliveries are forwarded to, along with which signals have
+// had a handler installed.
+struct Listeners {
+    started: bool,
+    installed: Vec<c_int>,
+    next_id: usize,
+    pipes: Vec<(usize, c_int, RawFd)>,
+}
+
+static INIT: Once = ONCE_INIT;
+static mut GLOBALS: *const Globals = 0 as *const _;
+
+// The write half of the pipe that the signal handler writes to in order to
+// wake up the dispatch thread.
+static HANDLER_PIPE: AtomicUsize = ATOMIC_USIZE_INIT;
+
+// Signal numbers are checked against this so they can index `pending`.
+const MAX_SIGNUM: c_int = 255;
+
+impl LoopHandle {
+    /// Creates a new stream of notifications that the signal `signum` has
+    /// been delivered to this process.
+    ///
+    /// A handler for `signum` is installed before this method returns, so the
+    /// signal can be raised as soon as this returns without terminating the
+    /// process. The returned future then resolves to the stream once it has
+    /// been registered with the event loop.
+    ///
+    /// The returned future will resolve to an error if a handler can't be
+    /// installed for `signum`, such as for `SIGKILL` or `SIGSTOP`.
+    pub fn signal(self, signum: c_int) -> IoFuture<Signal> {
+        let pipe = match Pipe::new(signum) {
+            Ok(pipe) => Arc::new(Source::new(pipe)),
+            Err(e) => return failed(e).boxed(),
+        };
+        ReadinessStream::new(self, pipe.clone()).map(move |ready| {
+            Signal {
+                signum: signum,
+                pipe: pipe,
+                ready: ready,
+            }
+        }).boxed()
+    }
+}
+
+impl Signal {
+    /// Returns the number of the signal that this stream listens for.
+    pub fn signum(&self) -> c_int {
+        self.signum
+    }
+}
+
+impl Stream for Signal {
+    type Item = c_int;
+    type Error = io::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<c_int>, io::Error> {
+        match self.ready.poll(task) {
+            Poll::Ok(Some(_)) => {}
+            Poll::Ok(None) => return Poll::Ok(None),
+            Poll::Err(e) => return Poll::Err(e),
+            Poll::NotReady => return Poll::NotReady,
+        }
+
+        // Each delivery is a byte in the pipe, so drain it to coalesce all of
+        // them into one item.
+        let mut delivered = false;
+        let mut buf = [0; 64];
+        loop {
+            match self.pipe.io().read(&mut buf) {
+                Ok(0) => break,
+                Ok(_) => delivered = true,
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => return Poll::Err(e),
+            }
+        }
+        if delivered {
+            Poll::Ok(Some(self.signum))
+        } else {
+            Poll::NotReady
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.ready.schedule(task)
+    }
+}
+
+impl Pipe {
+    fn new(signum: c_int) -> io::Result<Pipe> {
+        if signum <= 0 || signum > MAX_SIGNUM {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "invalid signal number"))
+        }
+
+        let (read, write) = try!(pipe(true));
+        let mut listeners = globals().listeners.lock().unwrap();
+        if let Err(e) = listeners.install(signum) {
+            unsafe {
+                libc::close(read);
+                libc::close(write);
+            }
+            return Err(e)
+        }
+        let id = listeners.next_id;
+        listeners.next_id += 1;
+        listeners.pipes.push((id, signum, write));
+        Ok(Pipe {
+            id: id,
+            read: read,
+            write: write,
+        })
+    }
+
+    fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = unsafe {
+            libc::read(self.read, buf.as_mut_ptr() as *mut _, buf.len())
+        };
+        if n < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(n as usize)
+        }
+    }
+}
+
+impl mio::Evented for Pipe {
+    fn register(&self,
+                poll: &mio::Poll,
+                token: mio::Token,
+                interest: mio::EventSet,
+                opts: mio::PollOpt) -> io::Result<()> {
+        EventedFd(&self.read).register(poll, token, interest, opts)
+    }
+
+    fn reregister(&self,
+                  poll: &mio::Poll,
+                  token: mio::Token,
+                  interest: mio::EventSet,
+                  opts: mio::PollOpt) -> io::Result<()> {
+        EventedFd(&self.read).reregister(poll, token, interest, opts)
+    }
+
+    fn deregister(&self, poll: &mio::Poll) -> io::Result<()> {
+        EventedFd(&self.read).deregister(poll)
+    }
+}
+
+impl Drop for Pipe {
+    fn drop(&mut self) {
+        // Make sure the dispatch thread is done with our pipe before closing
+        // it, as it only writes to pipes while holding the lock.
+        globals().listeners.lock().unwrap().pipes.retain(|p| p.0 != self.id);
+        unsafe {
+            libc::close(self.read);
+            libc::close(self.write);
+        }
+    }
+}
+
+impl Listeners {
+    fn install(&mut self, signum: c_int) -> io::Result<()> {
+        if !self.started {
+            let (read, write) = try!(pipe(false));
+            HANDLER_PIPE.store(write as usize, Ordering::SeqCst);
+            try!(thread::Builder::new()
+                                 .name("futures-mio-signal".to_string())
+                                 .spawn(move || dispatch(read)));
+            self.started = true;
+        }
+        if self.installed.contains(&signum) {
+            return Ok(())
+        }
+
+        unsafe {
+            let mut action: libc::sigaction = mem::zeroed();
+            action.sa_sigaction = handler as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigemptyset(&mut action.sa_mask);
+            if libc::sigaction(signum, &action, 0 as *mut _) != 0 {
+                return Err(io::Error::last_os_error())
+            }
+        }
+        self.installed.push(signum);
+        Ok(())
+    }
+}
+
+fn globals() -> &'static Globals {
+    INIT.call_once(|| unsafe {
+        let globals = Globals {
+            pending: (0..MAX_SIGNUM + 1).map(|_| AtomicBool::new(false))
+                                        .collect(),
+            listeners: Mutex::new(Listeners {
+                started: false,
+                installed: Vec::new(),
+                next_id: 0,
+                pipes: Vec::new(),
+            }),
+        };
+        GLOBALS = Box::into_raw(Box::new(globals));
+    });
+    unsafe { &*GLOBALS }
+}
+
+// Creates a pipe whose write half, and optionally read half, is nonblocking.
+fn pipe(nonblocking_read: bool) -> io::Result<(RawFd, RawFd)> {
+    let mut fds = [0; 2];
+    unsafe {
+        if libc::pipe(fds.as_mut_ptr()) != 0 {
+            return Err(io::Error::last_os_error())
+        }
+        for &fd in fds.iter() {
+            libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+        }
+        libc::fcntl(fds[1], libc::F_SETFL, libc::O_NONBLOCK);
+        if nonblocking_read {
+            libc::fcntl(fds[0], libc::F_SETFL, libc::O_NONBLOCK);
+        }
+    }
+    Ok((fds[0], fds[1]))
+}
+
+// The actual signal handler, which can only do a few things safely. Setting an
+// atomic flag and writing to a pipe are among them, so the delivery is marked
+// as pending and the dispatch thread is woken up to forward it. If the pipe is
+// full the wakeup byte is dropped, which is fine as the dispatch thread then
+// has a wakeup waiting already, and it checks every flag each time it wakes
+// up.
+//
+// The handler is only installed once `GLOBALS` is set, so it's safe to read.
+extern "C" fn handler(signum: c_int) {
+    unsafe {
+        // Whatever was interrupted may be about to look at `errno`, so make
+        // sure the write below doesn't change it.
+        let errno = *errno_location();
+        (*GLOBALS).pending[signum as usize].store(true, Ordering::SeqCst);
+        let fd = HANDLER_PIPE.load(Ordering::SeqCst) as RawFd;
+        let byte = 0u8;
+        libc::write(fd, &byte as *const u8 as *const _, 1);
+        *errno_location() = errno;
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "emscripten"))]
+unsafe fn errno_location() -> *mut c_int {
+    libc::__errno_location()
+}
+
+#[cfg(any(target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly"))]
+unsafe fn errno_location() -> *mut c_int {
+    libc::__error()
+}
+
+#[cfg(any(target_os = "android",
+          target_os = "openbsd",
+          target_os = "netbsd"))]
+unsafe fn errno_location() -> *mut c_int {
+    libc::__errno()
+}
+
+#[cfg(target_os = "solaris")]
+unsafe fn errno_location() -> *mut c_int {
+    libc::___errno()
+}
+
+// Forwards each pending signal to all pipes listening for it whenever the
+// handler wakes this thread up.
+fn dispatch(read: RawFd) {
+    let globals = globals();
+    let mut buf = [0u8; 64];
+    loop {
+        let n = unsafe {
+            libc::read(read, buf.as_mut_ptr() as *mut _, buf.len())
+        };
+        if n < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue
+            }
+            panic!("failed to read signals: {}", err);
+        }
+
+        let listeners = globals.listeners.lock().unwrap();
+        for (signum, pending) in globals.pending.iter().enumerate() {
+            if !pending.swap(false, Ordering::SeqCst) {
+                continue
+            }
+            debug!("dispatching signal {}", signum);
+            let byte = signum as u8;
+            for &(_, s, write) in listeners.pipes.iter() {
+                if s as usize != signum {
+                    continue
+                }
+                // A full pipe already has a delivery waiting to be seen, so
+                // there's no need to write any more.
+                unsafe {
+                    libc::write(write, &byte as *const u8 as *const _, 1);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate env_logger;
+
+    use std::time::Duration;
+
+    use futures::Future;
+    use futures::stream::Stream;
+    use libc;
+
+    use Loop;
+    use super::globals;
+
+    // Raises `signum` on this thread, so the handler has run by the time this
+    // returns.
+    fn raise(signum: libc::c_int) {
+        assert_eq!(unsafe { libc::raise(signum) }, 0);
+    }
+
+    #[test]
+    fn full_handler_pipe() {
+        drop(env_logger::init());
+        let mut l = Loop::new().unwrap();
+        let flood = l.handle().signal(libc::SIGWINCH);
+        let signal = l.handle().signal(libc::SIGALRM);
+        let (_flood, signal) = l.run(flood.join(signal)).unwrap();
+
+        // Stall the dispatch thread so that a flood of one signal fills up
+        // the handler's pipe, which mustn't lose the delivery of another.
+        {
+            let _listeners = globals().listeners.lock().unwrap();
+            for _ in 0..100_000 {
+                raise(libc::SIGWINCH);
+            }
+            raise(libc::SIGALRM);
+        }
+        let signals = signal.take(1).collect();
+        let signals = l.handle().deadline(signals, Duration::from_secs(5));
+        assert_eq!(l.run(signals).unwrap(), [libc::SIGALRM]);
+    }
+}
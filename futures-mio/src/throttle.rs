@@ -0,0 +1,75 @@
+use std::io;
+use std::time::Duration;
+
+use futures::{Task, Poll};
+use futures::stream::Stream;
+
+use LoopHandle;
+use timeout::Delay;
+
+/// A stream combinator which limits the rate at which items are yielded.
+///
+/// This is created by the `LoopHandle::throttle` method.
+pub struct Throttle<S> {
+    stream: S,
+    handle: LoopHandle,
+    dur: Duration,
+    // Present while the stream is held back after yielding an item.
+    delay: Option<Delay>,
+}
+
+impl LoopHandle {
+    /// Limits `stream` to yielding at most one item every `dur`.
+    ///
+    /// After an item is yielded, the underlying stream isn't polled again
+    /// until `dur` has elapsed, so items are delayed rather than dropped.
+    /// Errors are held back in the same way, as they aren't seen until the
+    /// underlying stream is polled again, but they don't start a new delay of
+    /// their own.
+    pub fn throttle<S>(self, stream: S, dur: Duration) -> Throttle<S>
+        where S: Stream,
+              S::Error: From<io::Error>,
+    {
+        Throttle {
+            stream: stream,
+            handle: self,
+            dur: dur,
+            delay: None,
+        }
+    }
+}
+
+impl<S> Stream for Throttle<S>
+    where S: Stream,
+          S::Error: From<io::Error>,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<S::Item>, S::Error> {
+        if let Some(ref mut delay) = self.delay {
+            match delay.poll(task) {
+                Poll::Ok(()) => {}
+                Poll::Err(e) => return Poll::Err(e.into()),
+                Poll::NotReady => return Poll::NotReady,
+            }
+        }
+        self.delay = None;
+
+        match self.stream.poll(task) {
+            Poll::Ok(Some(item)) => {
+                let at = self.handle.now() + self.dur;
+                self.delay = Some(Delay::new(self.handle.clone(), at));
+                Poll::Ok(Some(item))
+            }
+            other => other,
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match self.delay {
+            Some(ref mut delay) => delay.schedule(task),
+            None => self.stream.schedule(task),
+        }
+    }
+}
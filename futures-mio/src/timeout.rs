@@ -27,7 +27,8 @@ impl LoopHandle {
     /// timeout object. The timeout object itself is then a future which will be
     /// set to fire at the specified point in the future.
     pub fn timeout(self, dur: Duration) -> IoFuture<Timeout> {
-        self.timeout_at(Instant::now() + dur)
+        let now = self.now();
+        self.timeout_at(now + dur)
     }
 
     /// Creates a new timeout which will fire at the time specified by `at`.
@@ -52,7 +53,7 @@ impl Future for Timeout {
 
     fn poll(&mut self, _task: &mut Task) -> Poll<(), io::Error> {
         // TODO: is this fast enough?
-        if self.at <= Instant::now() {
+        if self.at <= self.handle.now() {
             Poll::Ok(())
         } else {
             Poll::NotReady
@@ -69,3 +70,89 @@ impl Drop for Timeout {
         self.handle.cancel_timeout(&self.token);
     }
 }
+
+/// An internal, resettable timer used to build the time-based streams in this
+/// crate.
+///
+/// A `Timeout` is only registered with the event loop once the delay is first
+/// polled, and resetting the delay cancels the registered timeout so a new
+/// one can be created for the new instant.
+pub struct Delay {
+    handle: LoopHandle,
+    at: Instant,
+    state: Option<DelayState>,
+}
+
+enum DelayState {
+    Adding(IoFuture<Timeout>),
+    Waiting(Timeout),
+}
+
+impl Delay {
+    /// Creates a new delay which will fire at `at`.
+    pub fn new(handle: LoopHandle, at: Instant) -> Delay {
+        Delay {
+            handle: handle,
+            at: at,
+            state: None,
+        }
+    }
+
+    /// Returns the handle of the event loop this delay runs on.
+    pub fn handle(&self) -> &LoopHandle {
+        &self.handle
+    }
+
+    /// Returns the instant at which this delay fires.
+    pub fn at(&self) -> Instant {
+        self.at
+    }
+
+    /// Changes this delay to fire at `at` instead.
+    pub fn reset(&mut self, at: Instant) {
+        self.at = at;
+        self.state = None;
+    }
+
+    /// Cancels the timeout registered with the event loop, if any.
+    ///
+    /// Polling the delay again registers a new timeout.
+    pub fn cancel(&mut self) {
+        self.state = None;
+    }
+
+    /// Returns whether this delay has fired, registering a timeout with the
+    /// event loop if one is needed.
+    pub fn poll(&mut self, task: &mut Task) -> Poll<(), io::Error> {
+        if self.at <= self.handle.now() {
+            self.state = None;
+            return Poll::Ok(())
+        }
+
+        if self.state.is_none() {
+            let add = self.handle.clone().timeout_at(self.at);
+            self.state = Some(DelayState::Adding(add));
+        }
+        let timeout = match self.state {
+            Some(DelayState::Adding(ref mut add)) => {
+                match add.poll(task) {
+                    Poll::Ok(timeout) => timeout,
+                    Poll::Err(e) => return Poll::Err(e),
+                    Poll::NotReady => return Poll::NotReady,
+                }
+            }
+            _ => return Poll::NotReady,
+        };
+        self.state = Some(DelayState::Waiting(timeout));
+        Poll::NotReady
+    }
+
+    /// Arranges for `task` to be notified once this delay fires.
+    pub fn schedule(&mut self, task: &mut Task) {
+        match self.state {
+            Some(DelayState::Adding(ref mut add)) => add.schedule(task),
+            Some(DelayState::Waiting(ref mut timeout)) => timeout.schedule(task),
+            None => task.notify(),
+        }
+    }
+}
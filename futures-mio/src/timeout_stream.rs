@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use futures::{Task, Poll};
+use futures::stream::Stream;
+
+use LoopHandle;
+use deadline::DeadlineError;
+use timeout::Delay;
+
+/// A stream combinator which yields an error whenever an item takes too long
+/// to arrive.
+///
+/// This is created by the `LoopHandle::timeout_stream` method.
+pub struct TimeoutStream<S> {
+    stream: S,
+    handle: LoopHandle,
+    dur: Duration,
+    // Armed once we start waiting for the next item.
+    delay: Option<Delay>,
+}
+
+impl LoopHandle {
+    /// Limits the time `stream` may take to produce each of its items to
+    /// `dur`, which is useful for timing out idle connections.
+    ///
+    /// The timer starts when the stream is first found not to be ready, and
+    /// is restarted after each item or error. If it fires before the next
+    /// item arrives, `DeadlineError::TimedOut` is yielded. Like other errors
+    /// this doesn't end the stream, and the stream is then given another `dur`
+    /// to produce an item.
+    pub fn timeout_stream<S: Stream>(self, stream: S, dur: Duration)
+                                     -> TimeoutStream<S> {
+        TimeoutStream {
+            stream: stream,
+            handle: self,
+            dur: dur,
+            delay: None,
+        }
+    }
+}
+
+impl<S> TimeoutStream<S> {
+    /// Consumes this combinator, returning the underlying stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Stream> Stream for TimeoutStream<S> {
+    type Item = S::Item;
+    type Error = DeadlineError<S::Error>;
+
+    fn poll(&mut self, task: &mut Task)
+            -> Poll<Option<S::Item>, DeadlineError<S::Error>> {
+        match self.stream.poll(task) {
+            Poll::Ok(item) => {
+                self.delay = None;
+                return Poll::Ok(item)
+            }
+            Poll::Err(e) => {
+                self.delay = None;
+                return Poll::Err(DeadlineError::Inner(e))
+            }
+            Poll::NotReady => {}
+        }
+
+        if self.delay.is_none() {
+            let at = self.handle.now() + self.dur;
+            self.delay = Some(Delay::new(self.handle.clone(), at));
+        }
+        let res = self.delay.as_mut().unwrap().poll(task);
+        match res {
+            Poll::Ok(()) => {
+                self.delay = None;
+                Poll::Err(DeadlineError::TimedOut)
+            }
+            Poll::Err(e) => Poll::Err(DeadlineError::Timer(e)),
+            Poll::NotReady => Poll::NotReady,
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.stream.schedule(task);
+        if let Some(ref mut delay) = self.delay {
+            delay.schedule(task);
+        }
+    }
+}
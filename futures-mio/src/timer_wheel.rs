@@ -1,36 +1,44 @@
 //! A timer wheel implementation
 
-use std::cmp;
+use std::cmp::{self, Ordering};
+use std::collections::BinaryHeap;
 use std::mem;
 use std::time::{Instant, Duration};
 
 use slab::Slab;
 
-/// An implementation of a timer wheel where data can be associated with each
-/// timer firing.
+/// An implementation of a hierarchical timer wheel where data can be
+/// associated with each timer firing.
 ///
 /// This structure implements a timer wheel data structure where each timeout
 /// has a piece of associated data, `T`. A timer wheel supports O(1) insertion
 /// and removal of timers, as well as quickly figuring out what needs to get
 /// fired.
 ///
-/// Note, though, that the resolution of a timer wheel means that timeouts will
-/// not arrive promptly when they expire, but rather in certain increments of
-/// each time. The time delta between each slot of a time wheel is of a fixed
-/// length, meaning that if a timeout is scheduled between two slots it'll end
-/// up getting scheduled into the later slot.
+/// The wheel is made up of several levels, each of which is a ring of slots.
+/// Each slot of the lowest level covers a single tick of time, and each slot
+/// of the levels above covers as much time as the entire level below it. A
+/// timeout is stored in the lowest level whose slots are fine-grained enough
+/// to tell it apart from the current time, so short timeouts live in the
+/// lowest level while timeouts hours away live a few levels up. As time moves
+/// forward and a slot of a higher level comes up, its timeouts are moved down
+/// into the lower levels, and eventually fire from the lowest one. Each
+/// timeout moves down at most once per level, so inserting, canceling, and
+/// firing timeouts is O(1) amortized no matter how far in the future they
+/// are.
+///
+/// Each timeout is rounded to the nearest tick to find its place in the wheel,
+/// but it is never handed out before the instant it was scheduled for. Once
+/// the tick of a timeout has come it waits in a heap ordered by instant until
+/// that instant has passed, so `next_timeout` can report exactly when the
+/// earliest of them is due. Timeouts in that heap cost O(log n) to insert,
+/// cancel, or fire, where n is the number of timeouts waiting in it.
 pub struct TimerWheel<T> {
-    // Actual timer wheel itself.
-    //
-    // Each slot represents a fixed duration of time, and this wheel also
-    // behaves like a ring buffer. All timeouts scheduled will correspond to one
-    // slot and therefore each slot has a linked list of timeouts scheduled in
-    // it. Right now linked lists are done through indices into the `slab`
-    // below.
+    // The levels of the wheel, lowest first.
     //
-    // Each slot also contains the next timeout associated with it (the minimum
-    // of the entire linked list).
-    wheel: Vec<Slot>,
+    // Each slot has a linked list of the timeouts scheduled in it. Right now
+    // linked lists are done through indices into the `slab` below.
+    levels: Vec<Level>,
 
     // A slab containing all the timeout entries themselves. This is the memory
     // backing the "linked lists" in the wheel above. Each entry has a prev/next
@@ -42,31 +50,65 @@ pub struct TimerWheel<T> {
     // computations are relative to.
     start: Instant,
 
-    // State used during `poll`. The `cur_wheel_tick` field is the current tick
-    // we've poll'd to. That is, all events from `cur_wheel_tick` to the
-    // actual current tick in time still need to be processed.
-    //
-    // The `cur_slab_idx` variable is basically just an iterator over the linked
-    // list associated with a wheel slot. This will get incremented as we move
-    // forward in `poll`
-    cur_wheel_tick: u64,
-    cur_slab_idx: usize,
+    // The last tick which has been processed. Everything scheduled for this
+    // tick or earlier has been moved to the `pending` heap.
+    elapsed: u64,
+
+    // Timeouts whose tick has been processed but which haven't been returned
+    // from `poll` yet, either because their instant hasn't passed or because
+    // nobody has asked for them. Removing a timeout leaves its item here, but
+    // stale items are popped as soon as they come to the top.
+    pending: BinaryHeap<Pending>,
+
+    // The sequence number given to the next timeout pushed onto `pending`,
+    // which tells its item apart from stale ones for the same slab entry.
+    next_seq: u64,
+}
+
+struct Level {
+    slots: Vec<Slot>,
+    // A bit for each slot, set if the slot has any timeouts in it.
+    occupied: u64,
 }
 
 #[derive(Clone)]
 struct Slot {
-    head: usize,
+    list: List,
+    // The earliest timeout in this slot. This may be stale if that timeout
+    // has since been canceled.
     next_timeout: Option<Instant>,
 }
 
+#[derive(Clone, Copy)]
+struct List {
+    head: usize,
+}
+
 struct Entry<T> {
     data: T,
     when: Instant,
-    wheel_idx: usize,
+    tick: u64,
+    location: Location,
     prev: usize,
     next: usize,
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum Location {
+    Wheel(usize, usize),
+    Pending(u64),
+}
+
+// An item of the `pending` heap, ordered so that the earliest timeout is at
+// the top, and timeouts with the same instant come out in the order they were
+// pushed.
+#[derive(PartialEq, Eq)]
+struct Pending {
+    when: Instant,
+    seq: u64,
+    slab_idx: usize,
+}
+
 /// A timeout which has been scheduled with a timer wheel.
 ///
 /// This can be used to later cancel a timeout, if necessary.
@@ -76,23 +118,42 @@ pub struct Timeout {
 }
 
 const EMPTY: usize = 0;
-const LEN: usize = 256;
-const MASK: usize = LEN - 1;
+const LEVELS: usize = 6;
+const LEVEL_BITS: usize = 6;
+const LEN: usize = 1 << LEVEL_BITS;
+const MASK: u64 = (LEN as u64) - 1;
+// The wheel can tell apart this many ticks, anything further in the future is
+// parked in the furthest slot of the top level until it comes closer.
+const MAX_TICKS: u64 = 1 << (LEVELS * LEVEL_BITS);
+// The top level wraps around, so the shift for its slots is needed to tell
+// which lap of it a timeout is in.
+const TOP_SHIFT: usize = (LEVELS - 1) * LEVEL_BITS;
 const TICK_MS: u64 = 100;
 
 impl<T> TimerWheel<T> {
     /// Creates a new timer wheel configured with no timeouts and with the
     /// default parameters.
     ///
-    /// Currently this is a timer wheel of length 256 with a 100ms time
-    /// resolution.
+    /// Currently this is a timer wheel of 6 levels of 64 slots each with a
+    /// 100ms time resolution, which can tell apart timeouts over 200 years
+    /// into the future.
     pub fn new() -> TimerWheel<T> {
+        TimerWheel::starting_at(Instant::now())
+    }
+
+    /// Creates a new timer wheel like `new`, except that `start` is taken as
+    /// the instant at which the wheel was created.
+    pub fn starting_at(start: Instant) -> TimerWheel<T> {
+        let empty = Slot { list: List::new(), next_timeout: None };
         TimerWheel {
-            wheel: vec![Slot { head: EMPTY, next_timeout: None }; LEN],
+            levels: (0..LEVELS).map(|_| {
+                Level { slots: vec![empty.clone(); LEN], occupied: 0 }
+            }).collect(),
             slab: Slab::new_starting_at(1, 256),
-            start: Instant::now(),
-            cur_wheel_tick: 0,
-            cur_slab_idx: EMPTY,
+            start: start,
+            elapsed: 0,
+            pending: BinaryHeap::new(),
+            next_seq: 0,
         }
     }
 
@@ -104,23 +165,18 @@ impl<T> TimerWheel<T> {
     /// The returned `Timeout` can later get passesd to `cancel` to retrieve the
     /// data and ensure the timeout doesn't fire.
     ///
-    /// This method completes in O(1) time.
+    /// This method completes in O(1) time, or O(log n) if the tick of `at` has
+    /// already come.
     ///
     /// # Panics
     ///
     /// This method will panic if `at` is before the time that this timer wheel
     /// was created.
     pub fn insert(&mut self, at: Instant, data: T) -> Timeout {
-        // First up, figure out where we're gonna go in the wheel. Note that if
-        // we're being scheduled on or before the current wheel tick we just
-        // make sure to defer ourselves to the next tick.
-        let mut tick = self.time_to_ticks(at);
-        if tick <= self.cur_wheel_tick {
-            debug!("moving {} to {}", tick, self.cur_wheel_tick + 1);
-            tick = self.cur_wheel_tick + 1;
-        }
-        let wheel_idx = self.ticks_to_wheel_idx(tick);
-        trace!("inserting timeout at {} for {}", wheel_idx, tick);
+        // First up, figure out which tick we're going to fire on. If that tick
+        // has already been processed the timeout goes straight to the
+        // `pending` heap, where it waits for `at` to pass.
+        let tick = self.time_to_ticks(at);
 
         // Next, make sure there's enough space in the slab for the timeout.
         if self.slab.vacant_entry().is_none() {
@@ -128,49 +184,35 @@ impl<T> TimerWheel<T> {
             self.slab.grow(amt);
         }
 
-        // Insert ourselves at the head of the linked list in the wheel.
-        let slot = &mut self.wheel[wheel_idx];
-        let prev_head;
-        {
+        let slab_idx = {
             let entry = self.slab.vacant_entry().unwrap();
-            prev_head = mem::replace(&mut slot.head, entry.index());
             trace!("timer wheel slab idx: {}", entry.index());
-
             entry.insert(Entry {
                 data: data,
                 when: at,
-                wheel_idx: wheel_idx,
+                tick: tick,
+                location: Location::Pending(0),
                 prev: EMPTY,
-                next: prev_head,
-            });
-        }
-        if prev_head != EMPTY {
-            self.slab[prev_head].prev = slot.head;
-        }
-
-        // Update the wheel slot's next timeout field.
-        if at <= slot.next_timeout.unwrap_or(at) {
-            let tick = tick as u32;
-            let actual_tick = self.start + Duration::from_millis(TICK_MS) * tick;
-            trace!("actual_tick: {:?}", actual_tick);
-            trace!("at:          {:?}", at);
-            let at = cmp::max(actual_tick, at);
-            debug!("updating[{}] next timeout: {:?}", wheel_idx, at);
-            slot.next_timeout = Some(at);
-        }
+                next: EMPTY,
+            }).index()
+        };
+        self.schedule(slab_idx);
 
         Timeout {
             when: at,
-            slab_idx: slot.head,
+            slab_idx: slab_idx,
         }
     }
 
     /// Queries this timer to see if any timeouts are ready to fire.
     ///
     /// This function will advance the internal wheel to the time specified by
-    /// `at`, returning any timeout which has happened up to that point. This
-    /// method should be called in a loop until it returns `None` to ensure that
-    /// all timeouts are processed.
+    /// `at`, returning any timeout which was scheduled for `at` or earlier.
+    /// This method should be called in a loop until it returns `None` to
+    /// ensure that all timeouts are processed.
+    ///
+    /// Timeouts are returned in the order of the instants they were scheduled
+    /// for.
     ///
     /// # Panics
     ///
@@ -179,74 +221,83 @@ impl<T> TimerWheel<T> {
     pub fn poll(&mut self, at: Instant) -> Option<T> {
         let wheel_tick = self.time_to_ticks(at);
 
-        trace!("polling {} => {}", self.cur_wheel_tick, wheel_tick);
-
-        // Advance forward in time to the `wheel_tick` specified.
-        //
-        // TODO: don't visit slots in the wheel more than once
-        while self.cur_wheel_tick <= wheel_tick {
-            let head = self.cur_slab_idx;
-            let idx = self.ticks_to_wheel_idx(self.cur_wheel_tick);
-            trace!("next head[{} => {}]: {}",
-                   self.cur_wheel_tick, wheel_tick, head);
-
-            // If the current slot has no entries or we're done iterating go to
-            // the next tick.
-            if head == EMPTY {
-                if head == self.wheel[idx].head {
-                    self.wheel[idx].next_timeout = None;
-                }
-                self.cur_wheel_tick += 1;
-                let idx = self.ticks_to_wheel_idx(self.cur_wheel_tick);
-                self.cur_slab_idx = self.wheel[idx].head;
-                continue
-            }
-
-            // If we're starting to iterate over a slot, clear its timeout as
-            // we're probably going to remove entries. As we skip over each
-            // element of this slot we'll restore the `next_timeout` field if
-            // necessary.
-            if head == self.wheel[idx].head {
-                self.wheel[idx].next_timeout = None;
+        trace!("polling {} => {}", self.elapsed, wheel_tick);
+
+        loop {
+            // Timeouts which share a tick with `at` may still be a little way
+            // off, so only hand out the earliest one if its instant has
+            // passed.
+            let due = match self.pending.peek() {
+                Some(top) if top.when <= at => Some(top.slab_idx),
+                _ => None,
+            };
+            if let Some(slab_idx) = due {
+                return self.remove_slab(slab_idx).map(|e| e.data)
             }
 
-            // Otherwise, continue iterating over the linked list in the wheel
-            // slot we're on and remove anything which has expired.
-            self.cur_slab_idx = self.slab[head].next;
-            let head_timeout = self.slab[head].when;
-            if self.time_to_ticks(head_timeout) <= self.time_to_ticks(at) {
-                return self.remove_slab(head).map(|e| e.data)
-            } else {
-                let next = self.wheel[idx].next_timeout.unwrap_or(head_timeout);
-                if head_timeout <= next {
-                    self.wheel[idx].next_timeout = Some(head_timeout);
+            // Jump straight to the next slot which has anything in it, as long
+            // as that's not past the time we're polling for. If there's
+            // nothing left to process then we're caught up.
+            let (level, slot, tick) = match self.next_slot() {
+                Some((level, slot, tick)) if tick <= wheel_tick => {
+                    (level, slot, tick)
                 }
+                _ => {
+                    self.elapsed = cmp::max(self.elapsed, wheel_tick);
+                    return None
+                }
+            };
+            trace!("processing slot {} at level {} for tick {}",
+                   slot, level, tick);
+            self.elapsed = tick;
+
+            // Take everything out of this slot and schedule it again. Anything
+            // whose tick has come is moved to the `pending` heap, and
+            // everything else moves down to a lower level.
+            let list = {
+                let level = &mut self.levels[level];
+                level.occupied &= !(1 << slot);
+                level.slots[slot].next_timeout = None;
+                mem::replace(&mut level.slots[slot].list, List::new())
+            };
+            let mut cur = list.head;
+            while cur != EMPTY {
+                let next = self.slab[cur].next;
+                self.schedule(cur);
+                cur = next;
             }
         }
-
-        None
     }
 
     /// Returns the instant in time that corresponds to the next timeout
     /// scheduled in this wheel.
+    ///
+    /// If the earliest timeout is in the lowest level of the wheel, or its
+    /// tick has already come, this is exactly the instant it was scheduled
+    /// for. Otherwise the instant returned may be earlier than any actual
+    /// timeout if that timeout is in a higher level of the wheel or has been
+    /// canceled. Polling the wheel at that instant will then simply not return
+    /// anything.
+    ///
+    /// This method completes in O(1) time.
     pub fn next_timeout(&self) -> Option<Instant> {
-        // TODO: can this be optimized to not look at the whole array?
-        let mut min = None;
-        for a in self.wheel.iter().filter_map(|s| s.next_timeout.as_ref()) {
-            if let Some(b) = min {
-                if b < a {
-                    continue
+        let min = if let Some(top) = self.pending.peek() {
+            Some(top.when)
+        } else {
+            self.next_slot().map(|(level, slot, tick)| {
+                let at = self.tick_start(tick);
+                match self.levels[level].slots[slot].next_timeout {
+                    Some(next) => cmp::max(at, next),
+                    None => at,
                 }
-            }
-            min = Some(a);
-        }
+            })
+        };
         if let Some(min) = min {
             debug!("next timeout {:?}", min);
-            debug!("now          {:?}", Instant::now());
         } else {
             debug!("next timeout never");
         }
-        min.map(|t| *t)
+        min
     }
 
     /// Cancels the specified timeout.
@@ -255,7 +306,8 @@ impl<T> TimerWheel<T> {
     /// to this method to cancel the associated timeout, retrieving the value
     /// inserted if the timeout has not already fired.
     ///
-    /// This method completes in O(1) time.
+    /// This method completes in O(1) time, or amortized O(log n) if the tick of
+    /// the timeout has already come.
     ///
     /// # Panics
     ///
@@ -269,28 +321,145 @@ impl<T> TimerWheel<T> {
         self.remove_slab(timeout.slab_idx).map(|e| e.data)
     }
 
+    // Places the entry at `slab_idx`, which isn't in any list, either in the
+    // wheel or in the `pending` heap if its tick has come.
+    fn schedule(&mut self, slab_idx: usize) {
+        let (tick, when) = {
+            let entry = &self.slab[slab_idx];
+            (entry.tick, entry.when)
+        };
+        if tick <= self.elapsed {
+            let seq = self.next_seq;
+            self.next_seq += 1;
+            self.slab[slab_idx].location = Location::Pending(seq);
+            self.pending.push(Pending {
+                when: when,
+                seq: seq,
+                slab_idx: slab_idx,
+            });
+            return
+        }
+
+        // Timeouts go in the lowest level where they don't share a slot with
+        // the current tick, which is where the tick differs from the current
+        // one in the most significant bits. Anything beyond the current
+        // lap of the top level goes in the top level too, in a slot before
+        // the current one, and far away timeouts which can't be told apart
+        // are parked in the last slot before the current one for now.
+        let top = (self.elapsed >> TOP_SHIFT) << TOP_SHIFT;
+        let tick = cmp::min(tick, top + MAX_TICKS - 1);
+        let significant = 63 - ((self.elapsed ^ tick) | MASK).leading_zeros();
+        let level = cmp::min(significant as usize / LEVEL_BITS, LEVELS - 1);
+        let slot = ((tick >> (level * LEVEL_BITS)) & MASK) as usize;
+        trace!("inserting timeout at slot {} of level {} for {}",
+               slot, level, tick);
+
+        self.slab[slab_idx].location = Location::Wheel(level, slot);
+        let mut list = self.levels[level].slots[slot].list;
+        self.push_front(&mut list, slab_idx);
+        let level = &mut self.levels[level];
+        level.occupied |= 1 << slot;
+        level.slots[slot].list = list;
+        let next = level.slots[slot].next_timeout.unwrap_or(when);
+        level.slots[slot].next_timeout = Some(cmp::min(next, when));
+    }
+
+    // Returns the level, slot, and starting tick of the next slot in the
+    // wheel which has any timeouts in it.
+    //
+    // Every timeout in a level below the top shares all the bits above that
+    // level with the current tick, and is later than it, so the next slot of
+    // a level is the first occupied one after the slot of the current tick.
+    // Lower levels cover earlier ticks, so the first level with such a slot
+    // has the next one overall. The top level also wraps around, so if none
+    // of its slots after the current one are occupied, the first occupied
+    // one before it is in the next lap.
+    fn next_slot(&self) -> Option<(usize, usize, u64)> {
+        for (i, level) in self.levels.iter().enumerate() {
+            let shift = i * LEVEL_BITS;
+            let cur = (self.elapsed >> shift) & MASK;
+            let mut base = self.elapsed & !((1 << (shift + LEVEL_BITS)) - 1);
+            let mut later = if cur == MASK {
+                0
+            } else {
+                level.occupied & !((1 << (cur + 1)) - 1)
+            };
+            if later == 0 && i == LEVELS - 1 {
+                later = level.occupied & ((1 << cur) - 1);
+                base += MAX_TICKS;
+            }
+            if later == 0 {
+                continue
+            }
+            let slot = later.trailing_zeros() as u64;
+            return Some((i, slot as usize, base + (slot << shift)))
+        }
+        None
+    }
+
     fn remove_slab(&mut self, slab_idx: usize) -> Option<Entry<T>> {
         debug!("removing timer slab {}", slab_idx);
-        let entry = match self.slab.remove(slab_idx) {
-            Some(e) => e,
+        let location = match self.slab.get(slab_idx) {
+            Some(e) => e.location,
             None => return None,
         };
 
-        // Remove the node from the linked list
-        if entry.prev == EMPTY {
-            self.wheel[entry.wheel_idx].head = entry.next;
-        } else {
-            self.slab[entry.prev].next = entry.next;
+        // Remove the node from the linked list it's in
+        match location {
+            Location::Wheel(level, slot) => {
+                let mut list = self.levels[level].slots[slot].list;
+                self.unlink(&mut list, slab_idx);
+                let level = &mut self.levels[level];
+                level.slots[slot].list = list;
+                if list.head == EMPTY {
+                    level.occupied &= !(1 << slot);
+                    level.slots[slot].next_timeout = None;
+                }
+            }
+            Location::Pending(_) => {}
         }
-        if entry.next != EMPTY {
-            self.slab[entry.next].prev = entry.prev;
+
+        let entry = self.slab.remove(slab_idx);
+
+        // The item in the heap is left behind, so pop it and any others left
+        // by earlier removals if they're at the top now.
+        loop {
+            let stale = match self.pending.peek() {
+                Some(top) => match self.slab.get(top.slab_idx) {
+                    Some(e) => e.location != Location::Pending(top.seq),
+                    None => true,
+                },
+                None => false,
+            };
+            if !stale {
+                return entry
+            }
+            self.pending.pop();
         }
+    }
 
-        if self.cur_slab_idx == slab_idx {
-            self.cur_slab_idx = entry.next;
+    fn push_front(&mut self, list: &mut List, slab_idx: usize) {
+        self.slab[slab_idx].prev = EMPTY;
+        self.slab[slab_idx].next = list.head;
+        if list.head != EMPTY {
+            self.slab[list.head].prev = slab_idx;
         }
+        list.head = slab_idx;
+    }
 
-        return Some(entry)
+    fn unlink(&mut self, list: &mut List, slab_idx: usize) {
+        let (prev, next) = {
+            let entry = &self.slab[slab_idx];
+            (entry.prev, entry.next)
+        };
+        if prev == EMPTY {
+            list.head = next;
+        } else {
+            self.slab[prev].next = next;
+        }
+        if next != EMPTY {
+            self.slab[next].prev = prev;
+        }
     }
 
     fn time_to_ticks(&self, time: Instant) -> u64 {
@@ -303,8 +472,36 @@ impl<T> TimerWheel<T> {
         (ms + TICK_MS / 2) / TICK_MS
     }
 
-    fn ticks_to_wheel_idx(&self, ticks: u64) -> usize {
-        (ticks as usize) & MASK
+    // Returns the earliest instant which is rounded to `tick`.
+    fn tick_start(&self, tick: u64) -> Instant {
+        let ms = tick.saturating_mul(TICK_MS).saturating_sub(TICK_MS / 2);
+        self.start + Duration::new(ms / 1_000, (ms % 1_000) as u32 * 1_000_000)
+    }
+}
+
+impl Timeout {
+    /// Returns the instant that this timeout was scheduled to fire at.
+    pub fn when(&self) -> Instant {
+        self.when
+    }
+}
+
+impl List {
+    fn new() -> List {
+        List { head: EMPTY }
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Pending) -> Ordering {
+        // `BinaryHeap` is a max-heap, so compare the other way around.
+        (other.when, other.seq).cmp(&(self.when, self.seq))
+    }
+}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Pending) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -312,9 +509,10 @@ impl<T> TimerWheel<T> {
 mod tests {
     extern crate env_logger;
 
+    use std::collections::BTreeMap;
     use std::time::{Instant, Duration};
 
-    use super::TimerWheel;
+    use super::{TimerWheel, Timeout};
 
     fn ms(amt: u64) -> Duration {
         Duration::from_millis(amt)
@@ -429,8 +627,11 @@ mod tests {
             found.push(i);
         }
         found.sort();
-        assert_eq!(found, [3, 4, 5]);
+        assert_eq!(found, [3, 4]);
 
+        // The same tick doesn't hand out timeouts which aren't due yet.
+        assert_eq!(timer.next_timeout(), Some(now + ms(201)));
+        assert_eq!(timer.poll(now + ms(201)), Some(5));
         assert_eq!(timer.poll(now + ms(300)), Some(6));
         assert_eq!(timer.poll(now + ms(300)), None);
     }
@@ -465,9 +666,10 @@ mod tests {
         let now = Instant::now();
 
         let t1 = timer.insert(now + ms(1), 1);
-        timer.insert(now + ms(2), 2);
-        assert_eq!(timer.poll(now + ms(200)), Some(2));
-        timer.cancel(&t1).unwrap();
+        let t2 = timer.insert(now + ms(2), 2);
+        assert_eq!(timer.poll(now + ms(200)), Some(1));
+        assert_eq!(timer.cancel(&t1), None);
+        timer.cancel(&t2).unwrap();
         assert_eq!(timer.poll(now + ms(200)), None);
     }
 
@@ -495,4 +697,202 @@ mod tests {
             assert_eq!(timer.next_timeout(), None);
         }
     }
+
+    // A small deterministic xorshift generator so failures are reproducible.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+
+        // Durations spread from a few milliseconds up to several hours.
+        fn delay(&mut self) -> Duration {
+            let max = [300, 10_000, 600_000, 36_000_000];
+            let max = max[self.below(4) as usize];
+            ms(self.below(max))
+        }
+    }
+
+    // Runs random inserts, cancels and polls against a `BTreeMap` of the
+    // instant each live timeout is expected to fire at, starting `skip` after
+    // the wheel was created.
+    fn check_against_reference(seed: u64, ops: usize, skip: Duration) {
+        let mut rng = Rng(seed);
+        let mut timer = TimerWheel::<usize>::new();
+        let start = timer.start;
+        let mut now = start + skip;
+        assert_eq!(timer.poll(now), None);
+        let mut live = BTreeMap::<usize, (Instant, Timeout)>::new();
+        let mut next_id = 0;
+
+        for _ in 0..ops {
+            match rng.below(10) {
+                n if n < 6 => {
+                    let at = now + rng.delay();
+                    let timeout = timer.insert(at, next_id);
+                    live.insert(next_id, (at, timeout));
+                    next_id += 1;
+                }
+                6 | 7 if !live.is_empty() => {
+                    let n = rng.below(live.len() as u64) as usize;
+                    let id = *live.keys().nth(n).unwrap();
+                    let (_, timeout) = live.remove(&id).unwrap();
+                    assert_eq!(timer.cancel(&timeout), Some(id));
+                    assert_eq!(timer.cancel(&timeout), None);
+                }
+                _ => {
+                    now = now + rng.delay();
+                    let mut last = start;
+                    while let Some(id) = timer.poll(now) {
+                        let (when, _) = live.remove(&id)
+                                            .expect("fired twice or canceled");
+                        assert!(when <= now, "{} fired early", id);
+                        assert!(when >= last, "{} fired out of order", id);
+                        last = when;
+                    }
+                    for (id, &(when, _)) in live.iter() {
+                        assert!(when > now, "{} missed", id);
+                    }
+                }
+            }
+
+            let min = live.values().map(|&(when, _)| when).min();
+            match (timer.next_timeout(), min) {
+                (None, None) => {}
+                (Some(next), Some(min)) => assert!(next <= min),
+                (next, min) => panic!("next timeout {:?} but min {:?}",
+                                      next.map(|n| n - start),
+                                      min.map(|m| m - start)),
+            }
+        }
+
+        // Finally jumping past everything fires all the remaining timeouts.
+        now = now + Duration::from_secs(24 * 60 * 60);
+        while let Some(id) = timer.poll(now) {
+            assert!(live.remove(&id).is_some());
+        }
+        assert!(live.is_empty());
+        assert_eq!(timer.next_timeout(), None);
+    }
+
+    #[test]
+    fn matches_reference() {
+        drop(env_logger::init());
+        for seed in 1..50u64 {
+            let seed = seed.wrapping_mul(0x9e3779b97f4a7c15);
+            check_against_reference(seed, 2_000, Duration::new(0, 0));
+        }
+
+        // Also run across the end of the first lap of the top level.
+        let lap = ticks(super::MAX_TICKS);
+        for seed in 1..10u64 {
+            let seed = seed.wrapping_mul(0x9e3779b97f4a7c15);
+            let skip = lap - Duration::from_secs(100 * 60 * 60);
+            check_against_reference(seed, 2_000, skip);
+        }
+    }
+
+    #[test]
+    fn jump_to_next_timeout() {
+        drop(env_logger::init());
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        let mut timer = TimerWheel::<usize>::new();
+        let start = timer.start;
+        let whens = (0..1_000).map(|_| start + rng.delay()).collect::<Vec<_>>();
+        for (i, &when) in whens.iter().enumerate() {
+            timer.insert(when, i);
+        }
+
+        // Moving time straight to each next timeout, as a paused clock does,
+        // fires every timeout at exactly the instant it was scheduled for.
+        let mut fired = 0;
+        while let Some(next) = timer.next_timeout() {
+            while let Some(i) = timer.poll(next) {
+                assert!(whens[i] == next, "{} fired at {:?}", i, next - start);
+                fired += 1;
+            }
+        }
+        assert_eq!(fired, whens.len());
+    }
+
+    #[test]
+    fn many_in_one_tick() {
+        drop(env_logger::init());
+        let mut timer = TimerWheel::<usize>::new();
+        let start = timer.start;
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+
+        // Lots of timeouts sharing a tick are drained the way the event loop
+        // does it, each coming out at exactly its instant and in order.
+        let tick = start + ms(1_000);
+        let whens = (0..32_000).map(|_| {
+            tick + Duration::new(0, rng.below(40_000_000) as u32)
+        }).collect::<Vec<_>>();
+        for (i, &when) in whens.iter().enumerate() {
+            timer.insert(when, i);
+        }
+        let mut fired = 0;
+        let mut last = start;
+        while let Some(next) = timer.next_timeout() {
+            assert!(next >= last);
+            while let Some(i) = timer.poll(next) {
+                assert!(whens[i] == next);
+                fired += 1;
+            }
+            last = next;
+        }
+        assert_eq!(fired, whens.len());
+    }
+
+    #[test]
+    fn far_future() {
+        drop(env_logger::init());
+        let mut timer = TimerWheel::<i32>::new();
+        let now = timer.start;
+        let hour = Duration::from_secs(60 * 60);
+
+        timer.insert(now + hour * 3, 3);
+        timer.insert(now + hour, 1);
+        timer.insert(now + ms(500), 0);
+        timer.insert(now + Duration::from_secs(10 * 365 * 24 * 60 * 60), 4);
+
+        assert_eq!(timer.poll(now + ms(500)), Some(0));
+        assert_eq!(timer.poll(now + hour - ms(100)), None);
+        assert!(timer.next_timeout().unwrap() <= now + hour);
+        assert_eq!(timer.poll(now + hour), Some(1));
+        assert_eq!(timer.poll(now + hour * 2), None);
+        assert_eq!(timer.poll(now + hour * 3), Some(3));
+        assert_eq!(timer.poll(now + hour * 24 * 365), None);
+        assert!(timer.next_timeout().is_some());
+        assert_eq!(timer.poll(now + hour * 24 * 366 * 10), Some(4));
+        assert_eq!(timer.next_timeout(), None);
+
+        // Timeouts beyond a full lap of the wheel have to wait for their lap
+        // to come around, both from afar and from right before it.
+        let lap = ticks(super::MAX_TICKS);
+        timer.insert(now + lap * 2 + hour, 6);
+        timer.insert(now + lap + ticks(10), 5);
+        assert_eq!(timer.poll(now + lap - ticks(5)), None);
+        timer.insert(now + lap + ticks(5), 7);
+        assert_eq!(timer.poll(now + lap - ticks(1)), None);
+        assert_eq!(timer.poll(now + lap), None);
+        assert_eq!(timer.poll(now + lap + ticks(5)), Some(7));
+        assert_eq!(timer.poll(now + lap + ticks(10)), Some(5));
+        assert_eq!(timer.poll(now + lap * 2), None);
+        assert!(timer.next_timeout().unwrap() <= now + lap * 2 + hour);
+        assert_eq!(timer.poll(now + lap * 2 + hour), Some(6));
+        assert_eq!(timer.next_timeout(), None);
+    }
+
+    fn ticks(n: u64) -> Duration {
+        ms(n * super::TICK_MS)
+    }
 }
@@ -0,0 +1,167 @@
+extern crate env_logger;
+extern crate futures;
+extern crate futures_io;
+extern crate futures_mio;
+
+use std::cell::Cell;
+use std::io::{self, Write};
+use std::net::TcpListener;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Instant, Duration};
+
+use futures::Future;
+use futures::stream::Stream;
+use futures_io::read_to_end;
+use futures_mio::{Backoff, Clock, DelayQueue, FixedBackoff, Loop};
+
+macro_rules! t {
+    ($e:expr) => (match $e {
+        Ok(e) => e,
+        Err(e) => panic!("{} failed with {:?}", stringify!($e), e),
+    })
+}
+
+fn secs(n: u64) -> Duration {
+    Duration::from_secs(n)
+}
+
+#[test]
+fn system_clock() {
+    drop(env_logger::init());
+    let l = t!(Loop::new());
+    assert!(!l.handle().clock().is_paused());
+    let before = Instant::now();
+    let now = l.handle().now();
+    assert!(before <= now && now <= Instant::now());
+}
+
+#[test]
+fn idle_jumps_to_timeout() {
+    drop(env_logger::init());
+    let clock = Clock::paused();
+    let mut l = t!(Loop::with_clock(clock.clone()));
+    let start = clock.now();
+    let real = Instant::now();
+
+    let timeout = l.handle().timeout(secs(60 * 60)).and_then(|t| t);
+    t!(l.run(timeout));
+    assert!(clock.now() - start >= secs(60 * 60));
+    assert!(real.elapsed() < secs(5));
+}
+
+#[test]
+fn earliest_timeout_fires_first() {
+    drop(env_logger::init());
+    let clock = Clock::paused();
+    let mut l = t!(Loop::with_clock(clock.clone()));
+    let start = clock.now();
+
+    let a = l.handle().timeout(secs(20)).and_then(|t| t).map(|()| 20);
+    let b = l.handle().timeout(secs(10)).and_then(|t| t).map(|()| 10);
+    let first = a.select(b).map(|(n, _)| n).map_err(|(e, _)| e);
+    assert_eq!(t!(l.run(first)), 10);
+    assert_eq!(clock.now() - start, secs(10));
+}
+
+#[test]
+fn advance_fires_due_timers() {
+    drop(env_logger::init());
+    let clock = Clock::paused();
+    let mut l = t!(Loop::with_clock(clock.clone()));
+    let start = clock.now();
+
+    // Moving time forward by hand makes the first three ticks due at once,
+    // without the clock having to jump any further.
+    let ticks = l.handle().interval(secs(1)).take(3).collect();
+    let clock2 = clock.clone();
+    let ticks = futures::lazy(move || {
+        clock2.advance(secs(3));
+        Ok(())
+    }).and_then(|()| ticks);
+    assert_eq!(t!(l.run(ticks)).len(), 3);
+    assert_eq!(clock.now() - start, secs(3));
+}
+
+#[test]
+fn delay_queue() {
+    drop(env_logger::init());
+    let clock = Clock::paused();
+    let mut l = t!(Loop::with_clock(clock.clone()));
+    let start = clock.now();
+
+    let mut queue = DelayQueue::new(l.handle());
+    queue.insert(3, secs(30 * 60));
+    queue.insert(1, secs(10 * 60));
+    queue.insert(2, secs(20 * 60));
+    let values = queue.take(3).map(|e| e.into_inner()).collect();
+    assert_eq!(t!(l.run(values)), [1, 2, 3]);
+    assert!(clock.now() - start >= secs(30 * 60));
+}
+
+#[test]
+fn retry() {
+    drop(env_logger::init());
+    let clock = Clock::paused();
+    let mut l = t!(Loop::with_clock(clock.clone()));
+    let start = clock.now();
+
+    let attempts = Rc::new(Cell::new(0));
+    let attempts2 = attempts.clone();
+    let backoff = FixedBackoff::new(secs(60 * 60)).max_attempts(3);
+    let retry = l.handle().retry(backoff, move || {
+        attempts2.set(attempts2.get() + 1);
+        Err::<(), _>(io::Error::new(io::ErrorKind::Other, "nope"))
+    });
+    assert!(l.run(retry).is_err());
+    assert_eq!(attempts.get(), 3);
+    assert!(clock.now() - start >= secs(2 * 60 * 60));
+}
+
+#[test]
+fn waits_for_io() {
+    drop(env_logger::init());
+    let clock = Clock::paused();
+    let mut l = t!(Loop::with_clock(clock.clone()));
+    let srv = t!(TcpListener::bind("127.0.0.1:0"));
+    let addr = t!(srv.local_addr());
+    let t = thread::spawn(move || {
+        let mut stream = t!(srv.accept()).0;
+        thread::sleep(Duration::from_millis(10));
+        t!(stream.write_all(b"hello"));
+    });
+
+    // The clock shouldn't jump past the deadline while the connection is
+    // still being established or waiting for data.
+    let start = clock.now();
+    let data = l.handle().tcp_connect(&addr).and_then(|stream| {
+        read_to_end(stream, Vec::new())
+    });
+    let data = l.handle().deadline(data, secs(1));
+    assert_eq!(t!(l.run(data)), b"hello");
+    assert!(clock.now() - start < secs(1));
+    t.join().unwrap();
+}
+
+#[test]
+fn advance_while_waiting_on_io() {
+    drop(env_logger::init());
+    let clock = Clock::paused();
+    let mut l = t!(Loop::with_clock(clock.clone()));
+    let start = clock.now();
+    let srv = l.handle().tcp_listen(&"127.0.0.1:0".parse().unwrap());
+    let srv = t!(l.run(srv));
+
+    // A listener waiting for connections keeps the clock from jumping to the
+    // timeout, so it only fires once the clock is moved by hand.
+    let clock2 = clock.clone();
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        clock2.advance_to(start + secs(2 * 60 * 60));
+    });
+    let timeout = l.handle().timeout(secs(60 * 60)).and_then(|t| t);
+    let incoming = srv.incoming().for_each(|_| Ok(()));
+    t!(l.run(timeout.select(incoming).map(|_| ()).map_err(|(e, _)| e)));
+    assert_eq!(clock.now() - start, secs(2 * 60 * 60));
+    t.join().unwrap();
+}
@@ -0,0 +1,137 @@
+extern crate env_logger;
+extern crate futures;
+extern crate futures_mio;
+
+use std::io;
+use std::thread;
+use std::time::{Instant, Duration};
+
+use futures::{empty, failed, finished, oneshot, Future, Poll};
+use futures::stream::{self, Stream};
+use futures_mio::{Clock, DeadlineError, Loop};
+
+macro_rules! t {
+    ($e:expr) => (match $e {
+        Ok(e) => e,
+        Err(e) => panic!("{} failed with {:?}", stringify!($e), e),
+    })
+}
+
+#[test]
+fn finishes_first() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let dur = Duration::from_secs(10);
+    let future = l.handle().deadline(finished::<i32, io::Error>(1), dur);
+    assert_eq!(t!(l.run(future)), 1);
+
+    let future = l.handle().deadline(failed::<i32, i32>(2), dur);
+    match l.run(future) {
+        Err(DeadlineError::Inner(2)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn finishing_first_cancels_timeout() {
+    drop(env_logger::init());
+    let clock = Clock::paused();
+    let mut l = t!(Loop::with_clock(clock.clone()));
+    let start = clock.now();
+
+    let (tx, mut rx) = oneshot();
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        tx.complete(());
+    });
+
+    // Keep the deadline around after the inner timer fires, and then wait on
+    // another thread. The loop only has the deadline's timeout left to jump
+    // the clock to if it wasn't canceled when the inner future finished.
+    let inner = l.handle().timeout(Duration::from_secs(10)).and_then(|t| t);
+    let mut future = l.handle().deadline(inner, Duration::from_secs(60 * 60));
+    let mut done = false;
+    let clock2 = clock.clone();
+    let stream = stream::poll_fn(move |task| {
+        if !done {
+            match future.poll(task) {
+                Poll::Ok(()) => done = true,
+                Poll::Err(e) => return Poll::Err(io::Error::from(e)),
+                Poll::NotReady => {
+                    future.schedule(task);
+                    return Poll::NotReady
+                }
+            }
+        }
+        match rx.poll(task) {
+            Poll::Ok(()) => Poll::Ok(Some(clock2.now())),
+            Poll::Err(_) => panic!("sender dropped"),
+            Poll::NotReady => {
+                rx.schedule(task);
+                Poll::NotReady
+            }
+        }
+    });
+    let (now, _stream) = t!(l.run(stream.into_future().map_err(|(e, _)| e)));
+    assert_eq!(now.unwrap() - start, Duration::from_secs(10));
+    t.join().unwrap();
+}
+
+#[test]
+fn times_out() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let dur = Duration::from_millis(10);
+    let future = l.handle().deadline(empty::<i32, io::Error>(), dur);
+    let start = Instant::now();
+    match l.run(future) {
+        Err(DeadlineError::TimedOut) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(start.elapsed() >= dur);
+
+    // Timing out can be turned into an I/O error.
+    let future = l.handle().deadline(empty::<i32, io::Error>(), dur);
+    let err = l.run(future.map_err(io::Error::from)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+}
+
+#[test]
+fn inner_timer() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let dur = Duration::from_millis(10);
+
+    // A future waiting on a timer of its own finishes before the deadline.
+    let inner = l.handle().timeout(dur).and_then(|t| t);
+    let future = l.handle().deadline(inner, dur * 20);
+    t!(l.run(future));
+
+    let inner = l.handle().timeout(dur * 20).and_then(|t| t);
+    let future = l.handle().deadline(inner, dur);
+    assert!(l.run(future).unwrap_err().is_timed_out());
+}
+
+#[test]
+fn idle_stream() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let dur = Duration::from_millis(50);
+
+    // Items arriving more often than the idle timeout pass straight through.
+    let ticks = l.handle().interval(Duration::from_millis(10)).take(5);
+    let ticks = l.handle().timeout_stream(ticks, dur * 20).collect();
+    assert_eq!(t!(l.run(ticks)).len(), 5);
+
+    // The timer restarts after every item, so a slow stream times out again.
+    let (tx, rx) = stream::channel::<i32, io::Error>();
+    let rx = l.handle().timeout_stream(rx, dur).map(Ok).or_else(|e| {
+        if e.is_timed_out() {
+            Ok(Err(()))
+        } else {
+            Err(e)
+        }
+    }).take(2).collect();
+    assert_eq!(t!(l.run(rx)), [Err(()), Err(())]);
+    drop(tx);
+}
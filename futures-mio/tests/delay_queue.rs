@@ -0,0 +1,151 @@
+extern crate env_logger;
+extern crate futures;
+extern crate futures_mio;
+
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
+use std::time::{Instant, Duration};
+
+use futures::{Future, Task, Poll};
+use futures::stream::Stream;
+use futures_mio::{DelayQueue, Expired, Loop};
+
+macro_rules! t {
+    ($e:expr) => (match $e {
+        Ok(e) => e,
+        Err(e) => panic!("{} failed with {:?}", stringify!($e), e),
+    })
+}
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+// A queue which can also be inserted into by other futures.
+struct Shared<T>(Rc<RefCell<DelayQueue<T>>>);
+
+impl<T: 'static> Stream for Shared<T> {
+    type Item = Expired<T>;
+    type Error = io::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<Expired<T>>, io::Error> {
+        self.0.borrow_mut().poll(task)
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.0.borrow_mut().schedule(task)
+    }
+}
+
+// Polls `queue` until it yields a value, while another future inserts `value`
+// into it after it's gone idle, returning the first value yielded and how
+// long that took.
+fn first_after_insert(l: &mut Loop,
+                      queue: DelayQueue<&'static str>,
+                      value: &'static str) -> (&'static str, Duration) {
+    let queue = Rc::new(RefCell::new(queue));
+    let queue2 = queue.clone();
+    let insert = l.handle().timeout(ms(20)).and_then(|t| t).map(move |()| {
+        queue2.borrow_mut().insert(value, ms(10));
+    });
+    let first = Shared(queue).take(1).collect();
+    let start = Instant::now();
+    let work = l.handle().deadline(first.join(insert), Duration::from_secs(5));
+    let (first, ()) = t!(l.run(work));
+    (*first[0].get_ref(), start.elapsed())
+}
+
+#[test]
+fn expires_in_order() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let mut queue = DelayQueue::new(l.handle());
+    queue.insert("a", ms(30));
+    queue.insert("b", ms(10));
+    queue.insert("c", ms(20));
+    assert_eq!(queue.len(), 3);
+
+    let items = queue.map(|expired| {
+        assert!(expired.deadline() <= Instant::now());
+        expired.into_inner()
+    }).take(3).collect();
+    assert_eq!(t!(l.run(items)), ["b", "c", "a"]);
+}
+
+#[test]
+fn remove_and_reset() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let mut queue = DelayQueue::new(l.handle());
+    let start = Instant::now();
+    let a = queue.insert("a", ms(10));
+    let b = queue.insert("b", ms(20));
+
+    assert_eq!(queue.remove(&a), Some("a"));
+    assert_eq!(queue.remove(&a), None);
+    assert!(!queue.reset(&a, ms(10)));
+    assert!(queue.reset(&b, ms(50)));
+    assert_eq!(queue.get(&b), Some(&"b"));
+    assert_eq!(queue.len(), 1);
+
+    let (expired, queue) = t!(l.run(queue.into_future().map_err(|(e, _)| e)));
+    let expired = expired.unwrap();
+    assert_eq!(expired.key(), b);
+    assert_eq!(expired.into_inner(), "b");
+    assert!(start.elapsed() >= ms(50));
+    assert!(queue.is_empty());
+    assert!(!queue.contains(&b));
+}
+
+#[test]
+fn stale_keys() {
+    drop(env_logger::init());
+    let l = t!(futures_mio::Loop::new());
+    let mut queue = DelayQueue::new(l.handle());
+    let a = queue.insert(1, ms(10));
+    assert_eq!(queue.remove(&a), Some(1));
+
+    // The space for `a` is reused, but `a` doesn't refer to the new value.
+    let b = queue.insert(2, ms(10));
+    assert!(!queue.contains(&a));
+    assert_eq!(queue.remove(&a), None);
+    assert_eq!(queue.deadline(&a), None);
+    assert!(queue.contains(&b));
+
+    queue.clear();
+    assert!(queue.is_empty());
+    assert!(!queue.contains(&b));
+}
+
+#[test]
+fn past_deadlines() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let mut queue = DelayQueue::new(l.handle());
+    let past = Instant::now() - ms(10);
+    queue.insert_at(1, past);
+    let (expired, _queue) = t!(l.run(queue.into_future().map_err(|(e, _)| e)));
+    assert_eq!(expired.unwrap().deadline(), past);
+}
+
+#[test]
+fn insert_into_idle() {
+    drop(env_logger::init());
+    let mut l = t!(Loop::new());
+    let queue = DelayQueue::new(l.handle());
+    let (first, elapsed) = first_after_insert(&mut l, queue, "a");
+    assert_eq!(first, "a");
+    assert!(elapsed < Duration::from_secs(1));
+}
+
+#[test]
+fn insert_earlier_deadline() {
+    drop(env_logger::init());
+    let mut l = t!(Loop::new());
+    let mut queue = DelayQueue::new(l.handle());
+    queue.insert("late", Duration::from_secs(60));
+    let (first, elapsed) = first_after_insert(&mut l, queue, "early");
+    assert_eq!(first, "early");
+    assert!(elapsed < Duration::from_secs(1));
+}
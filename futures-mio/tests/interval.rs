@@ -0,0 +1,156 @@
+extern crate env_logger;
+extern crate futures;
+extern crate futures_mio;
+
+use std::io;
+use std::time::{Instant, Duration};
+
+use futures::Future;
+use futures::stream::{self, Stream};
+use futures_mio::{Clock, Loop, MissedTicks};
+
+macro_rules! t {
+    ($e:expr) => (match $e {
+        Ok(e) => e,
+        Err(e) => panic!("{} failed with {:?}", stringify!($e), e),
+    })
+}
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[test]
+fn interval() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let dur = Duration::from_millis(10);
+    let ticks = l.handle().interval(dur).take(3).collect();
+    let start = Instant::now();
+    let ticks = t!(l.run(ticks));
+    assert_eq!(ticks.len(), 3);
+    assert!(start.elapsed() >= dur * 3);
+}
+
+// Runs an interval of one second on a paused clock which is moved forward
+// by five and a half seconds before the interval is first polled, returning
+// when each of the first `n` ticks was yielded.
+fn ticks_after_delay(missed_ticks: MissedTicks, n: u64) -> Vec<Duration> {
+    let clock = Clock::paused();
+    let mut l = t!(Loop::with_clock(clock.clone()));
+    let start = clock.now();
+    let mut ticks = l.handle().interval(ms(1000));
+    ticks.set_missed_ticks(missed_ticks);
+
+    let clock2 = clock.clone();
+    let ticks = ticks.take(n).map(move |()| clock2.now() - start).collect();
+    let ticks = futures::lazy(move || {
+        clock.advance(ms(5500));
+        Ok(())
+    }).and_then(|()| ticks);
+    t!(l.run(ticks))
+}
+
+// Checks that ticks were yielded at exactly the `expected` milliseconds.
+fn assert_ticks(ticks: &[Duration], expected: &[u64]) {
+    let expected = expected.iter().map(|&n| ms(n)).collect::<Vec<_>>();
+    assert_eq!(ticks, &expected[..]);
+}
+
+#[test]
+fn missed_ticks() {
+    drop(env_logger::init());
+    let l = t!(Loop::new());
+    assert_eq!(l.handle().interval(ms(10)).missed_ticks(),
+               MissedTicks::Burst);
+
+    // By default missed ticks all come out right away, and then the original
+    // schedule continues.
+    let ticks = ticks_after_delay(MissedTicks::Burst, 6);
+    assert_ticks(&ticks, &[5500, 5500, 5500, 5500, 5500, 6000]);
+
+    // Skipping picks the original schedule back up after the delay.
+    let ticks = ticks_after_delay(MissedTicks::Skip, 3);
+    assert_ticks(&ticks, &[5500, 6000, 7000]);
+
+    // Delaying restarts the schedule from the late tick.
+    let ticks = ticks_after_delay(MissedTicks::Delay, 3);
+    assert_ticks(&ticks, &[5500, 6500, 7500]);
+}
+
+#[test]
+fn throttle() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let dur = Duration::from_millis(10);
+    let items = stream::iter((0..3).map(Ok::<i32, io::Error>));
+    let items = l.handle().throttle(items, dur).collect();
+    let start = Instant::now();
+    assert_eq!(t!(l.run(items)), [0, 1, 2]);
+    assert!(start.elapsed() >= dur * 2);
+}
+
+#[test]
+fn debounce() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let dur = Duration::from_millis(10);
+
+    // A burst of items only yields the last one, once the time has passed.
+    let items = stream::iter((0..3).map(Ok::<i32, io::Error>));
+    let items = l.handle().debounce(items, dur).collect();
+    assert_eq!(t!(l.run(items)), [2]);
+
+    // The stream doesn't end here, so the last item waits for the timer.
+    let (tx, rx) = stream::channel::<i32, io::Error>();
+    let tx = tx.send(Ok(1)).and_then(|tx| tx.send(Ok(2))).map_err(|_| {
+        io::Error::new(io::ErrorKind::Other, "receiver gone")
+    });
+    let rx = l.handle().debounce(rx, dur).into_future().map_err(|(e, _)| e);
+    let start = Instant::now();
+    let ((item, _rx), _tx) = t!(l.run(rx.join(tx)));
+    assert_eq!(item, Some(2));
+    assert!(start.elapsed() >= dur);
+}
+
+#[test]
+fn timeout_stream() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let dur = Duration::from_millis(10);
+
+    let (tx, rx) = stream::channel::<i32, io::Error>();
+    let rx = l.handle().timeout_stream(rx, dur).into_future();
+    let start = Instant::now();
+    match l.run(rx) {
+        Err((e, _)) => assert!(e.is_timed_out()),
+        Ok(_) => panic!("expected a timeout"),
+    }
+    assert!(start.elapsed() >= dur);
+    drop(tx);
+
+    let items = stream::iter((0..3).map(Ok::<i32, io::Error>));
+    let items = l.handle().timeout_stream(items, dur).collect();
+    assert_eq!(t!(l.run(items)), [0, 1, 2]);
+}
+
+#[test]
+fn chunks_timeout() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let dur = Duration::from_millis(50);
+
+    // Full batches are flushed right away.
+    let items = stream::iter((1..6).map(Ok::<i32, io::Error>));
+    let chunks = l.handle().chunks_timeout(items, 2, dur).collect();
+    assert_eq!(t!(l.run(chunks)), [vec![1, 2], vec![3, 4], vec![5]]);
+
+    // A batch which doesn't fill up is flushed once the time runs out.
+    let ticks = l.handle().interval(Duration::from_millis(10));
+    let chunks = l.handle().chunks_timeout(ticks, 1000, dur).into_future();
+    let start = Instant::now();
+    let (chunk, _) = t!(l.run(chunks).map_err(|(e, _)| e));
+    let chunk = chunk.unwrap();
+    assert!(!chunk.is_empty() && chunk.len() < 1000);
+    assert!(start.elapsed() >= dur);
+}
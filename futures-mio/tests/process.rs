@@ -0,0 +1,97 @@
+#![cfg(unix)]
+
+extern crate env_logger;
+extern crate futures;
+extern crate futures_io;
+extern crate futures_mio;
+
+use std::process::{Command, Stdio};
+
+use futures::Future;
+use futures_io::{read_to_end, write_all};
+use futures_mio::{CommandExt, Loop};
+
+macro_rules! t {
+    ($e:expr) => (match $e {
+        Ok(e) => e,
+        Err(e) => panic!("{} failed with {:?}", stringify!($e), e),
+    })
+}
+
+#[test]
+fn exit_status() {
+    drop(env_logger::init());
+    let mut l = t!(Loop::new());
+    let child = Command::new("sh").arg("-c").arg("exit 3")
+                                  .spawn_async(l.handle());
+    let status = t!(l.run(child.and_then(|child| child)));
+    assert_eq!(status.code(), Some(3));
+}
+
+#[test]
+fn piped_stdio() {
+    drop(env_logger::init());
+    let mut l = t!(Loop::new());
+    let child = Command::new("cat")
+                        .stdin(Stdio::piped())
+                        .stdout(Stdio::piped())
+                        .spawn_async(l.handle());
+    let mut child = t!(l.run(child));
+    assert!(child.stderr().is_none());
+
+    // Dropping stdin once everything is written lets `cat` finish.
+    let stdin = child.stdin().take().unwrap();
+    let stdout = child.stdout().take().unwrap();
+    let input = write_all(stdin, b"hello world".to_vec()).map(|_| ());
+    let output = read_to_end(stdout, Vec::new());
+    let (((), output), status) = t!(l.run(input.join(output).join(child)));
+    assert_eq!(output, b"hello world");
+    assert!(status.success());
+}
+
+#[test]
+fn stderr() {
+    drop(env_logger::init());
+    let mut l = t!(Loop::new());
+    let child = Command::new("sh").arg("-c").arg("echo oops >&2")
+                                  .stderr(Stdio::piped())
+                                  .spawn_async(l.handle());
+    let mut child = t!(l.run(child));
+    let stderr = read_to_end(child.stderr().take().unwrap(), Vec::new());
+    let (output, status) = t!(l.run(stderr.join(child)));
+    assert_eq!(output, b"oops\n");
+    assert!(status.success());
+}
+
+#[test]
+fn many_children() {
+    drop(env_logger::init());
+    let mut l = t!(Loop::new());
+    let children = (0..5).map(|i| {
+        Command::new("sh").arg("-c").arg(format!("sleep 0.{}; exit {}", i, i))
+                          .spawn_async(l.handle())
+                          .and_then(|child| child)
+    }).collect::<Vec<_>>();
+    let statuses = t!(l.run(futures::collect(children)));
+    let codes = statuses.iter().map(|s| s.code()).collect::<Vec<_>>();
+    assert_eq!(codes, [Some(0), Some(1), Some(2), Some(3), Some(4)]);
+}
+
+#[test]
+fn kill() {
+    drop(env_logger::init());
+    let mut l = t!(Loop::new());
+    let child = Command::new("sleep").arg("1000").spawn_async(l.handle());
+    let mut child = t!(l.run(child));
+    t!(child.kill());
+    let status = t!(l.run(child));
+    assert!(!status.success());
+}
+
+#[test]
+fn spawn_error() {
+    drop(env_logger::init());
+    let mut l = t!(Loop::new());
+    let child = Command::new("/nonexistent/binary").spawn_async(l.handle());
+    assert!(l.run(child).is_err());
+}
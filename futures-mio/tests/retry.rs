@@ -0,0 +1,110 @@
+extern crate env_logger;
+extern crate futures;
+extern crate futures_mio;
+
+use std::cell::Cell;
+use std::io;
+use std::rc::Rc;
+use std::time::{Instant, Duration};
+
+use futures_mio::{Backoff, FixedBackoff, ExponentialBackoff};
+
+macro_rules! t {
+    ($e:expr) => (match $e {
+        Ok(e) => e,
+        Err(e) => panic!("{} failed with {:?}", stringify!($e), e),
+    })
+}
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+// Returns a closure whose attempts fail until the `n`th one, along with a
+// count of the attempts made.
+fn flaky(n: u32) -> (Box<FnMut() -> io::Result<u32>>, Rc<Cell<u32>>) {
+    let attempts = Rc::new(Cell::new(0));
+    let attempts2 = attempts.clone();
+    let action = Box::new(move || {
+        attempts2.set(attempts2.get() + 1);
+        if attempts2.get() < n {
+            Err(io::Error::new(io::ErrorKind::Other, "not yet"))
+        } else {
+            Ok(attempts2.get())
+        }
+    });
+    (action, attempts)
+}
+
+#[test]
+fn succeeds_eventually() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let (action, attempts) = flaky(3);
+    let backoff = FixedBackoff::new(ms(10)).max_attempts(5);
+    let retry = l.handle().retry(backoff, action);
+    let start = Instant::now();
+    assert_eq!(t!(l.run(retry)), 3);
+    assert_eq!(attempts.get(), 3);
+    assert!(start.elapsed() >= ms(20));
+}
+
+#[test]
+fn gives_up() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let (action, attempts) = flaky(10);
+    let backoff = ExponentialBackoff::new(ms(1)).jitter(true).max_attempts(3);
+    let retry = l.handle().retry(backoff, action);
+    let err = l.run(retry).unwrap_err();
+    assert_eq!(err.into_inner().unwrap().kind(), io::ErrorKind::Other);
+    assert_eq!(attempts.get(), 3);
+}
+
+#[test]
+fn not_retryable() {
+    drop(env_logger::init());
+    let mut l = t!(futures_mio::Loop::new());
+    let (action, attempts) = flaky(10);
+    let backoff = FixedBackoff::new(ms(1));
+    let retry = l.handle().retry_if(backoff, action, |e: &io::Error| {
+        e.kind() == io::ErrorKind::ConnectionRefused
+    });
+    assert!(l.run(retry).is_err());
+    assert_eq!(attempts.get(), 1);
+}
+
+#[test]
+fn exponential() {
+    let mut b = ExponentialBackoff::new(ms(10)).max_delay(ms(50));
+    let delays = (1..6).map(|n| b.next_delay(n, ms(0)).unwrap())
+                       .collect::<Vec<_>>();
+    assert_eq!(delays, [ms(10), ms(20), ms(40), ms(50), ms(50)]);
+
+    let mut b = ExponentialBackoff::new(ms(10)).factor(3);
+    assert_eq!(b.next_delay(3, ms(0)), Some(ms(90)));
+
+    // Huge attempt counts saturate rather than overflow.
+    let mut b = ExponentialBackoff::new(ms(10)).max_delay(ms(50));
+    assert_eq!(b.next_delay(u32::max_value(), ms(0)), Some(ms(50)));
+
+    let mut b = ExponentialBackoff::new(ms(100)).jitter(true);
+    for n in 1..10 {
+        let full = ms(100) * 2u32.pow(n - 1);
+        let delay = b.next_delay(n, ms(0)).unwrap();
+        assert!(delay >= full / 2 && delay <= full);
+    }
+}
+
+#[test]
+fn limits() {
+    let mut b = FixedBackoff::new(ms(10)).max_attempts(3);
+    assert_eq!(b.next_delay(1, ms(0)), Some(ms(10)));
+    assert_eq!(b.next_delay(2, ms(0)), Some(ms(10)));
+    assert_eq!(b.next_delay(3, ms(0)), None);
+
+    let mut b = FixedBackoff::new(ms(10)).max_elapsed(ms(100));
+    assert_eq!(b.next_delay(1, ms(50)), Some(ms(10)));
+    assert_eq!(b.next_delay(2, ms(90)), Some(ms(10)));
+    assert_eq!(b.next_delay(3, ms(95)), None);
+}
@@ -0,0 +1,83 @@
+#![cfg(unix)]
+
+extern crate env_logger;
+extern crate futures;
+extern crate futures_mio;
+extern crate libc;
+
+use std::sync::mpsc;
+use std::thread;
+
+use futures::Future;
+use futures::stream::Stream;
+use futures_mio::Loop;
+
+macro_rules! t {
+    ($e:expr) => (match $e {
+        Ok(e) => e,
+        Err(e) => panic!("{} failed with {:?}", stringify!($e), e),
+    })
+}
+
+// Each test uses its own signal as they all run in the same process.
+fn raise(signum: libc::c_int) {
+    assert_eq!(unsafe { libc::kill(libc::getpid(), signum) }, 0);
+}
+
+#[test]
+fn smoke() {
+    drop(env_logger::init());
+    let mut l = t!(Loop::new());
+    let signal = l.handle().signal(libc::SIGUSR1);
+    let signal = t!(l.run(signal));
+    assert_eq!(signal.signum(), libc::SIGUSR1);
+
+    raise(libc::SIGUSR1);
+    let signals = t!(l.run(signal.take(1).collect()));
+    assert_eq!(signals, [libc::SIGUSR1]);
+}
+
+#[test]
+fn multiple_on_one_loop() {
+    drop(env_logger::init());
+    let mut l = t!(Loop::new());
+    let a = l.handle().signal(libc::SIGHUP);
+    let b = l.handle().signal(libc::SIGHUP);
+    let (a, b) = t!(l.run(a.join(b)));
+
+    raise(libc::SIGHUP);
+    let both = a.take(1).collect().join(b.take(1).collect());
+    let (a, b) = t!(l.run(both));
+    assert_eq!(a, [libc::SIGHUP]);
+    assert_eq!(b, [libc::SIGHUP]);
+}
+
+#[test]
+fn multiple_loops() {
+    drop(env_logger::init());
+    let (tx, rx) = mpsc::channel();
+    let child = thread::spawn(move || {
+        let mut l = t!(Loop::new());
+        let signal = l.handle().signal(libc::SIGUSR2);
+        let signal = t!(l.run(signal));
+        tx.send(()).unwrap();
+        t!(l.run(signal.take(1).collect()))
+    });
+    rx.recv().unwrap();
+
+    let mut l = t!(Loop::new());
+    let signal = l.handle().signal(libc::SIGUSR2);
+    let signal = t!(l.run(signal));
+
+    raise(libc::SIGUSR2);
+    assert_eq!(t!(l.run(signal.take(1).collect())), [libc::SIGUSR2]);
+    assert_eq!(child.join().unwrap(), [libc::SIGUSR2]);
+}
+
+#[test]
+fn cannot_catch_sigkill() {
+    drop(env_logger::init());
+    let mut l = t!(Loop::new());
+    let signal = l.handle().signal(libc::SIGKILL);
+    assert!(l.run(signal).is_err());
+}
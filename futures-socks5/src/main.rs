@@ -60,7 +60,7 @@ use futures::stream::Stream;
 use futures_cpupool::CpuPool;
 use futures_io::{IoFuture, read_exact, write_all, Window};
 use futures_io::{TaskIo, TaskIoRead, TaskIoWrite, ReadTask, WriteTask};
-use futures_mio::{Loop, LoopData, LoopHandle, TcpStream};
+use futures_mio::{Loop, LoopData, LoopHandle, TcpStream, DeadlineError};
 
 fn main() {
     drop(env_logger::init());
@@ -454,41 +454,25 @@ impl Client {
         // feature here where we'll time out any initial connect operations
         // which take too long.
         //
-        // Here we create a timeout future, using the `LoopHandle::timeout`
-        // method, which will create a future that will resolve to `()` in 10
-        // seconds. We then apply this timeout to the entire handshake all at
-        // once by performing a `select` between the timeout and the handshake
-        // itself.
-        let timeout = self.handle.clone().timeout(Duration::new(10, 0));
-        let pair = timeout.and_then(|timeout| {
-            handshake_finish.map(Ok).select(timeout.map(Err)).then(|res| {
-                match res {
-                    // The handshake finished before the timeout fired, so we
-                    // drop the future representing the timeout, canceling the
-                    // timeout, and then return the pair of connections the
-                    // handshake resolved with.
-                    Ok((Ok(pair), _timeout)) => Ok(pair),
-
-                    // The timeout fired before the handshake finished. In this
-                    // case we drop the future representing the handshake, which
-                    // cleans up the associated connection and all other
-                    // resources.
-                    //
-                    // This automatically "cancels" any I/O associated with the
-                    // handshake: reads, writes, TCP connects, etc. All of those
-                    // I/O resources are owned by the future, so if we drop the
-                    // future they're all released!
-                    Ok((Err(()), _handshake)) => {
-                        Err(other("timeout during handshake"))
-                    }
-
-                    // One of the futures (handshake or timeout) hit an error
-                    // along the way. We're not entirely sure which at this
-                    // point, but in any case that shouldn't happen, so we just
-                    // keep propagating along the error.
-                    Err((e, _other)) => Err(e),
-                }
-            })
+        // Here we apply a deadline to the entire handshake all at once with
+        // the `LoopHandle::deadline` method, which creates a future that
+        // resolves to the handshake's result, or to an error if it doesn't
+        // finish within 10 seconds.
+        //
+        // If the deadline passes first, the future representing the
+        // handshake is dropped, which cleans up the associated connection and
+        // all other resources. This automatically "cancels" any I/O
+        // associated with the handshake: reads, writes, TCP connects, etc.
+        // All of those I/O resources are owned by the future, so if we drop
+        // the future they're all released! If instead the handshake finishes
+        // first, the timeout is canceled right away.
+        let dur = Duration::new(10, 0);
+        let deadline = self.handle.clone().deadline(handshake_finish, dur);
+        let pair = deadline.map_err(|e| {
+            match e {
+                DeadlineError::TimedOut => other("timeout during handshake"),
+                e => e.into(),
+            }
         }).boxed();
 
         // At this point we've *actually* finished the handshake. Not only have
@@ -0,0 +1,187 @@
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use {Future, Task, TaskHandle, Poll};
+
+/// A future which can be remotely aborted through an `AbortHandle`.
+///
+/// This is created by the `abortable` function.
+pub struct Abortable<F> {
+    inner: Arc<Inner<F>>,
+    done: bool,
+}
+
+/// A handle which can be used to abort an `Abortable` future.
+///
+/// This handle can be cloned and sent to other threads, and any clone can be
+/// used to abort the associated future.
+#[derive(Clone)]
+pub struct AbortHandle {
+    inner: Arc<Abort>,
+}
+
+// The underlying future lives in here rather than in `Abortable` itself so
+// that `AbortHandle::abort` can drop it.
+struct Inner<F> {
+    aborted: AtomicBool,
+    task: Mutex<Option<TaskHandle>>,
+    future: Mutex<Option<F>>,
+}
+
+// Erases the type of the future from `AbortHandle`.
+trait Abort: Send + Sync {
+    fn abort(&self);
+    fn is_aborted(&self) -> bool;
+}
+
+/// Error returned from an `Abortable` future.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AbortError<E> {
+    /// The future was aborted through its `AbortHandle`.
+    Aborted,
+
+    /// The underlying future failed with this error.
+    Inner(E),
+}
+
+fn _assert() {
+    fn _assert_send<T: Send>() {}
+    fn _assert_sync<T: Sync>() {}
+    _assert_send::<AbortHandle>();
+    _assert_sync::<AbortHandle>();
+}
+
+/// Wraps a future so it can be aborted from elsewhere, returning the wrapped
+/// future along with a handle to abort it.
+///
+/// The returned `Abortable` future behaves exactly like `future` until the
+/// `AbortHandle` is used. Once `AbortHandle::abort` is called the underlying
+/// future is dropped, and the task driving the future is notified so that the
+/// next time it is polled `AbortError::Aborted` is returned. If the future
+/// happens to be in the middle of being polled on another thread, it's
+/// instead dropped as soon as that poll returns.
+///
+/// This is useful for canceling work that has been handed off with `forget`,
+/// as the handle can be kept around and triggered from any thread.
+///
+/// The future must be `Send` because `AbortHandle::abort` drops it on
+/// whichever thread the handle is used from. Futures which can't leave their
+/// thread, such as those run on a `LocalPool` or built on the `unsync`
+/// channels, therefore can't be made abortable.
+///
+/// # Examples
+///
+/// ```
+/// use futures::*;
+///
+/// let (mut future, handle) = abortable(empty::<u32, u32>());
+/// let mut task = Task::new();
+/// assert!(future.poll(&mut task).is_not_ready());
+///
+/// handle.abort();
+/// assert_eq!(future.poll(&mut task), Poll::Err(AbortError::Aborted));
+/// ```
+pub fn abortable<F>(future: F) -> (Abortable<F>, AbortHandle)
+    where F: Future + Send,
+{
+    let inner = Arc::new(Inner {
+        aborted: AtomicBool::new(false),
+        task: Mutex::new(None),
+        future: Mutex::new(Some(future)),
+    });
+    let future = Abortable { inner: inner.clone(), done: false };
+    (future, AbortHandle { inner: inner })
+}
+
+impl AbortHandle {
+    /// Aborts the associated `Abortable` future.
+    ///
+    /// The underlying future is dropped right away, releasing its resources,
+    /// and the task driving it, if any, is notified so the future resolves
+    /// with `AbortError::Aborted`. Calling this more than once has no further
+    /// effect.
+    pub fn abort(&self) {
+        self.inner.abort()
+    }
+
+    /// Returns whether `abort` has been called on this or any other handle to
+    /// the same future.
+    pub fn is_aborted(&self) -> bool {
+        self.inner.is_aborted()
+    }
+}
+
+impl<F: Send> Abort for Inner<F> {
+    fn abort(&self) {
+        self.aborted.store(true, Ordering::SeqCst);
+
+        // If the future is being polled right now the poll will drop it once
+        // it sees the flag, so only drop it here if it's free.
+        let future = match self.future.try_lock() {
+            Ok(mut future) => future.take(),
+            Err(_) => None,
+        };
+        drop(future);
+
+        let task = self.task.lock().unwrap().take();
+        if let Some(task) = task {
+            task.notify();
+        }
+    }
+
+    fn is_aborted(&self) -> bool {
+        self.aborted.load(Ordering::SeqCst)
+    }
+}
+
+impl<F: Future> Future for Abortable<F> {
+    type Item = F::Item;
+    type Error = AbortError<F::Error>;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<F::Item, AbortError<F::Error>> {
+        if self.done {
+            panic!("cannot poll Abortable twice")
+        }
+        let mut future = self.inner.future.lock().unwrap();
+        if !self.inner.aborted.load(Ordering::SeqCst) {
+            // `abort` sets the flag before dropping the future, so it's still
+            // here.
+            let res = future.as_mut().unwrap().poll(task)
+                            .map_err(AbortError::Inner);
+            if !res.is_not_ready() {
+                self.done = true;
+                *future = None;
+                return res
+            }
+            // `abort` may have run during the poll and left the future for us
+            // to drop.
+            if !self.inner.aborted.load(Ordering::SeqCst) {
+                return res
+            }
+        }
+        self.done = true;
+        *future = None;
+        Poll::Err(AbortError::Aborted)
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        // Store our task before checking the flag, `abort` does the opposite,
+        // so one of us is guaranteed to see the other.
+        *self.inner.task.lock().unwrap() = Some(task.handle().clone());
+        if self.inner.aborted.load(Ordering::SeqCst) {
+            return task.notify()
+        }
+        if let Some(ref mut f) = *self.inner.future.lock().unwrap() {
+            f.schedule(task);
+        }
+    }
+}
+
+impl<F> Drop for Abortable<F> {
+    fn drop(&mut self) {
+        // Handles may outlive us, but the future shouldn't.
+        if let Ok(mut future) = self.inner.future.lock() {
+            future.take();
+        }
+    }
+}
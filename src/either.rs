@@ -0,0 +1,85 @@
+use {Future, Task, Poll};
+use stream::Stream;
+
+/// Combines two different futures or streams yielding the same item and error
+/// types into a single type.
+///
+/// This is useful when different branches of a computation produce different
+/// concrete futures, as both branches can return an `Either` instead of
+/// allocating a trait object.
+///
+/// # Examples
+///
+/// ```
+/// use futures::*;
+///
+/// fn parse(s: &str) -> Either<Done<u32, u32>, Failed<u32, u32>> {
+///     match s.parse() {
+///         Ok(n) => Either::A(done(Ok(n))),
+///         Err(_) => Either::B(failed(0)),
+///     }
+/// }
+///
+/// let mut task = Task::new();
+/// assert_eq!(parse("3").poll(&mut task), Poll::Ok(3));
+/// assert_eq!(parse("x").poll(&mut task), Poll::Err(0));
+/// ```
+#[derive(Debug)]
+pub enum Either<A, B> {
+    /// The first branch of the type.
+    A(A),
+    /// The second branch of the type.
+    B(B),
+}
+
+impl<A, B> Future for Either<A, B>
+    where A: Future,
+          B: Future<Item=A::Item, Error=A::Error>,
+{
+    type Item = A::Item;
+    type Error = A::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<A::Item, A::Error> {
+        match *self {
+            Either::A(ref mut a) => a.poll(task),
+            Either::B(ref mut b) => b.poll(task),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match *self {
+            Either::A(ref mut a) => a.schedule(task),
+            Either::B(ref mut b) => b.schedule(task),
+        }
+    }
+
+    unsafe fn tailcall(&mut self)
+                       -> Option<Box<Future<Item=Self::Item, Error=Self::Error>>> {
+        match *self {
+            Either::A(ref mut a) => a.tailcall(),
+            Either::B(ref mut b) => b.tailcall(),
+        }
+    }
+}
+
+impl<A, B> Stream for Either<A, B>
+    where A: Stream,
+          B: Stream<Item=A::Item, Error=A::Error>,
+{
+    type Item = A::Item;
+    type Error = A::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<A::Item>, A::Error> {
+        match *self {
+            Either::A(ref mut a) => a.poll(task),
+            Either::B(ref mut b) => b.poll(task),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match *self {
+            Either::A(ref mut a) => a.schedule(task),
+            Either::B(ref mut b) => b.schedule(task),
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use std::cell::RefCell;
+use std::mem;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, Thread};
+
+use {Future, Task, Poll, EventSet, UnparkEvent};
+use task;
+use util::Collapsed;
+
+// The event id used for the future passed to `LocalPool::run_until`, all other
+// ids are indices into the pool's list of tasks.
+const MAIN: usize = !0;
+
+type LocalFuture = Box<Future<Item=(), Error=()>>;
+
+/// A single-threaded executor for futures which are not necessarily `Send`.
+///
+/// A `LocalPool` owns any number of futures, each driven by its own `Task`,
+/// and polls them all on the thread that created the pool. Futures are only
+/// polled when they've generated a notification, and the thread is parked
+/// while none of them can make progress. Notifications may come from any
+/// thread, but polling always happens inside of `run` or `run_until`.
+///
+/// New futures can be added with `LocalPool::spawn` or through a
+/// `LocalSpawner` handle, which futures running on the pool can hold on to in
+/// order to spawn more work.
+///
+/// Note that this executor cannot honor requests made through
+/// `Task::poll_on`, and will panic if a future makes one.
+pub struct LocalPool {
+    tasks: Vec<Option<(Task, Collapsed<LocalFuture>)>>,
+    free: Vec<usize>,
+    len: usize,
+    spawner: LocalSpawner,
+    ready: Arc<ReadySet>,
+}
+
+/// A handle to a `LocalPool` which can be used to spawn new futures onto it.
+///
+/// Handles can be cloned and held by futures running on the pool itself. As
+/// the pool is single-threaded, this handle is not `Send`.
+///
+/// This is created by the `LocalPool::spawner` method.
+#[derive(Clone)]
+pub struct LocalSpawner {
+    pending: Rc<RefCell<Vec<LocalFuture>>>,
+}
+
+// The list of tasks in a `LocalPool` which have been notified, along with the
+// thread to unpark when a notification comes in.
+struct ReadySet {
+    ids: Mutex<Vec<usize>>,
+    thread: Thread,
+}
+
+impl LocalPool {
+    /// Creates a new, empty pool of futures which will run on the current
+    /// thread.
+    pub fn new() -> LocalPool {
+        LocalPool {
+            tasks: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            spawner: LocalSpawner {
+                pending: Rc::new(RefCell::new(Vec::new())),
+            },
+            ready: Arc::new(ReadySet {
+                ids: Mutex::new(Vec::new()),
+                thread: thread::current(),
+            }),
+        }
+    }
+
+    /// Returns a handle which can be used to spawn futures onto this pool.
+    pub fn spawner(&self) -> LocalSpawner {
+        self.spawner.clone()
+    }
+
+    /// Spawns a future onto this pool.
+    ///
+    /// The future will not be polled until the pool is next run with either
+    /// `run` or `run_until`.
+    pub fn spawn<F>(&self, future: F)
+        where F: Future<Item=(), Error=()>,
+    {
+        self.spawner.spawn(future)
+    }
+
+    /// Runs all futures spawned onto this pool until they've all completed.
+    ///
+    /// This includes any futures spawned while the pool is running. The
+    /// current thread will be blocked whenever none of the futures are ready
+    /// to make progress.
+    ///
+    /// # Panics
+    ///
+    /// If any future panics then the panic is propagated out of this method,
+    /// leaving the remaining futures in the pool.
+    pub fn run(&mut self) {
+        loop {
+            self.run_ready();
+            if self.len == 0 && self.spawner.pending.borrow().is_empty() {
+                return
+            }
+            self.ready.park();
+        }
+    }
+
+    /// Runs the futures in this pool until the future `f` has completed,
+    /// returning its result.
+    ///
+    /// The future `f` is driven on the current thread alongside all other
+    /// futures in the pool, and it does not need to be `Send` either. Any
+    /// futures which are still pending when `f` resolves are left in the pool
+    /// and will make progress the next time it's run.
+    ///
+    /// # Panics
+    ///
+    /// If `f` or any future in the pool panics then the panic is propagated
+    /// out of this method.
+    pub fn run_until<F>(&mut self, mut f: F) -> Result<F::Item, F::Error>
+        where F: Future,
+    {
+        let mut task = Task::new();
+        let event = UnparkEvent::new(self.ready.clone(), MAIN);
+        loop {
+            match poll_on(&mut task, &event, |task| f.poll(task)) {
+                Poll::Ok(e) => return Ok(e),
+                Poll::Err(e) => return Err(e),
+                Poll::NotReady => {}
+            }
+            poll_on(&mut task, &event, |task| f.schedule(task));
+
+            while !self.run_ready() {
+                self.ready.park();
+            }
+        }
+    }
+
+    // Polls every task which has been notified, along with any newly spawned
+    // tasks, returning whether the `run_until` future was notified.
+    fn run_ready(&mut self) -> bool {
+        let mut main = false;
+        loop {
+            let pending = mem::replace(&mut *self.spawner.pending.borrow_mut(),
+                                       Vec::new());
+            for future in pending {
+                self.insert(future);
+            }
+
+            let ready = mem::replace(&mut *self.ready.ids.lock().unwrap(),
+                                     Vec::new());
+            if ready.is_empty() {
+                return main
+            }
+            for id in ready {
+                if id == MAIN {
+                    main = true;
+                } else {
+                    self.poll_task(id);
+                }
+            }
+        }
+    }
+
+    fn poll_task(&mut self, id: usize) {
+        let done = match self.tasks[id] {
+            Some((ref mut task, ref mut future)) => {
+                let event = UnparkEvent::new(self.ready.clone(), id);
+                match poll_on(task, &event, |task| future.poll(task)) {
+                    Poll::NotReady => {
+                        future.collapse();
+                        poll_on(task, &event, |task| future.schedule(task));
+                        false
+                    }
+                    Poll::Ok(()) | Poll::Err(()) => true,
+                }
+            }
+            // This task already finished and this is just a stale
+            // notification.
+            None => false,
+        };
+        if done {
+            self.tasks[id] = None;
+            self.free.push(id);
+            self.len -= 1;
+        }
+    }
+
+    fn insert(&mut self, future: LocalFuture) {
+        let task = Some((Task::new(), Collapsed::Start(future)));
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.tasks[id] = task;
+                id
+            }
+            None => {
+                self.tasks.push(task);
+                self.tasks.len() - 1
+            }
+        };
+        self.len += 1;
+        self.ready.insert(id);
+    }
+}
+
+fn poll_on<F, R>(task: &mut Task, event: &UnparkEvent, f: F) -> R
+    where F: FnOnce(&mut Task) -> R
+{
+    let ret = task.with_unpark_event(event.clone(), f);
+    assert!(!task::has_poll_requests(task),
+            "a future on a `LocalPool` requested to be polled on a specific \
+             executor");
+    ret
+}
+
+impl LocalSpawner {
+    /// Spawns a future onto the associated pool.
+    ///
+    /// The future will be polled the next time the pool is run, or as part of
+    /// the current run if it's already running. If the pool has since been
+    /// dropped then the future will never be polled.
+    pub fn spawn<F>(&self, future: F)
+        where F: Future<Item=(), Error=()>,
+    {
+        self.pending.borrow_mut().push(Box::new(future));
+    }
+}
+
+impl ReadySet {
+    fn park(&self) {
+        while self.ids.lock().unwrap().is_empty() {
+            thread::park();
+        }
+    }
+}
+
+impl EventSet for ReadySet {
+    fn insert(&self, id: usize) {
+        self.ids.lock().unwrap().push(id);
+        self.thread.unpark();
+    }
+}
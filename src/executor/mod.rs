@@ -6,6 +6,11 @@
 use std::cell::{Cell, RefCell};
 use std::sync::Arc;
 
+mod local_pool;
+mod test_executor;
+pub use self::local_pool::{LocalPool, LocalSpawner};
+pub use self::test_executor::TestExecutor;
+
 /// Encapsulation of a value which has the ability to execute arbitrary code.
 ///
 /// This trait is object safe and intended to be used through pointers like
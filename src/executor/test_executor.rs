@@ -0,0 +1,211 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use {Future, Task, Poll, EventSet, UnparkEvent};
+use task;
+use util::Collapsed;
+
+type TestFuture = Box<Future<Item=(), Error=()>>;
+
+/// A deterministic executor intended for testing implementations of `Future`
+/// and `Stream`.
+///
+/// Futures spawned onto a `TestExecutor` are never polled behind the test's
+/// back. Instead every `TaskHandle::notify` for a future is recorded, and the
+/// test decides when to poll with `step` or `run`. This makes it possible to
+/// check precisely which futures were woken up, how many times, and how many
+/// polls it took for each one to complete.
+///
+/// Each time a future returns `NotReady` it is scheduled, and the executor
+/// then checks that the future arranged to be woken up. That is, either it
+/// was notified while being polled or scheduled, or a handle to its task was
+/// cloned and is still alive somewhere. If neither is the case the future
+/// could never make progress again, and the executor will panic to flag the
+/// lost wakeup. Note that this means futures which never resolve on purpose,
+/// like `empty`, can't be run on this executor.
+///
+/// Futures are identified by the index returned from `spawn`.
+pub struct TestExecutor {
+    entries: Vec<Entry>,
+    events: Arc<Events>,
+}
+
+struct Entry {
+    task: Task,
+    future: Option<Collapsed<TestFuture>>,
+    polls: usize,
+}
+
+struct Events {
+    state: Mutex<EventsState>,
+}
+
+struct EventsState {
+    notifications: Vec<usize>,
+    ready: VecDeque<usize>,
+}
+
+impl TestExecutor {
+    /// Creates a new executor with no futures.
+    pub fn new() -> TestExecutor {
+        TestExecutor {
+            entries: Vec::new(),
+            events: Arc::new(Events {
+                state: Mutex::new(EventsState {
+                    notifications: Vec::new(),
+                    ready: VecDeque::new(),
+                }),
+            }),
+        }
+    }
+
+    /// Adds a future to this executor, returning the index used to refer to
+    /// it.
+    ///
+    /// The future starts out as notified, so it will be polled by the next
+    /// call to `step`. This initial notification is not counted by
+    /// `notifications`.
+    pub fn spawn<F>(&mut self, future: F) -> usize
+        where F: Future<Item=(), Error=()>,
+    {
+        let id = self.entries.len();
+        self.entries.push(Entry {
+            task: Task::new(),
+            future: Some(Collapsed::Start(Box::new(future))),
+            polls: 0,
+        });
+        let mut state = self.events.state.lock().unwrap();
+        state.notifications.push(0);
+        state.ready.push_back(id);
+        id
+    }
+
+    /// Polls the next future which has been notified, returning its index.
+    ///
+    /// Futures are polled in the order in which they were notified, and a
+    /// future notified multiple times before being polled is only polled once.
+    /// Notifications for futures which have already completed are discarded.
+    /// If no future has been notified then `None` is returned and nothing is
+    /// polled.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the future returns `NotReady` without
+    /// arranging to be notified, or if it requests to be polled on another
+    /// executor with `Task::poll_on`.
+    pub fn step(&mut self) -> Option<usize> {
+        loop {
+            let id = match self.events.state.lock().unwrap().ready.pop_front() {
+                Some(id) => id,
+                None => return None,
+            };
+            if self.entries[id].future.is_some() {
+                self.poll(id);
+                return Some(id)
+            }
+        }
+    }
+
+    /// Calls `step` until no futures are notified, returning the number of
+    /// polls performed.
+    pub fn run(&mut self) -> usize {
+        let mut polls = 0;
+        while self.step().is_some() {
+            polls += 1;
+        }
+        polls
+    }
+
+    /// Manually marks the future `id` as notified, as if its task had been
+    /// woken up.
+    ///
+    /// This can be used to simulate spurious wakeups. Like the initial
+    /// notification from `spawn`, this is not counted by `notifications`.
+    pub fn notify(&self, id: usize) {
+        assert!(id < self.entries.len(), "no future with index {}", id);
+        self.events.state.lock().unwrap().mark_ready(id);
+    }
+
+    /// Returns whether the future `id` has been notified and is waiting to be
+    /// polled.
+    pub fn is_notified(&self, id: usize) -> bool {
+        self.events.state.lock().unwrap().ready.contains(&id)
+    }
+
+    /// Returns the number of times the task of future `id` has been notified
+    /// through a `TaskHandle`.
+    pub fn notifications(&self, id: usize) -> usize {
+        self.events.state.lock().unwrap().notifications[id]
+    }
+
+    /// Returns the number of times the future `id` has been polled.
+    pub fn polls(&self, id: usize) -> usize {
+        self.entries[id].polls
+    }
+
+    /// Returns whether the future `id` has completed.
+    ///
+    /// Completed futures are dropped immediately.
+    pub fn is_done(&self, id: usize) -> bool {
+        self.entries[id].future.is_none()
+    }
+
+    fn poll(&mut self, id: usize) {
+        let before = self.notifications(id);
+        let event = UnparkEvent::new(self.events.clone(), id);
+        let entry = &mut self.entries[id];
+        entry.polls += 1;
+
+        let done = {
+            let task = &mut entry.task;
+            let future = entry.future.as_mut().unwrap();
+            match with_event(task, &event, |task| future.poll(task)) {
+                Poll::Ok(()) | Poll::Err(()) => true,
+                Poll::NotReady => {
+                    future.collapse();
+                    with_event(task, &event, |task| future.schedule(task));
+                    false
+                }
+            }
+        };
+        if done {
+            entry.future = None;
+            return
+        }
+
+        // The only strong reference to the task's handle is the one in the
+        // task itself, so nothing could possibly wake it up.
+        let notified = self.events.state.lock().unwrap()
+                           .notifications[id] > before;
+        if !notified && task::handle_count(&entry.task) == 1 {
+            panic!("future {} returned NotReady without arranging to be \
+                    notified", id);
+        }
+    }
+}
+
+fn with_event<F, R>(task: &mut Task, event: &UnparkEvent, f: F) -> R
+    where F: FnOnce(&mut Task) -> R
+{
+    let ret = task.with_unpark_event(event.clone(), f);
+    assert!(!task::has_poll_requests(task),
+            "a future on a `TestExecutor` requested to be polled on a \
+             specific executor");
+    ret
+}
+
+impl EventsState {
+    fn mark_ready(&mut self, id: usize) {
+        if !self.ready.contains(&id) {
+            self.ready.push_back(id);
+        }
+    }
+}
+
+impl EventSet for Events {
+    fn insert(&self, id: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.notifications[id] += 1;
+        state.mark_ready(id);
+    }
+}
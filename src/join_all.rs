@@ -0,0 +1,248 @@
+use {Future, IntoFuture, Task, Poll};
+use util::Collapsed;
+
+/// A future which takes a list of futures and resolves with a vector of the
+/// completed values, failing as soon as any future fails.
+///
+/// This future is created with the `join_all` function.
+pub struct JoinAll<A> where A: Future {
+    elems: Option<Vec<ElemState<A>>>,
+}
+
+/// A future which takes a list of futures and waits for all of them to
+/// complete, collecting every error that happens along the way.
+///
+/// This future is created with the `join_all_errors` function.
+pub struct JoinAllErrors<A> where A: Future {
+    elems: Option<Vec<ElemState<A>>>,
+}
+
+enum ElemState<A> where A: Future {
+    Pending(Collapsed<A>),
+    Done(Result<A::Item, A::Error>),
+}
+
+/// Creates a future which represents a collection of the results of the futures
+/// given, running all of them concurrently.
+///
+/// Unlike `collect`, the returned future will poll all of the underlying
+/// futures at the same time rather than one after another. Once all of them
+/// have completed successfully the future resolves to a `Vec` of their values,
+/// in the same order as the futures were given.
+///
+/// This future fails fast. As soon as any future returns an error all other
+/// futures and all values collected so far are dropped, and the error is
+/// returned immediately. If all errors should be seen instead, use
+/// `join_all_errors`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::*;
+///
+/// let f = join_all(vec![
+///     finished::<u32, u32>(1),
+///     finished::<u32, u32>(2),
+///     finished::<u32, u32>(3),
+/// ]);
+/// let f = f.map(|x| {
+///     assert_eq!(x, [1, 2, 3]);
+/// });
+///
+/// let f = join_all(vec![
+///     finished::<u32, u32>(1).boxed(),
+///     failed::<u32, u32>(2).boxed(),
+///     finished::<u32, u32>(3).boxed(),
+/// ]);
+/// let f = f.then(|x| {
+///     assert_eq!(x, Err(2));
+///     x
+/// });
+/// ```
+pub fn join_all<I>(i: I) -> JoinAll<<I::Item as IntoFuture>::Future>
+    where I: IntoIterator,
+          I::Item: IntoFuture,
+{
+    JoinAll { elems: Some(new_elems(i)) }
+}
+
+/// Creates a future which waits for all of the futures given to complete,
+/// collecting either all of their values or all of their errors.
+///
+/// Like `join_all`, all futures are run concurrently. This function, however,
+/// will not stop when one future fails. Instead, every future is run to
+/// completion. If they all succeed then the returned future resolves to a
+/// `Vec` of their values, and otherwise it fails with a `Vec` of every error
+/// that happened, in the same order as the futures were given.
+///
+/// # Examples
+///
+/// ```
+/// use futures::*;
+///
+/// let f = join_all_errors(vec![
+///     finished::<u32, u32>(1).boxed(),
+///     failed::<u32, u32>(2).boxed(),
+///     failed::<u32, u32>(3).boxed(),
+/// ]);
+/// let f = f.then(|x| {
+///     assert_eq!(x, Err(vec![2, 3]));
+///     x
+/// });
+/// ```
+pub fn join_all_errors<I>(i: I) -> JoinAllErrors<<I::Item as IntoFuture>::Future>
+    where I: IntoIterator,
+          I::Item: IntoFuture,
+{
+    JoinAllErrors { elems: Some(new_elems(i)) }
+}
+
+fn new_elems<I>(i: I) -> Vec<ElemState<<I::Item as IntoFuture>::Future>>
+    where I: IntoIterator,
+          I::Item: IntoFuture,
+{
+    i.into_iter()
+     .map(IntoFuture::into_future)
+     .map(Collapsed::Start)
+     .map(ElemState::Pending)
+     .collect()
+}
+
+// Polls all pending futures, returning whether they've all completed. If
+// `fail_fast` is set then the first error seen is returned immediately,
+// otherwise errors are stored alongside successful values.
+fn poll_elems<A>(elems: &mut [ElemState<A>],
+                 task: &mut Task,
+                 fail_fast: bool) -> Result<bool, A::Error>
+    where A: Future,
+{
+    let mut all_done = true;
+    for elem in elems.iter_mut() {
+        let result = match *elem {
+            ElemState::Pending(ref mut f) => {
+                match f.poll(task) {
+                    Poll::Ok(e) => Ok(e),
+                    Poll::Err(e) => {
+                        if fail_fast {
+                            return Err(e)
+                        }
+                        Err(e)
+                    }
+                    Poll::NotReady => {
+                        all_done = false;
+                        continue
+                    }
+                }
+            }
+            ElemState::Done(_) => continue,
+        };
+        *elem = ElemState::Done(result);
+    }
+    Ok(all_done)
+}
+
+fn schedule_elems<A: Future>(elems: &mut [ElemState<A>], task: &mut Task) {
+    for elem in elems.iter_mut() {
+        if let ElemState::Pending(ref mut f) = *elem {
+            f.schedule(task);
+        }
+    }
+}
+
+fn collapse_elems<A: Future>(elems: &mut [ElemState<A>]) {
+    for elem in elems.iter_mut() {
+        if let ElemState::Pending(ref mut f) = *elem {
+            f.collapse();
+        }
+    }
+}
+
+impl<A> Future for JoinAll<A> where A: Future {
+    type Item = Vec<A::Item>;
+    type Error = A::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Self::Item, Self::Error> {
+        let res = match self.elems {
+            Some(ref mut elems) => poll_elems(elems, task, true),
+            None => panic!("cannot poll JoinAll twice"),
+        };
+        match res {
+            Ok(true) => {}
+            Ok(false) => return Poll::NotReady,
+
+            // If we hit an error, drop all our associated resources ASAP.
+            Err(e) => {
+                self.elems = None;
+                return Poll::Err(e)
+            }
+        }
+
+        let elems = self.elems.take().unwrap();
+        Poll::Ok(elems.into_iter().map(|e| {
+            match e {
+                ElemState::Done(Ok(e)) => e,
+                _ => panic!(),
+            }
+        }).collect())
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if let Some(ref mut elems) = self.elems {
+            schedule_elems(elems, task)
+        }
+    }
+
+    unsafe fn tailcall(&mut self)
+                       -> Option<Box<Future<Item=Self::Item, Error=Self::Error>>> {
+        if let Some(ref mut elems) = self.elems {
+            collapse_elems(elems);
+        }
+        None
+    }
+}
+
+impl<A> Future for JoinAllErrors<A> where A: Future {
+    type Item = Vec<A::Item>;
+    type Error = Vec<A::Error>;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Self::Item, Self::Error> {
+        let res = match self.elems {
+            Some(ref mut elems) => poll_elems(elems, task, false),
+            None => panic!("cannot poll JoinAllErrors twice"),
+        };
+        match res {
+            Ok(true) => {}
+            Ok(false) => return Poll::NotReady,
+            Err(_) => panic!(), // we're not failing fast
+        }
+
+        let mut items = Vec::new();
+        let mut errors = Vec::new();
+        for elem in self.elems.take().unwrap() {
+            match elem {
+                ElemState::Done(Ok(e)) => items.push(e),
+                ElemState::Done(Err(e)) => errors.push(e),
+                ElemState::Pending(_) => panic!(),
+            }
+        }
+        if errors.is_empty() {
+            Poll::Ok(items)
+        } else {
+            Poll::Err(errors)
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if let Some(ref mut elems) = self.elems {
+            schedule_elems(elems, task)
+        }
+    }
+
+    unsafe fn tailcall(&mut self)
+                       -> Option<Box<Future<Item=Self::Item, Error=Self::Error>>> {
+        if let Some(ref mut elems) = self.elems {
+            collapse_elems(elems);
+        }
+        None
+    }
+}
@@ -164,9 +164,16 @@ mod poll;
 pub use poll::Poll;
 
 mod task;
-pub use task::{Task, TaskData, TaskHandle};
+pub use task::{Task, TaskData, TaskHandle, EventSet, UnparkEvent};
+
+#[macro_use]
+mod task_local;
+pub use task_local::{LocalKey, LocalScope};
 
 pub mod executor;
+pub mod sink;
+pub mod sync;
+pub mod unsync;
 
 // Primitive futures
 mod collect;
@@ -175,7 +182,9 @@ mod empty;
 mod failed;
 mod finished;
 mod lazy;
+mod loop_fn;
 mod oneshot;
+mod spawn;
 mod store;
 pub use collect::{collect, Collect};
 pub use done::{done, Done};
@@ -183,30 +192,42 @@ pub use empty::{empty, Empty};
 pub use failed::{failed, Failed};
 pub use finished::{finished, Finished};
 pub use lazy::{lazy, Lazy};
+pub use loop_fn::{loop_fn, Loop, LoopFn};
 #[allow(deprecated)]
 pub use oneshot::{oneshot, promise, Oneshot, Promise, Complete, Canceled};
+pub use spawn::{spawn, JoinHandle, JoinError};
 pub use store::{store, Store};
 
 // combinators
+mod abortable;
 mod and_then;
+mod either;
 mod flatten;
 mod fuse;
 mod join;
+mod join_all;
 mod map;
 mod map_err;
 mod or_else;
 mod select;
 mod select_all;
+mod select_ok;
+mod shared;
 mod then;
+pub use abortable::{abortable, Abortable, AbortHandle, AbortError};
 pub use and_then::AndThen;
+pub use either::Either;
 pub use flatten::Flatten;
 pub use fuse::Fuse;
 pub use join::{Join, Join3, Join4, Join5};
+pub use join_all::{join_all, join_all_errors, JoinAll, JoinAllErrors};
 pub use map::Map;
 pub use map_err::MapErr;
 pub use or_else::OrElse;
 pub use select::{Select, SelectNext};
 pub use select_all::{SelectAll, SelectAllNext, select_all};
+pub use select_ok::{SelectOk, select_ok};
+pub use shared::Shared;
 pub use then::Then;
 
 // streams
@@ -216,6 +237,7 @@ pub mod stream;
 mod chain;
 mod impls;
 mod forget;
+mod wait;
 
 /// Trait for types which represent a placeholder of a value that will become
 /// available at possible some later point in time.
@@ -800,6 +822,69 @@ pub trait Future: 'static {
         assert_future::<Self::Item, Self::Error, _>(f)
     }
 
+    /// Create a cloneable handle to this future where all handles will resolve
+    /// to the same result.
+    ///
+    /// The returned `Shared` future can be cloned any number of times, and
+    /// each clone can be polled and scheduled from a different task. The
+    /// underlying future is only driven once, and when it completes every
+    /// task waiting on a clone is notified. Each clone then resolves to a
+    /// reference-counted copy of the item or error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::*;
+    ///
+    /// let future = finished::<u32, u32>(6).shared();
+    /// let mut a = future.clone();
+    /// let mut b = future.map(|x| *x + 1);
+    ///
+    /// let mut task = Task::new();
+    /// assert_eq!(*a.poll(&mut task).unwrap().unwrap(), 6);
+    /// assert_eq!(b.poll(&mut task).unwrap(), Ok(7));
+    /// ```
+    fn shared(self) -> Shared<Self>
+        where Self: Sized
+    {
+        let f = shared::new(self);
+        assert_future::<std::sync::Arc<Self::Item>,
+                        std::sync::Arc<Self::Error>, _>(f)
+    }
+
+    /// Block the current thread until this future is resolved.
+    ///
+    /// This method will consume ownership of this future, driving it to
+    /// completion via `poll` and blocking the current thread while it's waiting
+    /// for the value to become available. The future is polled on the current
+    /// thread with a fresh `Task`, and notifications to that task will unpark
+    /// this thread so the future can be polled again.
+    ///
+    /// Note that this method should not be called from within an event loop
+    /// or any other context where blocking is not allowed, as it may deadlock.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the future requests to be polled on a
+    /// different executor via `Task::poll_on`, as it can only ever poll the
+    /// future on the current thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use futures::*;
+    ///
+    /// let (c, p) = oneshot::<u32>();
+    /// thread::spawn(|| c.complete(3));
+    /// assert_eq!(p.map(|x| x + 1).wait(), Ok(4));
+    /// ```
+    fn wait(self) -> Result<Self::Item, Self::Error>
+        where Self: Sized
+    {
+        wait::wait(self)
+    }
+
     /// Consume this future drive it to completion.
     ///
     /// This function is one of the primary methods of driving a future
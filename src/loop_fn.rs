@@ -0,0 +1,93 @@
+use {Future, IntoFuture, Task, Poll};
+use util::Collapsed;
+
+/// The status of a `loop_fn` loop.
+#[derive(Debug)]
+pub enum Loop<T, S> {
+    /// Indicates that the loop has completed with output `T`.
+    Break(T),
+
+    /// Indicates that the loop function should be called again with input
+    /// state `S`.
+    Continue(S),
+}
+
+/// A future implementing a tail-recursive loop.
+///
+/// This is created by the `loop_fn` function.
+pub struct LoopFn<A, F> where A: IntoFuture {
+    future: Collapsed<A::Future>,
+    func: F,
+}
+
+/// Creates a new future implementing a tail-recursive loop.
+///
+/// The loop function is immediately called with `initial_state` and should
+/// return a value that can be converted to a future. On successful completion,
+/// this future should output a `Loop<T, S>` to indicate the status of the
+/// loop.
+///
+/// `Loop::Break(T)` halts the loop and completes the future with output `T`.
+///
+/// `Loop::Continue(S)` reinvokes the loop function with state `S`. The
+/// returned future will be subsequently polled for a new `Loop<T, S>` value.
+///
+/// Each iteration replaces the previous future in place, so a loop which runs
+/// for many iterations neither allocates nor grows the stack, unlike a
+/// recursive chain of boxed `and_then` calls.
+///
+/// # Examples
+///
+/// ```
+/// use futures::*;
+///
+/// let mut countdown = loop_fn(10, |n| {
+///     if n == 0 {
+///         Ok::<_, u32>(Loop::Break("liftoff"))
+///     } else {
+///         Ok(Loop::Continue(n - 1))
+///     }
+/// });
+/// assert_eq!(countdown.poll(&mut Task::new()), Poll::Ok("liftoff"));
+/// ```
+pub fn loop_fn<S, T, A, F>(initial_state: S, mut func: F) -> LoopFn<A, F>
+    where F: FnMut(S) -> A + 'static,
+          A: IntoFuture<Item=Loop<T, S>>,
+{
+    LoopFn {
+        future: Collapsed::Start(func(initial_state).into_future()),
+        func: func,
+    }
+}
+
+impl<S, T, A, F> Future for LoopFn<A, F>
+    where F: FnMut(S) -> A + 'static,
+          A: IntoFuture<Item=Loop<T, S>>,
+          T: 'static,
+{
+    type Item = T;
+    type Error = A::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<T, A::Error> {
+        loop {
+            match try_poll!(self.future.poll(task)) {
+                Ok(Loop::Break(x)) => return Poll::Ok(x),
+                Ok(Loop::Continue(s)) => {
+                    let next = (self.func)(s).into_future();
+                    self.future = Collapsed::Start(next);
+                }
+                Err(e) => return Poll::Err(e),
+            }
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.future.schedule(task)
+    }
+
+    unsafe fn tailcall(&mut self)
+                       -> Option<Box<Future<Item=Self::Item, Error=Self::Error>>> {
+        self.future.collapse();
+        None
+    }
+}
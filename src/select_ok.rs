@@ -0,0 +1,96 @@
+use std::mem;
+
+use {Future, IntoFuture, Task, Poll};
+use util::Collapsed;
+
+/// Future for the `select_ok` combinator, waiting for one of any of a list of
+/// futures to successfully complete.
+///
+/// This is created by this `select_ok` function.
+pub struct SelectOk<A> where A: Future {
+    inner: Vec<Collapsed<A>>,
+}
+
+/// Creates a new future which will select the first successful future over a
+/// list of futures.
+///
+/// The returned future will wait for any future within `list` to complete
+/// successfully. As soon as one succeeds the remaining futures are dropped
+/// and the successful value is returned.
+///
+/// Futures which fail are dropped as soon as their error is seen. If every
+/// future fails then the returned future fails with the error of the last
+/// future to do so.
+///
+/// # Panics
+///
+/// This function will panic if the iterator specified contains no items.
+///
+/// # Examples
+///
+/// ```
+/// use futures::*;
+///
+/// let f = select_ok(vec![
+///     failed::<u32, u32>(1).boxed(),
+///     finished::<u32, u32>(2).boxed(),
+///     finished::<u32, u32>(3).boxed(),
+/// ]);
+/// let f = f.then(|x| {
+///     assert_eq!(x, Ok(2));
+///     x
+/// });
+/// ```
+pub fn select_ok<I>(iter: I) -> SelectOk<<I::Item as IntoFuture>::Future>
+    where I: IntoIterator,
+          I::Item: IntoFuture,
+{
+    let ret = SelectOk {
+        inner: iter.into_iter()
+                   .map(|a| a.into_future())
+                   .map(Collapsed::Start)
+                   .collect(),
+    };
+    assert!(ret.inner.len() > 0);
+    ret
+}
+
+impl<A> Future for SelectOk<A> where A: Future {
+    type Item = A::Item;
+    type Error = A::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Self::Item, Self::Error> {
+        let mut i = 0;
+        while i < self.inner.len() {
+            match self.inner[i].poll(task) {
+                // We've got a winner, so drop all the losers right away.
+                Poll::Ok(e) => {
+                    drop(mem::replace(&mut self.inner, Vec::new()));
+                    return Poll::Ok(e)
+                }
+                Poll::Err(e) => {
+                    drop(self.inner.remove(i));
+                    if self.inner.is_empty() {
+                        return Poll::Err(e)
+                    }
+                }
+                Poll::NotReady => i += 1,
+            }
+        }
+        Poll::NotReady
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        for f in self.inner.iter_mut() {
+            f.schedule(task);
+        }
+    }
+
+    unsafe fn tailcall(&mut self)
+                       -> Option<Box<Future<Item=Self::Item, Error=Self::Error>>> {
+        for f in self.inner.iter_mut() {
+            f.collapse();
+        }
+        None
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use {Future, Task, TaskHandle, Poll};
+use lock::Lock;
+
+/// A future which can be cloned and polled from any number of tasks at once.
+///
+/// The underlying future is only ever driven once, and each clone will resolve
+/// to a reference-counted copy of its item or error.
+///
+/// This is created by the `Future::shared` method.
+pub struct Shared<F> where F: Future {
+    inner: Arc<Inner<F>>,
+    id: usize,
+}
+
+struct Inner<F> where F: Future {
+    next_id: AtomicUsize,
+    state: Lock<State<F>>,
+    waiters: Mutex<HashMap<usize, TaskHandle>>,
+}
+
+enum State<F> where F: Future {
+    Waiting(F),
+    Done(Result<Arc<F::Item>, Arc<F::Error>>),
+}
+
+pub fn new<F: Future>(f: F) -> Shared<F> {
+    Shared {
+        inner: Arc::new(Inner {
+            next_id: AtomicUsize::new(1),
+            state: Lock::new(State::Waiting(f)),
+            waiters: Mutex::new(HashMap::new()),
+        }),
+        id: 0,
+    }
+}
+
+impl<F> Shared<F> where F: Future {
+    fn notify_waiters(&self) {
+        // Take the waiters out first, notifying a task may end up polling
+        // another handle to this same future on this thread.
+        let waiters = {
+            let mut waiters = self.inner.waiters.lock().unwrap();
+            mem::replace(&mut *waiters, HashMap::new())
+        };
+        for (_, handle) in waiters {
+            handle.notify();
+        }
+    }
+}
+
+impl<F> Future for Shared<F> where F: Future {
+    type Item = Arc<F::Item>;
+    type Error = Arc<F::Error>;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Self::Item, Self::Error> {
+        let result = {
+            // If the lock is held then some other handle is currently polling
+            // the future. They'll notify everyone waiting once the result is
+            // available.
+            let mut state = match self.inner.state.try_lock() {
+                Some(state) => state,
+                None => return Poll::NotReady,
+            };
+            let result = match *state {
+                State::Done(ref result) => result.clone(),
+                State::Waiting(ref mut f) => {
+                    match f.poll(task) {
+                        Poll::Ok(e) => Ok(Arc::new(e)),
+                        Poll::Err(e) => Err(Arc::new(e)),
+                        Poll::NotReady => return Poll::NotReady,
+                    }
+                }
+            };
+            *state = State::Done(result.clone());
+            result
+        };
+
+        // Anyone who tried to schedule while we held the lock is still waiting
+        // on us, so wake them all up now that we're done.
+        self.notify_waiters();
+        result.into()
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        // Register ourselves first so whoever is holding the lock below will
+        // see us when they go to notify everyone.
+        self.inner.waiters.lock().unwrap()
+            .insert(self.id, task.handle().clone());
+
+        if let Some(mut state) = self.inner.state.try_lock() {
+            match *state {
+                State::Waiting(ref mut f) => f.schedule(task),
+                State::Done(_) => task.notify(),
+            }
+        }
+    }
+}
+
+impl<F> Clone for Shared<F> where F: Future {
+    fn clone(&self) -> Shared<F> {
+        Shared {
+            inner: self.inner.clone(),
+            id: self.inner.next_id.fetch_add(1, Ordering::SeqCst),
+        }
+    }
+}
+
+impl<F> Drop for Shared<F> where F: Future {
+    fn drop(&mut self) {
+        // We may have been the last handle to schedule the underlying future,
+        // in which case its notification would go nowhere. Wake up one of the
+        // remaining waiters so it can take over scheduling.
+        let next = {
+            let mut waiters = self.inner.waiters.lock().unwrap();
+            waiters.remove(&self.id);
+            waiters.values().next().cloned()
+        };
+        if let Some(handle) = next {
+            handle.notify();
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::VecDeque;
+
+use {Task, Poll};
+use sink::{Sink, StartSend, AsyncSink};
+
+/// Sink for the `Sink::buffer` combinator, which buffers up to some fixed
+/// number of values when the underlying sink is unable to accept them.
+///
+/// This is produced by the `Sink::buffer` method.
+pub struct Buffer<S: Sink> {
+    sink: S,
+    buf: VecDeque<S::SinkItem>,
+    cap: usize,
+}
+
+pub fn new<S: Sink>(sink: S, amt: usize) -> Buffer<S> {
+    Buffer {
+        sink: sink,
+        buf: VecDeque::with_capacity(amt),
+        cap: amt,
+    }
+}
+
+impl<S: Sink> Buffer<S> {
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    fn try_empty_buffer(&mut self, task: &mut Task) -> Poll<(), S::SinkError> {
+        while let Some(item) = self.buf.pop_front() {
+            match self.sink.start_send(task, item) {
+                Ok(AsyncSink::Ready) => {}
+                Ok(AsyncSink::NotReady(item)) => {
+                    self.buf.push_front(item);
+                    return Poll::NotReady
+                }
+                Err(e) => return Poll::Err(e),
+            }
+        }
+        Poll::Ok(())
+    }
+}
+
+impl<S: Sink> Sink for Buffer<S> {
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, task: &mut Task, item: S::SinkItem)
+                  -> StartSend<S::SinkItem, S::SinkError> {
+        if self.cap == 0 {
+            return self.sink.start_send(task, item)
+        }
+        if let Poll::Err(e) = self.try_empty_buffer(task) {
+            return Err(e)
+        }
+        if self.buf.len() >= self.cap {
+            return Ok(AsyncSink::NotReady(item))
+        }
+        self.buf.push_back(item);
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self, task: &mut Task) -> Poll<(), S::SinkError> {
+        match try_poll!(self.try_empty_buffer(task)) {
+            Ok(()) => self.sink.poll_complete(task),
+            Err(e) => Poll::Err(e),
+        }
+    }
+
+    fn close(&mut self, task: &mut Task) -> Poll<(), S::SinkError> {
+        match try_poll!(self.try_empty_buffer(task)) {
+            Ok(()) => self.sink.close(task),
+            Err(e) => Poll::Err(e),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.sink.schedule(task)
+    }
+}
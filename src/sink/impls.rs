@@ -0,0 +1,24 @@
+use {Task, Poll};
+use sink::{Sink, StartSend};
+
+impl<S: ?Sized + Sink> Sink for Box<S> {
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, task: &mut Task, item: Self::SinkItem)
+                  -> StartSend<Self::SinkItem, Self::SinkError> {
+        (**self).start_send(task, item)
+    }
+
+    fn poll_complete(&mut self, task: &mut Task) -> Poll<(), Self::SinkError> {
+        (**self).poll_complete(task)
+    }
+
+    fn close(&mut self, task: &mut Task) -> Poll<(), Self::SinkError> {
+        (**self).close(task)
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        (**self).schedule(task)
+    }
+}
@@ -0,0 +1,48 @@
+use {Task, Poll};
+use sink::{Sink, StartSend};
+
+/// Sink for the `Sink::sink_map_err` combinator, changing the error type of a
+/// sink from one type to another.
+///
+/// This is produced by the `Sink::sink_map_err` method.
+pub struct SinkMapErr<S, F> {
+    sink: S,
+    f: F,
+}
+
+pub fn new<S, F, E>(s: S, f: F) -> SinkMapErr<S, F>
+    where S: Sink,
+          F: FnMut(S::SinkError) -> E + 'static,
+          E: 'static,
+{
+    SinkMapErr {
+        sink: s,
+        f: f,
+    }
+}
+
+impl<S, F, E> Sink for SinkMapErr<S, F>
+    where S: Sink,
+          F: FnMut(S::SinkError) -> E + 'static,
+          E: 'static,
+{
+    type SinkItem = S::SinkItem;
+    type SinkError = E;
+
+    fn start_send(&mut self, task: &mut Task, item: S::SinkItem)
+                  -> StartSend<S::SinkItem, E> {
+        self.sink.start_send(task, item).map_err(&mut self.f)
+    }
+
+    fn poll_complete(&mut self, task: &mut Task) -> Poll<(), E> {
+        self.sink.poll_complete(task).map_err(&mut self.f)
+    }
+
+    fn close(&mut self, task: &mut Task) -> Poll<(), E> {
+        self.sink.close(task).map_err(&mut self.f)
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.sink.schedule(task)
+    }
+}
@@ -0,0 +1,224 @@
+//! Asynchronous sinks
+//!
+//! This module contains the `Sink` trait, the write-side dual of the `Stream`
+//! trait, along with a number of adaptors for it. A sink is a value into which
+//! other values can be sent asynchronously, such as the sending half of a
+//! channel or a framed transport.
+//!
+//! Sending a value is split up into two phases. First a value is handed to the
+//! sink with `start_send`, which may refuse it if the sink is full. The sink
+//! may then buffer the value internally, and `poll_complete` is used to drive
+//! any such buffered values all the way through.
+
+use {IntoFuture, Task, Poll};
+
+mod buffer;
+mod map_err;
+mod send;
+mod with;
+pub use self::buffer::Buffer;
+pub use self::map_err::SinkMapErr;
+pub use self::send::Send;
+pub use self::with::With;
+
+mod impls;
+
+/// The result of an asynchronous attempt to send a value to a sink.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AsyncSink<T> {
+    /// The `start_send` attempt succeeded, so the sending process has
+    /// *started*; you must use `Sink::poll_complete` to drive the send to
+    /// completion.
+    Ready,
+
+    /// The `start_send` attempt failed due to the sink being full. The value
+    /// being sent is returned, and the current task will be notified, after
+    /// being scheduled, once the sink may be able to accept it.
+    NotReady(T),
+}
+
+impl<T> AsyncSink<T> {
+    /// Returns whether this is `AsyncSink::Ready`.
+    pub fn is_ready(&self) -> bool {
+        match *self {
+            AsyncSink::Ready => true,
+            AsyncSink::NotReady(_) => false,
+        }
+    }
+
+    /// Returns whether this is `AsyncSink::NotReady`.
+    pub fn is_not_ready(&self) -> bool {
+        !self.is_ready()
+    }
+}
+
+/// The return type of the `Sink::start_send` method, indicating the outcome
+/// of a send attempt.
+pub type StartSend<T, E> = Result<AsyncSink<T>, E>;
+
+/// A `Sink` is a value into which other values can be sent, asynchronously.
+///
+/// Basic examples of sinks include the sending side of a channel, a
+/// connection which frames values onto a socket, or a writer which serializes
+/// values into a buffer. Sinks are the write-side dual of the `Stream` trait,
+/// and `Stream::forward` can be used to send every item of a stream into a
+/// sink.
+///
+/// # Basic methods
+///
+/// Like streams, a `Sink` never blocks. Values are sent with `start_send`,
+/// which either accepts the value or hands it back if the sink can't accept it
+/// at the moment. Sinks may buffer values before actually sending them, and
+/// `poll_complete` is used to drive all buffered values to their destination.
+/// Once no more values will be sent, `close` is used to flush the sink and
+/// shut it down.
+///
+/// Whenever `start_send` hands a value back or `poll_complete` or `close`
+/// return `Poll::NotReady`, the `schedule` method can be used to receive a
+/// notification for when the sink may be able to make progress again.
+pub trait Sink: 'static {
+    /// The type of value that the sink accepts.
+    type SinkItem: 'static;
+
+    /// The type of value produced by the sink when an error occurs.
+    type SinkError: 'static;
+
+    /// Begin the process of sending a value to the sink.
+    ///
+    /// If the sink can accept the value then `AsyncSink::Ready` is returned,
+    /// and the sink has taken ownership of it. Note that this does not mean
+    /// the value has been sent yet, only that the sending process has
+    /// started, and `poll_complete` must be called to finish it.
+    ///
+    /// If the sink can't accept the value right now, for example because its
+    /// buffer is full, then `AsyncSink::NotReady` is returned with the value.
+    /// The `schedule` method can then be used to learn when the send should
+    /// be attempted again.
+    ///
+    /// An `Err` indicates that the sink has failed permanently, and the value
+    /// has been lost.
+    fn start_send(&mut self,
+                  task: &mut Task,
+                  item: Self::SinkItem)
+                  -> StartSend<Self::SinkItem, Self::SinkError>;
+
+    /// Flush all values which have been accepted by `start_send`.
+    ///
+    /// This method returns `Poll::Ok(())` once every value handed to
+    /// `start_send` so far has been fully processed by the sink, and
+    /// `Poll::NotReady` if there's still more work to do. In the latter case
+    /// `schedule` can be used to learn when more progress can be made.
+    ///
+    /// Note that sinks may rely on this method being called in order to make
+    /// any progress at all, so it must be called after values are sent.
+    fn poll_complete(&mut self, task: &mut Task) -> Poll<(), Self::SinkError>;
+
+    /// Flush all values and shut down this sink.
+    ///
+    /// This method is like `poll_complete`, except that it also signals that
+    /// no more values will be sent. The default implementation simply calls
+    /// `poll_complete`, but sinks like network connections may want to
+    /// additionally perform a shutdown once all data has been flushed.
+    ///
+    /// After `close` has returned `Poll::Ok(())` no more values should be
+    /// sent to this sink.
+    fn close(&mut self, task: &mut Task) -> Poll<(), Self::SinkError> {
+        self.poll_complete(task)
+    }
+
+    /// Schedule a task to be notified when this sink is ready to make
+    /// progress.
+    ///
+    /// This is very similar to the `Stream::schedule` method. It should be
+    /// called after `start_send` has returned `AsyncSink::NotReady`, or after
+    /// `poll_complete` or `close` have returned `Poll::NotReady`. The task
+    /// will be notified once for when the sink may be able to accept a value
+    /// or make more progress flushing.
+    ///
+    /// Multiple calls to `schedule` will only result in the final `task`
+    /// getting notified.
+    fn schedule(&mut self, task: &mut Task);
+
+    /// Composes a function in front of the sink.
+    ///
+    /// This adapter produces a new sink that passes each value through the
+    /// given function `f` before sending it to `self`.
+    ///
+    /// To process each value, `f` produces a *future*, which is then polled to
+    /// completion before passing its result down to the underlying sink. If
+    /// the future produces an error, that error is returned by the new sink.
+    ///
+    /// Note that this function consumes the given sink, returning a wrapped
+    /// version, much like `Stream::map`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures::sink::Sink;
+    /// use futures::stream::{self, Stream};
+    ///
+    /// let (tx, rx) = stream::channel::<i32, u32>();
+    /// let tx = tx.sink_map_err(|_| 0u32)
+    ///            .with(|x: i32| Ok::<_, u32>(Ok(x * 2)));
+    /// let rx = rx.take(1).collect();
+    ///
+    /// let sent = tx.send(3).map(|_| ());
+    /// assert_eq!(sent.join(rx).wait().ok().unwrap(), ((), vec![6]));
+    /// ```
+    fn with<U, F, Fut>(self, f: F) -> With<Self, U, F, Fut>
+        where F: FnMut(U) -> Fut + 'static,
+              Fut: IntoFuture<Item=Self::SinkItem>,
+              Fut::Error: From<Self::SinkError>,
+              U: 'static,
+              Self: Sized
+    {
+        with::new(self, f)
+    }
+
+    /// Adds a fixed-size buffer to the current sink.
+    ///
+    /// The resulting sink will buffer up to `amt` values when the underlying
+    /// sink is unwilling to accept additional values. Calling `poll_complete`
+    /// on the buffered sink will attempt to both empty the buffer and
+    /// complete processing on the underlying sink.
+    ///
+    /// Note that this function consumes the given sink, returning a wrapped
+    /// version, much like `Stream::map`.
+    fn buffer(self, amt: usize) -> Buffer<Self>
+        where Self: Sized
+    {
+        buffer::new(self, amt)
+    }
+
+    /// Converts a sink of error type `T` to a sink of error type `U` by
+    /// applying `f` to each error.
+    ///
+    /// This function is the sink equivalent of `Stream::map_err`, and the
+    /// closure is executed inline with the calls to the sink which produced
+    /// the error.
+    ///
+    /// Note that this function consumes the given sink, returning a wrapped
+    /// version, much like `Stream::map`.
+    fn sink_map_err<F, E>(self, f: F) -> SinkMapErr<Self, F>
+        where F: FnMut(Self::SinkError) -> E + 'static,
+              E: 'static,
+              Self: Sized
+    {
+        map_err::new(self, f)
+    }
+
+    /// A future that completes after the given item has been fully processed
+    /// into the sink, including flushing.
+    ///
+    /// Note that, because of the flushing requirement, it is usually better to
+    /// batch together items to send via `Stream::forward`, rather than
+    /// flushing between each item.
+    ///
+    /// On completion, the sink is returned.
+    fn send(self, item: Self::SinkItem) -> Send<Self>
+        where Self: Sized
+    {
+        send::new(self, item)
+    }
+}
@@ -0,0 +1,56 @@
+use {Future, Task, Poll};
+use sink::{Sink, AsyncSink};
+
+/// Future for the `Sink::send` combinator, which sends a value to a sink and
+/// then waits until the sink has fully flushed.
+///
+/// This is produced by the `Sink::send` method.
+pub struct Send<S: Sink> {
+    sink: Option<S>,
+    item: Option<S::SinkItem>,
+}
+
+pub fn new<S: Sink>(sink: S, item: S::SinkItem) -> Send<S> {
+    Send {
+        sink: Some(sink),
+        item: Some(item),
+    }
+}
+
+impl<S: Sink> Send<S> {
+    fn sink_mut(&mut self) -> &mut S {
+        self.sink.as_mut().expect("cannot poll Send twice")
+    }
+}
+
+impl<S: Sink> Future for Send<S> {
+    type Item = S;
+    type Error = S::SinkError;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<S, S::SinkError> {
+        if let Some(item) = self.item.take() {
+            match self.sink_mut().start_send(task, item) {
+                Ok(AsyncSink::Ready) => {}
+                Ok(AsyncSink::NotReady(item)) => {
+                    self.item = Some(item);
+                    return Poll::NotReady
+                }
+                Err(e) => return Poll::Err(e),
+            }
+        }
+
+        // we're done sending the item, but want to block on flushing the
+        // sink
+        match try_poll!(self.sink_mut().poll_complete(task)) {
+            Ok(()) => Poll::Ok(self.sink.take().unwrap()),
+            Err(e) => Poll::Err(e),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match self.sink {
+            Some(ref mut sink) => sink.schedule(task),
+            None => task.notify(),
+        }
+    }
+}
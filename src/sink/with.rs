@@ -0,0 +1,135 @@
+use std::marker::PhantomData;
+use std::mem;
+
+use {Future, IntoFuture, Task, Poll};
+use sink::{Sink, StartSend, AsyncSink};
+
+/// Sink for the `Sink::with` combinator, chaining a computation to run *prior*
+/// to pushing a value into the underlying sink.
+///
+/// This is produced by the `Sink::with` method.
+pub struct With<S, U, F, Fut>
+    where S: Sink,
+          Fut: IntoFuture,
+{
+    sink: S,
+    f: F,
+    state: State<Fut::Future, S::SinkItem>,
+    _phantom: PhantomData<fn(U)>,
+}
+
+enum State<Fut, T> {
+    Empty,
+    Process(Fut),
+    Buffered(T),
+}
+
+pub fn new<S, U, F, Fut>(sink: S, f: F) -> With<S, U, F, Fut>
+    where S: Sink,
+          F: FnMut(U) -> Fut + 'static,
+          Fut: IntoFuture<Item=S::SinkItem>,
+          Fut::Error: From<S::SinkError>,
+          U: 'static,
+{
+    With {
+        sink: sink,
+        f: f,
+        state: State::Empty,
+        _phantom: PhantomData,
+    }
+}
+
+impl<S, U, F, Fut> With<S, U, F, Fut>
+    where S: Sink,
+          F: FnMut(U) -> Fut + 'static,
+          Fut: IntoFuture<Item=S::SinkItem>,
+          Fut::Error: From<S::SinkError>,
+          U: 'static,
+{
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    // Drives the value currently being processed, if any, into the
+    // underlying sink.
+    fn poll(&mut self, task: &mut Task) -> Poll<(), Fut::Error> {
+        loop {
+            match mem::replace(&mut self.state, State::Empty) {
+                State::Empty => return Poll::Ok(()),
+                State::Process(mut fut) => {
+                    match fut.poll(task) {
+                        Poll::Ok(item) => self.state = State::Buffered(item),
+                        Poll::Err(e) => return Poll::Err(e),
+                        Poll::NotReady => {
+                            self.state = State::Process(fut);
+                            return Poll::NotReady
+                        }
+                    }
+                }
+                State::Buffered(item) => {
+                    match self.sink.start_send(task, item) {
+                        Ok(AsyncSink::Ready) => return Poll::Ok(()),
+                        Ok(AsyncSink::NotReady(item)) => {
+                            self.state = State::Buffered(item);
+                            return Poll::NotReady
+                        }
+                        Err(e) => return Poll::Err(From::from(e)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<S, U, F, Fut> Sink for With<S, U, F, Fut>
+    where S: Sink,
+          F: FnMut(U) -> Fut + 'static,
+          Fut: IntoFuture<Item=S::SinkItem>,
+          Fut::Error: From<S::SinkError>,
+          U: 'static,
+{
+    type SinkItem = U;
+    type SinkError = Fut::Error;
+
+    fn start_send(&mut self, task: &mut Task, item: U)
+                  -> StartSend<U, Fut::Error> {
+        match self.poll(task) {
+            Poll::Ok(()) => {}
+            Poll::NotReady => return Ok(AsyncSink::NotReady(item)),
+            Poll::Err(e) => return Err(e),
+        }
+        self.state = State::Process((self.f)(item).into_future());
+        match self.poll(task) {
+            Poll::Ok(()) | Poll::NotReady => Ok(AsyncSink::Ready),
+            Poll::Err(e) => Err(e),
+        }
+    }
+
+    fn poll_complete(&mut self, task: &mut Task) -> Poll<(), Fut::Error> {
+        match try_poll!(self.poll(task)) {
+            Ok(()) => self.sink.poll_complete(task).map_err(From::from),
+            Err(e) => Poll::Err(e),
+        }
+    }
+
+    fn close(&mut self, task: &mut Task) -> Poll<(), Fut::Error> {
+        match try_poll!(self.poll(task)) {
+            Ok(()) => self.sink.close(task).map_err(From::from),
+            Err(e) => Poll::Err(e),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match self.state {
+            State::Process(ref mut fut) => fut.schedule(task),
+            State::Empty |
+            State::Buffered(_) => self.sink.schedule(task),
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use {Future, Task, TaskHandle, Poll};
+use util::Collapsed;
+
+/// A future representing the completion of a future spawned with `spawn`.
+///
+/// This future resolves to the value of the spawned future, or fails with a
+/// `JoinError` if the spawned future failed or panicked.
+///
+/// Dropping a `JoinHandle` cancels the spawned future. Its task will be
+/// woken up and the future will be dropped without being polled again. To
+/// let the spawned future keep running in the background instead, use the
+/// `detach` method.
+pub struct JoinHandle<T, E> {
+    inner: Option<Arc<Inner<T, E>>>,
+}
+
+/// The error of a `JoinHandle`, returned if the spawned future didn't complete
+/// successfully.
+#[derive(Debug)]
+pub enum JoinError<E> {
+    /// The spawned future resolved with this error.
+    Inner(E),
+    /// The spawned future panicked while being polled, and this is the payload
+    /// of the panic.
+    Panicked(Box<Any + Send>),
+}
+
+struct Inner<T, E> {
+    canceled: AtomicBool,
+    result: Mutex<Option<thread::Result<Result<T, E>>>>,
+    waiter: Mutex<Option<TaskHandle>>,
+    child: Mutex<Option<TaskHandle>>,
+}
+
+struct Child<F> where F: Future {
+    future: Option<Collapsed<F>>,
+    inner: Arc<Inner<F::Item, F::Error>>,
+}
+
+/// Spawns a future onto a new task, returning a handle to its result.
+///
+/// Like `Future::forget`, this will allocate a new `Task` to drive `future`
+/// to completion in the background. Unlike `forget`, though, the returned
+/// `JoinHandle` is a future which resolves to the value or error that
+/// `future` produced. If `future` panics while being polled the panic is
+/// caught, and the payload is handed to the `JoinHandle` through
+/// `JoinError::Panicked`.
+///
+/// Dropping the returned handle will cancel the spawned future, so if its
+/// result isn't needed `JoinHandle::detach` should be used.
+///
+/// # Examples
+///
+/// ```
+/// use futures::*;
+///
+/// let handle = spawn(finished::<u32, u32>(1).map(|x| x + 1));
+/// assert_eq!(handle.wait().ok().unwrap(), 2);
+/// ```
+pub fn spawn<F>(future: F) -> JoinHandle<F::Item, F::Error>
+    where F: Future + Send,
+          F::Item: Send,
+          F::Error: Send,
+{
+    let inner = Arc::new(Inner {
+        canceled: AtomicBool::new(false),
+        result: Mutex::new(None),
+        waiter: Mutex::new(None),
+        child: Mutex::new(None),
+    });
+    let child = Child {
+        future: Some(Collapsed::Start(future)),
+        inner: inner.clone(),
+    };
+    Task::new().run(Box::new(child));
+    JoinHandle { inner: Some(inner) }
+}
+
+impl<T, E> JoinHandle<T, E> {
+    /// Detaches this handle from the spawned future, letting it run to
+    /// completion in the background.
+    ///
+    /// The spawned future will no longer be canceled, but its result will be
+    /// discarded once it's done. This includes any panic, which will not be
+    /// propagated anywhere.
+    pub fn detach(mut self) {
+        self.inner.take();
+    }
+
+    /// Returns whether the spawned future has completed.
+    pub fn is_complete(&self) -> bool {
+        match self.inner {
+            Some(ref inner) => inner.result.lock().unwrap().is_some(),
+            None => true,
+        }
+    }
+}
+
+impl<T, E> Future for JoinHandle<T, E>
+    where T: Send + 'static,
+          E: Send + 'static,
+{
+    type Item = T;
+    type Error = JoinError<E>;
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<T, JoinError<E>> {
+        let result = {
+            let inner = self.inner.as_ref()
+                            .expect("cannot poll JoinHandle twice");
+            match inner.result.lock().unwrap().take() {
+                Some(result) => result,
+                None => return Poll::NotReady,
+            }
+        };
+        self.inner = None;
+        match result {
+            Ok(Ok(e)) => Poll::Ok(e),
+            Ok(Err(e)) => Poll::Err(JoinError::Inner(e)),
+            Err(payload) => Poll::Err(JoinError::Panicked(payload)),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        let inner = match self.inner {
+            Some(ref inner) => inner,
+            None => return task.notify(),
+        };
+        *inner.waiter.lock().unwrap() = Some(task.handle().clone());
+        if inner.result.lock().unwrap().is_some() {
+            task.notify();
+        }
+    }
+}
+
+impl<T, E> Drop for JoinHandle<T, E> {
+    fn drop(&mut self) {
+        let inner = match self.inner.take() {
+            Some(inner) => inner,
+            None => return,
+        };
+        inner.canceled.store(true, Ordering::SeqCst);
+        let child = inner.child.lock().unwrap().take();
+        if let Some(child) = child {
+            child.notify();
+        }
+    }
+}
+
+impl<F> Child<F> where F: Future {
+    fn complete(&mut self, result: thread::Result<Result<F::Item, F::Error>>) {
+        self.future = None;
+        *self.inner.result.lock().unwrap() = Some(result);
+        let waiter = self.inner.waiter.lock().unwrap().take();
+        if let Some(waiter) = waiter {
+            waiter.notify();
+        }
+    }
+}
+
+impl<F> Future for Child<F> where F: Future {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self, task: &mut Task) -> Poll<(), ()> {
+        if self.inner.canceled.load(Ordering::SeqCst) {
+            self.future = None;
+            return Poll::Ok(())
+        }
+        let result = match self.future {
+            Some(ref mut future) => {
+                let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                    future.poll(task)
+                }));
+                match res {
+                    Ok(Poll::Ok(e)) => Ok(Ok(e)),
+                    Ok(Poll::Err(e)) => Ok(Err(e)),
+                    Ok(Poll::NotReady) => return Poll::NotReady,
+                    Err(payload) => Err(payload),
+                }
+            }
+            // We panicked while being scheduled and already stored the
+            // result.
+            None => return Poll::Ok(()),
+        };
+        self.complete(result);
+        Poll::Ok(())
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        *self.inner.child.lock().unwrap() = Some(task.handle().clone());
+        if self.inner.canceled.load(Ordering::SeqCst) {
+            return task.notify()
+        }
+        let res = match self.future {
+            Some(ref mut future) => {
+                panic::catch_unwind(AssertUnwindSafe(|| future.schedule(task)))
+            }
+            None => return task.notify(),
+        };
+        if let Err(payload) = res {
+            self.complete(Err(payload));
+            task.notify();
+        }
+    }
+
+    unsafe fn tailcall(&mut self) -> Option<Box<Future<Item=(), Error=()>>> {
+        if let Some(ref mut future) = self.future {
+            future.collapse();
+        }
+        None
+    }
+}
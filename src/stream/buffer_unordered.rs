@@ -0,0 +1,78 @@
+use {Task, IntoFuture, Poll};
+use stream::{Stream, Fuse, FuturesUnordered};
+
+/// An adaptor for a stream of futures to execute the futures concurrently,
+/// yielding their results as soon as they complete.
+///
+/// This adaptor will buffer up to a fixed number of pending futures, and then
+/// return their results in the order in which they complete, regardless of
+/// the order they were pulled out of the original stream. This is created by
+/// the `Stream::buffer_unordered` method.
+pub struct BufferUnordered<S>
+    where S: Stream,
+          S::Item: IntoFuture,
+{
+    stream: Fuse<S>,
+    queue: FuturesUnordered<<S::Item as IntoFuture>::Future>,
+    max: usize,
+}
+
+pub fn new<S>(s: S, amt: usize) -> BufferUnordered<S>
+    where S: Stream,
+          S::Item: IntoFuture<Error=<S as Stream>::Error>,
+{
+    assert!(amt > 0, "the limit of buffer_unordered must not be 0");
+    BufferUnordered {
+        stream: super::fuse::new(s),
+        queue: FuturesUnordered::new(),
+        max: amt,
+    }
+}
+
+impl<S> Stream for BufferUnordered<S>
+    where S: Stream,
+          S::Item: IntoFuture<Error=<S as Stream>::Error>,
+{
+    type Item = <S::Item as IntoFuture>::Item;
+    type Error = <S as Stream>::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<Self::Item>, Self::Error> {
+        // First, try to fill up the set of running futures
+        while self.queue.len() < self.max {
+            match self.stream.poll(task) {
+                Poll::Ok(Some(future)) => self.queue.push(future.into_future()),
+                Poll::Ok(None) => break,
+                Poll::Err(e) => return Poll::Err(e),
+                Poll::NotReady => break,
+            }
+        }
+
+        // Next, hand out the result of whichever future finishes first
+        match self.queue.poll(task) {
+            Poll::Ok(Some(e)) => return Poll::Ok(Some(e)),
+            Poll::Err(e) => return Poll::Err(e),
+            Poll::Ok(None) | Poll::NotReady => {}
+        }
+
+        if self.stream.is_done() && self.queue.is_empty() {
+            Poll::Ok(None)
+        } else {
+            Poll::NotReady
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if self.stream.is_done() && self.queue.is_empty() {
+            return task.notify()
+        }
+
+        // If there's room for more futures we're interested in the stream,
+        // and otherwise only in the futures already running.
+        if self.queue.len() < self.max {
+            self.stream.schedule(task);
+        }
+        if !self.queue.is_empty() {
+            self.queue.schedule(task);
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use {Future, Task, Poll};
+use sink::{Sink, StartSend, AsyncSink};
 use slot::{Slot, Token};
 use stream::Stream;
 
@@ -26,6 +27,7 @@ pub fn channel<T, E>() -> (Sender<T, E>, Receiver<T, E>)
     });
     let sender = Sender {
         inner: inner.clone(),
+        on_empty_token: None,
     };
     let receiver = Receiver {
         inner: inner,
@@ -36,12 +38,16 @@ pub fn channel<T, E>() -> (Sender<T, E>, Receiver<T, E>)
 
 /// The transmission end of a channel which is used to send values.
 ///
+/// Values can either be sent one at a time with `Sender::send`, or through
+/// the `Sink` implementation, whose `SinkItem` is the `Result` to send.
+///
 /// This is created by the `channel` method in the `stream` module.
 pub struct Sender<T, E>
     where T: Send + 'static,
           E: Send + 'static,
 {
     inner: Arc<Inner<T, E>>,
+    on_empty_token: Option<Token>,
 }
 
 /// A future returned by the `Sender::send` method which will resolve to the
@@ -77,8 +83,19 @@ enum Message<T> {
     Done,
 }
 
+/// The error returned when sending a value into a channel whose `Receiver`
+/// has been dropped.
+///
+/// This contains the value which failed to be sent.
 pub struct SendError<T, E>(Result<T, E>);
 
+impl<T, E> SendError<T, E> {
+    /// Returns the value which failed to be sent.
+    pub fn into_inner(self) -> Result<T, E> {
+        self.0
+    }
+}
+
 impl<T, E> Stream for Receiver<T, E>
     where T: Send + 'static,
           E: Send + 'static,
@@ -87,6 +104,12 @@ impl<T, E> Stream for Receiver<T, E>
     type Error = E;
 
     fn poll(&mut self, _task: &mut Task) -> Poll<Option<T>, E> {
+        // We may be polled before a value arrives if the task was woken up
+        // by something else, so unregister our callback before consuming.
+        if let Some(token) = self.on_full_token.take() {
+            self.inner.slot.cancel(token);
+        }
+
         // TODO: disconnect?
         match self.inner.slot.try_consume() {
             Ok(Message::Data(Ok(e))) => Poll::Ok(Some(e)),
@@ -137,6 +160,56 @@ impl<T, E> Sender<T, E>
             data: Some(t),
         }
     }
+
+    fn schedule_empty(&mut self, task: &mut Task) {
+        if let Some(token) = self.on_empty_token.take() {
+            self.inner.slot.cancel(token);
+        }
+
+        let handle = task.handle().clone();
+        self.on_empty_token = Some(self.inner.slot.on_empty(move |_slot| {
+            handle.notify();
+        }));
+    }
+}
+
+impl<T, E> Sink for Sender<T, E>
+    where T: Send + 'static,
+          E: Send + 'static,
+{
+    type SinkItem = Result<T, E>;
+    type SinkError = SendError<T, E>;
+
+    fn start_send(&mut self, _task: &mut Task, t: Result<T, E>)
+                  -> StartSend<Result<T, E>, SendError<T, E>> {
+        // As with `Receiver::poll`, we may be polled again before the slot
+        // empties, so unregister our callback before producing.
+        if let Some(token) = self.on_empty_token.take() {
+            self.inner.slot.cancel(token);
+        }
+
+        if self.inner.receiver_gone.load(Ordering::SeqCst) {
+            return Err(SendError(t))
+        }
+        match self.inner.slot.try_produce(Message::Data(t)) {
+            Ok(()) => Ok(AsyncSink::Ready),
+            Err(e) => {
+                match e.into_inner() {
+                    Message::Data(t) => Ok(AsyncSink::NotReady(t)),
+                    Message::Done => panic!(),
+                }
+            }
+        }
+    }
+
+    fn poll_complete(&mut self, _task: &mut Task) -> Poll<(), SendError<T, E>> {
+        // Values go straight into the channel, there's nothing to flush.
+        Poll::Ok(())
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.schedule_empty(task)
+    }
 }
 
 impl<T, E> Drop for Sender<T, E>
@@ -144,6 +217,9 @@ impl<T, E> Drop for Sender<T, E>
           E: Send + 'static,
 {
     fn drop(&mut self) {
+        if let Some(token) = self.on_empty_token.take() {
+            self.inner.slot.cancel(token);
+        }
         self.inner.slot.on_empty(|slot| {
             slot.try_produce(Message::Done).ok().unwrap();
         });
@@ -159,7 +235,10 @@ impl<T, E> Future for FutureSender<T, E>
 
     fn poll(&mut self, _task: &mut Task) -> Poll<Self::Item, Self::Error> {
         let data = self.data.take().expect("cannot poll FutureSender twice");
-        let sender = self.sender.take().expect("cannot poll FutureSender twice");
+        let mut sender = self.sender.take().expect("cannot poll FutureSender twice");
+        if let Some(token) = sender.on_empty_token.take() {
+            sender.inner.slot.cancel(token);
+        }
         match sender.inner.slot.try_produce(Message::Data(data)) {
             Ok(()) => Poll::Ok(sender),
             Err(e) => {
@@ -175,13 +254,7 @@ impl<T, E> Future for FutureSender<T, E>
 
     fn schedule(&mut self, task: &mut Task) {
         match self.sender {
-            Some(ref s) => {
-                let handle = task.handle().clone();
-                // TODO: don't drop token?
-                s.inner.slot.on_empty(move |_slot| {
-                    handle.notify();
-                });
-            }
+            Some(ref mut s) => s.schedule_empty(task),
             None => task.notify(),
         }
     }
@@ -0,0 +1,79 @@
+use std::mem;
+
+use {Task, Poll};
+use stream::{Stream, Fuse};
+
+/// A stream combinator which batches up the elements of a stream into
+/// vectors.
+///
+/// This structure is produced by the `Stream::chunks` method.
+pub struct Chunks<S: Stream> {
+    stream: Fuse<S>,
+    items: Vec<S::Item>,
+    cap: usize,
+    // An error which is held back until the elements buffered before it have
+    // been yielded.
+    err: Option<S::Error>,
+}
+
+pub fn new<S: Stream>(s: S, capacity: usize) -> Chunks<S> {
+    assert!(capacity > 0, "the capacity of chunks must not be 0");
+
+    Chunks {
+        stream: s.fuse(),
+        items: Vec::with_capacity(capacity),
+        cap: capacity,
+        err: None,
+    }
+}
+
+impl<S: Stream> Chunks<S> {
+    fn take(&mut self) -> Vec<S::Item> {
+        mem::replace(&mut self.items, Vec::with_capacity(self.cap))
+    }
+}
+
+impl<S: Stream> Stream for Chunks<S> {
+    type Item = Vec<S::Item>;
+    type Error = S::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<Vec<S::Item>>, S::Error> {
+        if let Some(e) = self.err.take() {
+            return Poll::Err(e)
+        }
+
+        loop {
+            match self.stream.poll(task) {
+                Poll::Ok(Some(e)) => {
+                    self.items.push(e);
+                    if self.items.len() >= self.cap {
+                        return Poll::Ok(Some(self.take()))
+                    }
+                }
+                Poll::Ok(None) => {
+                    return if self.items.is_empty() {
+                        Poll::Ok(None)
+                    } else {
+                        Poll::Ok(Some(self.take()))
+                    }
+                }
+                Poll::Err(e) => {
+                    if self.items.is_empty() {
+                        return Poll::Err(e)
+                    }
+                    self.err = Some(e);
+                    return Poll::Ok(Some(self.take()))
+                }
+                Poll::NotReady => return Poll::NotReady,
+            }
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if self.err.is_some() {
+            task.notify()
+        } else {
+            self.stream.schedule(task)
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use {Task, Future, Poll};
+use stream::Stream;
+
+/// A future which concatenates all of the elements of a stream into one
+/// value.
+///
+/// This future is created by the `Stream::concat` method.
+pub struct Concat<S> where S: Stream {
+    stream: S,
+    extend: Option<S::Item>,
+}
+
+pub fn new<S>(s: S) -> Concat<S>
+    where S: Stream,
+          S::Item: Extend<<S::Item as IntoIterator>::Item> + IntoIterator + Default,
+{
+    Concat {
+        stream: s,
+        extend: None,
+    }
+}
+
+impl<S> Future for Concat<S>
+    where S: Stream,
+          S::Item: Extend<<S::Item as IntoIterator>::Item> + IntoIterator + Default,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<S::Item, S::Error> {
+        loop {
+            match try_poll!(self.stream.poll(task)) {
+                Ok(Some(e)) => {
+                    match self.extend {
+                        Some(ref mut extend) => extend.extend(e),
+                        None => self.extend = Some(e),
+                    }
+                }
+                Ok(None) => {
+                    return Poll::Ok(self.extend.take().unwrap_or_else(Default::default))
+                }
+                Err(e) => {
+                    self.extend = None;
+                    return Poll::Err(e)
+                }
+            }
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.stream.schedule(task)
+    }
+}
@@ -0,0 +1,122 @@
+use {Future, Task, Poll};
+use sink::{Sink, AsyncSink};
+use stream::Stream;
+
+/// Future for the `Stream::forward` combinator, which sends a stream of values
+/// to a sink and then waits until the sink has fully flushed those values.
+///
+/// This is produced by the `Stream::forward` method.
+pub struct Forward<S: Stream, K: Sink> {
+    stream: Option<S>,
+    sink: Option<K>,
+    buffered: Option<S::Item>,
+    closing: bool,
+    sink_pending: bool,
+}
+
+pub fn new<S, K>(stream: S, sink: K) -> Forward<S, K>
+    where S: Stream,
+          K: Sink<SinkItem=S::Item>,
+          S::Error: From<K::SinkError>,
+{
+    Forward {
+        stream: Some(stream),
+        sink: Some(sink),
+        buffered: None,
+        closing: false,
+        sink_pending: false,
+    }
+}
+
+impl<S, K> Forward<S, K>
+    where S: Stream,
+          K: Sink<SinkItem=S::Item>,
+          S::Error: From<K::SinkError>,
+{
+    fn sink_mut(&mut self) -> &mut K {
+        self.sink.as_mut().expect("cannot poll Forward twice")
+    }
+
+    fn stream_mut(&mut self) -> &mut S {
+        self.stream.as_mut().expect("cannot poll Forward twice")
+    }
+
+    fn try_start_send(&mut self, task: &mut Task, item: S::Item)
+                      -> Poll<(), S::Error> {
+        match self.sink_mut().start_send(task, item) {
+            Ok(AsyncSink::Ready) => Poll::Ok(()),
+            Ok(AsyncSink::NotReady(item)) => {
+                self.buffered = Some(item);
+                self.sink_pending = true;
+                Poll::NotReady
+            }
+            Err(e) => Poll::Err(From::from(e)),
+        }
+    }
+}
+
+impl<S, K> Future for Forward<S, K>
+    where S: Stream,
+          K: Sink<SinkItem=S::Item>,
+          S::Error: From<K::SinkError>,
+{
+    type Item = (S, K);
+    type Error = S::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<(S, K), S::Error> {
+        self.sink_pending = false;
+
+        // If we've got an item buffered already, we need to write it to the
+        // sink before we can do anything else
+        if let Some(item) = self.buffered.take() {
+            if let Err(e) = try_poll!(self.try_start_send(task, item)) {
+                return Poll::Err(e)
+            }
+        }
+
+        while !self.closing {
+            match self.stream_mut().poll(task) {
+                Poll::Ok(Some(item)) => {
+                    if let Err(e) = try_poll!(self.try_start_send(task, item)) {
+                        return Poll::Err(e)
+                    }
+                }
+                Poll::Ok(None) => self.closing = true,
+                Poll::Err(e) => return Poll::Err(e),
+
+                // Make sure that everything we've sent so far gets flushed
+                // out while we wait for the next item from the stream.
+                Poll::NotReady => {
+                    match self.sink_mut().poll_complete(task) {
+                        Poll::Ok(()) => {}
+                        Poll::NotReady => self.sink_pending = true,
+                        Poll::Err(e) => return Poll::Err(From::from(e)),
+                    }
+                    return Poll::NotReady
+                }
+            }
+        }
+
+        match self.sink_mut().close(task) {
+            Poll::Ok(()) => {}
+            Poll::NotReady => {
+                self.sink_pending = true;
+                return Poll::NotReady
+            }
+            Poll::Err(e) => return Poll::Err(From::from(e)),
+        }
+        Poll::Ok((self.stream.take().unwrap(), self.sink.take().unwrap()))
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if self.stream.is_none() {
+            return task.notify()
+        }
+        if self.buffered.is_some() || self.sink_pending {
+            self.sink_mut().schedule(task);
+        }
+        if self.buffered.is_none() && !self.closing {
+            self.stream_mut().schedule(task);
+        }
+    }
+}
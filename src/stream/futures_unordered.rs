@@ -0,0 +1,172 @@
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use {Task, IntoFuture, Poll, Future, EventSet, UnparkEvent};
+use stream::Stream;
+use util::Collapsed;
+
+/// A set of futures which may complete in any order.
+///
+/// This structure is optimized to manage a large number of futures. Futures
+/// managed by `FuturesUnordered` will only be polled when they generate
+/// notifications, so the cost of a wakeup doesn't grow with the number of
+/// futures in the set.
+///
+/// New futures can be added to the set at any time with `push`. The set
+/// itself implements the `Stream` trait, yielding the result of each future in
+/// the order that they complete. The stream is finished once the set is empty.
+///
+/// This is created by the `futures_unordered` function or with
+/// `FuturesUnordered::new`.
+pub struct FuturesUnordered<F> where F: Future {
+    futures: Vec<Option<Collapsed<F>>>,
+    free: Vec<usize>,
+    len: usize,
+    ready: Arc<ReadySet>,
+    to_schedule: Vec<usize>,
+}
+
+// The list of futures in a `FuturesUnordered` which have been notified and
+// need to be polled again.
+struct ReadySet {
+    ids: Mutex<Vec<usize>>,
+}
+
+/// Converts a list of futures into a `Stream` of results from the futures.
+///
+/// This function will take a list of futures (e.g. a vector, an iterator,
+/// etc), and return a stream. The stream will yield items as they become
+/// available on the futures internally, in the order that they become
+/// available. This function is similar to `buffered` in that it may
+/// return items in a different order than in the list specified.
+pub fn futures_unordered<I>(futures: I)
+                            -> FuturesUnordered<<I::Item as IntoFuture>::Future>
+    where I: IntoIterator,
+          I::Item: IntoFuture,
+{
+    let mut set = FuturesUnordered::new();
+    for future in futures {
+        set.push(future.into_future());
+    }
+    set
+}
+
+impl<F> FuturesUnordered<F> where F: Future {
+    /// Constructs a new, empty `FuturesUnordered`.
+    ///
+    /// The returned set will not contain any futures, and as a stream it is
+    /// finished until a future is pushed into it.
+    pub fn new() -> FuturesUnordered<F> {
+        FuturesUnordered {
+            futures: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            ready: Arc::new(ReadySet { ids: Mutex::new(Vec::new()) }),
+            to_schedule: Vec::new(),
+        }
+    }
+
+    /// Adds a future to this set.
+    ///
+    /// The future will be polled the next time this stream is polled, and
+    /// after that only when it has generated a notification.
+    pub fn push(&mut self, future: F) {
+        let future = Some(Collapsed::Start(future));
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.futures[id] = future;
+                id
+            }
+            None => {
+                self.futures.push(future);
+                self.futures.len() - 1
+            }
+        };
+        self.len += 1;
+        self.ready.insert(id);
+    }
+
+    /// Returns the number of futures contained in this set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this set contains no futures.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn release(&mut self, id: usize) {
+        self.futures[id] = None;
+        self.free.push(id);
+        self.len -= 1;
+    }
+}
+
+impl<F> Stream for FuturesUnordered<F> where F: Future {
+    type Item = F::Item;
+    type Error = F::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<F::Item>, F::Error> {
+        let mut ready = mem::replace(&mut *self.ready.ids.lock().unwrap(),
+                                     Vec::new());
+
+        for i in 0..ready.len() {
+            let id = ready[i];
+            let event = UnparkEvent::new(self.ready.clone(), id);
+            let result = match self.futures[id] {
+                Some(ref mut f) => {
+                    match task.with_unpark_event(event, |task| f.poll(task)) {
+                        Poll::Ok(e) => Ok(e),
+                        Poll::Err(e) => Err(e),
+                        Poll::NotReady => {
+                            f.collapse();
+                            self.to_schedule.push(id);
+                            continue
+                        }
+                    }
+                }
+                // This future already finished and this is just a stale
+                // notification.
+                None => continue,
+            };
+            self.release(id);
+
+            // We haven't looked at the rest of the notifications yet, so put
+            // them back in front of anything which has come in since.
+            let mut ids = self.ready.ids.lock().unwrap();
+            let rest = ready.split_off(i + 1);
+            let newer = mem::replace(&mut *ids, rest);
+            ids.extend(newer);
+            return result.map(Some).into()
+        }
+
+        if self.len == 0 {
+            Poll::Ok(None)
+        } else {
+            Poll::NotReady
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if self.len == 0 || !self.ready.ids.lock().unwrap().is_empty() {
+            return task.notify()
+        }
+
+        // Only futures which have been polled since the last time we were
+        // scheduled need to get scheduled again, everything else still has
+        // its interest registered.
+        for id in self.to_schedule.drain(..) {
+            if let Some(ref mut f) = self.futures[id] {
+                let event = UnparkEvent::new(self.ready.clone(), id);
+                task.with_unpark_event(event, |task| f.schedule(task));
+            }
+        }
+    }
+}
+
+impl EventSet for ReadySet {
+    fn insert(&self, id: usize) {
+        self.ids.lock().unwrap().push(id);
+    }
+}
@@ -0,0 +1,43 @@
+use {Task, Poll};
+use stream::Stream;
+
+/// A stream combinator which calls a closure on each element before passing
+/// it on.
+///
+/// This is produced by the `Stream::inspect` method.
+pub struct Inspect<S, F> {
+    stream: S,
+    f: F,
+}
+
+pub fn new<S, F>(s: S, f: F) -> Inspect<S, F>
+    where S: Stream,
+          F: FnMut(&S::Item) + 'static,
+{
+    Inspect {
+        stream: s,
+        f: f,
+    }
+}
+
+impl<S, F> Stream for Inspect<S, F>
+    where S: Stream,
+          F: FnMut(&S::Item) + 'static,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<S::Item>, S::Error> {
+        match self.stream.poll(task) {
+            Poll::Ok(Some(e)) => {
+                (self.f)(&e);
+                Poll::Ok(Some(e))
+            }
+            other => other,
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.stream.schedule(task)
+    }
+}
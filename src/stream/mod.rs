@@ -12,49 +12,80 @@
 // TODO: expand these docs
 
 use {Task, IntoFuture, Poll};
+use sink::Sink;
 
 mod channel;
+mod futures_unordered;
 mod iter;
-pub use self::channel::{channel, Sender, Receiver};
+mod once;
+mod poll_fn;
+mod repeat;
+mod select_all;
+mod unfold;
+pub use self::channel::{channel, Sender, Receiver, SendError};
+pub use self::futures_unordered::{futures_unordered, FuturesUnordered};
 pub use self::iter::{iter, IterStream};
+pub use self::once::{once, Once};
+pub use self::poll_fn::{poll_fn, PollFn};
+pub use self::repeat::{repeat, Repeat};
+pub use self::select_all::{select_all, SelectAll};
+pub use self::unfold::{unfold, Unfold};
 
 mod and_then;
+mod buffer_unordered;
 mod buffered;
+mod chunks;
 mod collect;
+mod concat;
 mod filter;
 mod filter_map;
 mod flatten;
 mod fold;
 mod for_each;
+mod forward;
 mod fuse;
 mod future;
+mod inspect;
 mod map;
 mod map_err;
 mod merge;
 mod or_else;
+mod peekable;
+mod scan;
 mod skip;
 mod skip_while;
 mod take;
+mod take_while;
 mod then;
+mod wait;
 mod zip;
 pub use self::and_then::AndThen;
+pub use self::buffer_unordered::BufferUnordered;
 pub use self::buffered::Buffered;
+pub use self::chunks::Chunks;
 pub use self::collect::Collect;
+pub use self::concat::Concat;
 pub use self::filter::Filter;
 pub use self::filter_map::FilterMap;
 pub use self::flatten::Flatten;
 pub use self::fold::Fold;
 pub use self::for_each::ForEach;
+pub use self::forward::Forward;
 pub use self::fuse::Fuse;
 pub use self::future::StreamFuture;
+pub use self::inspect::Inspect;
 pub use self::map::Map;
 pub use self::map_err::MapErr;
 pub use self::merge::{Merge, MergedItem};
 pub use self::or_else::OrElse;
+pub use self::peekable::Peekable;
+pub use self::scan::Scan;
 pub use self::skip::Skip;
 pub use self::skip_while::SkipWhile;
 pub use self::take::Take;
+pub use self::take_while::TakeWhile;
 pub use self::then::Then;
+pub use self::wait::Wait;
 pub use self::zip::Zip;
 
 mod impls;
@@ -198,6 +229,40 @@ pub trait Stream: 'static {
         future::new(self)
     }
 
+    /// Creates an iterator which blocks the current thread until each item of
+    /// this stream is resolved.
+    ///
+    /// This method will consume ownership of this stream, returning an
+    /// implementation of a standard iterator. This iterator will block the
+    /// current thread on each call to `next` if the item in the stream isn't
+    /// ready yet, using the stream's task notifications to unpark the thread.
+    ///
+    /// Each item is yielded as a `Result`, and the iterator is done once the
+    /// stream is finished.
+    ///
+    /// Note that this method should not be called from within an event loop
+    /// or any other context where blocking is not allowed, as it may deadlock.
+    ///
+    /// # Panics
+    ///
+    /// The returned iterator will panic if the stream requests to be polled on
+    /// a different executor via `Task::poll_on`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::*;
+    ///
+    /// let s = iter(vec![Ok::<i32, u32>(1), Ok(2), Err(3)].into_iter());
+    /// let items = s.wait().collect::<Vec<_>>();
+    /// assert_eq!(items, [Ok(1), Ok(2), Err(3)]);
+    /// ```
+    fn wait(self) -> Wait<Self>
+        where Self: Sized
+    {
+        wait::new(self)
+    }
+
     /// Converts a stream of type `T` to a stream of type `U`.
     ///
     /// The provided closure is executed over all elements of this stream as
@@ -478,6 +543,36 @@ pub trait Stream: 'static {
         collect::new(self)
     }
 
+    /// Concatenate all of the elements of this stream into a single value,
+    /// returning a future representing the result.
+    ///
+    /// This combinator works on streams whose elements can be extended with
+    /// their own contents, such as `Vec<T>` or `String`. Each element is
+    /// appended to the first one, and the returned future resolves to the
+    /// result once the stream is finished. An empty stream resolves to the
+    /// default value of the element type.
+    ///
+    /// If an error happens then everything concatenated so far is dropped
+    /// and the error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures::stream::{self, Stream};
+    ///
+    /// let chunks = vec![Ok::<_, u32>(vec![1, 2]), Ok(vec![]), Ok(vec![3])];
+    /// let all = stream::iter(chunks.into_iter()).concat();
+    /// assert_eq!(all.wait(), Ok(vec![1, 2, 3]));
+    /// ```
+    fn concat(self) -> Concat<Self>
+        where Self::Item: Extend<<Self::Item as IntoIterator>::Item> +
+                          IntoIterator + Default,
+              Self: Sized
+    {
+        concat::new(self)
+    }
+
     /// Execute an accumulating computation over a stream, collecting all the
     /// values into one final result.
     ///
@@ -568,6 +663,62 @@ pub trait Stream: 'static {
         skip_while::new(self, pred)
     }
 
+    /// Take elements from this stream while the predicate provided resolves
+    /// to `true`.
+    ///
+    /// This function, like `Iterator::take_while`, will yield elements from
+    /// the stream until the `predicate` resolves to `false`. Once one element
+    /// returns false the stream is finished, and that element is discarded.
+    /// Errors are passed through without running the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, Stream};
+    ///
+    /// let items = vec![Ok::<i32, u32>(1), Ok(2), Ok(3), Ok(1)];
+    /// let small = stream::iter(items.into_iter()).take_while(|i| Ok(*i < 3));
+    /// assert_eq!(small.wait().collect::<Vec<_>>(), [Ok(1), Ok(2)]);
+    /// ```
+    fn take_while<P, R>(self, pred: P) -> TakeWhile<Self, P, R>
+        where P: FnMut(&Self::Item) -> R + 'static,
+              R: IntoFuture<Item=bool, Error=Self::Error>,
+              Self: Sized
+    {
+        take_while::new(self, pred)
+    }
+
+    /// Threads some state through this stream, producing a new stream from
+    /// it.
+    ///
+    /// This is similar to `Iterator::scan`. The closure is given mutable
+    /// access to the state along with each element of the stream, and
+    /// returns a future. If the future resolves to `Some` then that value is
+    /// the next element of the new stream, and if it resolves to `None` the
+    /// new stream is finished. Errors are passed through.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, Stream};
+    ///
+    /// let items = vec![Ok::<i32, u32>(1), Ok(2), Ok(3)];
+    /// let sums = stream::iter(items.into_iter()).scan(0, |sum, i| {
+    ///     *sum += i;
+    ///     Ok(Some(*sum))
+    /// });
+    /// assert_eq!(sums.wait().collect::<Vec<_>>(), [Ok(1), Ok(3), Ok(6)]);
+    /// ```
+    fn scan<T, F, Fut, B>(self, initial_state: T, f: F) -> Scan<Self, T, F, Fut>
+        where F: FnMut(&mut T, Self::Item) -> Fut + 'static,
+              Fut: IntoFuture<Item=Option<B>, Error=Self::Error>,
+              T: 'static,
+              B: 'static,
+              Self: Sized
+    {
+        scan::new(self, initial_state, f)
+    }
+
     /// Runs this stream to completion, executing the provided closure for each
     /// element on the stream.
     ///
@@ -586,6 +737,36 @@ pub trait Stream: 'static {
         for_each::new(self, f)
     }
 
+    /// A future that completes after the given stream has been fully processed
+    /// into the sink, including flushing.
+    ///
+    /// This future will drive the stream to keep producing items until it is
+    /// exhausted, sending each item to the sink. It will complete once both
+    /// the stream is exhausted and the sink has been closed, returning both
+    /// of them. Any error from the stream or the sink is returned immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::Future;
+    /// use futures::sink::Sink;
+    /// use futures::stream::{self, Stream};
+    ///
+    /// let (tx, rx) = stream::channel::<i32, u32>();
+    /// let items = stream::iter(vec![Ok(Ok(1)), Ok(Ok(2))].into_iter());
+    /// let forward = items.forward(tx.sink_map_err(|_| 0u32)).map(|_| ());
+    ///
+    /// let all = forward.join(rx.collect());
+    /// assert_eq!(all.wait().ok().unwrap(), ((), vec![1, 2]));
+    /// ```
+    fn forward<K>(self, sink: K) -> Forward<Self, K>
+        where K: Sink<SinkItem=Self::Item>,
+              Self::Error: From<K::SinkError>,
+              Self: Sized
+    {
+        forward::new(self, sink)
+    }
+
     /// Creates a new stream of at most `amt` items.
     ///
     /// Once `amt` items have been yielded from this stream then it will always
@@ -606,6 +787,56 @@ pub trait Stream: 'static {
         skip::new(self, amt)
     }
 
+    /// Do something with each element of this stream, passing it on
+    /// unchanged.
+    ///
+    /// This is handy for debugging or logging, as the closure is called with
+    /// a reference to each element before it's yielded.
+    fn inspect<F>(self, f: F) -> Inspect<Self, F>
+        where F: FnMut(&Self::Item) + 'static,
+              Self: Sized
+    {
+        inspect::new(self, f)
+    }
+
+    /// Creates a stream which can peek at its next element without consuming
+    /// it.
+    ///
+    /// The `Peekable::peek` method returns a reference to the next element,
+    /// which is then yielded from the next call to `poll`.
+    fn peekable(self) -> Peekable<Self>
+        where Self: Sized
+    {
+        peekable::new(self)
+    }
+
+    /// An adaptor for batching up elements of this stream into vectors.
+    ///
+    /// The returned stream yields vectors of `capacity` elements as they
+    /// become available. The last vector may hold fewer elements if the
+    /// stream ends first. If an error happens then the elements buffered so
+    /// far are yielded first, followed by the error.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, Stream};
+    ///
+    /// let items = (1..6).map(Ok::<i32, u32>);
+    /// let chunks = stream::iter(items).chunks(2);
+    /// assert_eq!(chunks.wait().collect::<Vec<_>>(),
+    ///            [Ok(vec![1, 2]), Ok(vec![3, 4]), Ok(vec![5])]);
+    /// ```
+    fn chunks(self, capacity: usize) -> Chunks<Self>
+        where Self: Sized
+    {
+        chunks::new(self, capacity)
+    }
+
     /// Fuse a stream such that `poll`/`schedule` will never again be called
     /// once it has terminated (signaled emptyness or an error).
     ///
@@ -643,6 +874,47 @@ pub trait Stream: 'static {
         buffered::new(self, amt)
     }
 
+    /// An adaptor for creating a buffered list of pending futures (unordered).
+    ///
+    /// If this stream's item can be converted into a future, then this adaptor
+    /// will buffer up to `amt` futures and then return results in the order
+    /// in which they complete. No more than `amt` futures will be buffered at
+    /// any point in time, and less than `amt` may also be buffered depending
+    /// on the state of each future.
+    ///
+    /// Unlike `buffered`, a slow future doesn't hold up the results of the
+    /// futures after it. The returned stream will be a stream of each
+    /// future's result, with errors passed through whenever they occur.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `amt` is 0, as no future could ever be run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::oneshot;
+    /// use futures::stream::{self, Stream};
+    ///
+    /// let (a, first) = oneshot::<i32>();
+    /// let (b, second) = oneshot::<i32>();
+    /// let futures = vec![Ok(first), Ok(second)];
+    /// let stream = stream::iter(futures.into_iter()).buffer_unordered(2);
+    /// let mut results = stream.wait();
+    ///
+    /// b.complete(2);
+    /// assert_eq!(results.next(), Some(Ok(2)));
+    /// a.complete(1);
+    /// assert_eq!(results.next(), Some(Ok(1)));
+    /// assert_eq!(results.next(), None);
+    /// ```
+    fn buffer_unordered(self, amt: usize) -> BufferUnordered<Self>
+        where Self::Item: IntoFuture<Error = <Self as Stream>::Error>,
+              Self: Sized
+    {
+        buffer_unordered::new(self, amt)
+    }
+
     /// An adapter for merging the output of two streams.
     ///
     /// The merged stream produces items from one or both of the underlying
@@ -0,0 +1,43 @@
+use {Task, Poll};
+use stream::Stream;
+
+/// A stream which yields a single element and then ends.
+///
+/// This stream is created by the `stream::once` function.
+pub struct Once<T, E> {
+    item: Option<Result<T, E>>,
+}
+
+/// Creates a stream of a single element.
+///
+/// The stream yields `item`, whether it's a value or an error, and then it's
+/// finished.
+///
+/// # Examples
+///
+/// ```
+/// use futures::stream::{self, Stream};
+///
+/// let stream = stream::once::<(), _>(Err(17));
+/// assert_eq!(stream.wait().collect::<Vec<_>>(), [Err(17)]);
+/// ```
+pub fn once<T: 'static, E: 'static>(item: Result<T, E>) -> Once<T, E> {
+    Once { item: Some(item) }
+}
+
+impl<T: 'static, E: 'static> Stream for Once<T, E> {
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<Option<T>, E> {
+        match self.item.take() {
+            Some(Ok(e)) => Poll::Ok(Some(e)),
+            Some(Err(e)) => Poll::Err(e),
+            None => Poll::Ok(None),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        task.notify()
+    }
+}
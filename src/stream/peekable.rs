@@ -0,0 +1,57 @@
+use {Task, Poll};
+use stream::{Stream, Fuse};
+
+/// A stream which allows looking at the next element without consuming it.
+///
+/// This structure is produced by the `Stream::peekable` method.
+pub struct Peekable<S: Stream> {
+    stream: Fuse<S>,
+    peeked: Option<S::Item>,
+}
+
+pub fn new<S: Stream>(s: S) -> Peekable<S> {
+    Peekable {
+        stream: s.fuse(),
+        peeked: None,
+    }
+}
+
+impl<S: Stream> Peekable<S> {
+    /// Peek at the next element of the stream without consuming it.
+    ///
+    /// This polls the underlying stream if no element has been peeked at yet,
+    /// and the element is then held on to until it's returned from `poll`.
+    /// Like `poll`, `Poll::NotReady` is returned if the stream isn't ready, in
+    /// which case `schedule` can be used to learn when to try again. Errors
+    /// are returned rather than held on to.
+    pub fn peek(&mut self, task: &mut Task) -> Poll<Option<&S::Item>, S::Error> {
+        if self.peeked.is_none() {
+            match try_poll!(self.stream.poll(task)) {
+                Ok(Some(e)) => self.peeked = Some(e),
+                Ok(None) => return Poll::Ok(None),
+                Err(e) => return Poll::Err(e),
+            }
+        }
+        Poll::Ok(self.peeked.as_ref())
+    }
+}
+
+impl<S: Stream> Stream for Peekable<S> {
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<S::Item>, S::Error> {
+        match self.peeked.take() {
+            Some(e) => Poll::Ok(Some(e)),
+            None => self.stream.poll(task),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if self.peeked.is_some() {
+            task.notify()
+        } else {
+            self.stream.schedule(task)
+        }
+    }
+}
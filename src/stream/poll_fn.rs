@@ -0,0 +1,67 @@
+use {Task, Poll};
+use stream::Stream;
+
+/// A stream which adapts a closure to the `Stream` trait.
+///
+/// This stream is created by the `stream::poll_fn` function.
+pub struct PollFn<F> {
+    inner: F,
+}
+
+/// Creates a stream whose `poll` calls the provided closure.
+///
+/// This is handy for wrapping up some ad-hoc polling logic without writing a
+/// new type for it.
+///
+/// # Wakeups
+///
+/// The stream's `schedule` method does nothing, as there's no closure to
+/// forward it to. This means that whenever the closure returns
+/// `Poll::NotReady` it must itself arrange for the task to be notified once
+/// it's worth polling again, typically by handing a clone of `task.handle()`
+/// to whatever will produce the next item, or by calling `schedule` on the
+/// futures and streams it polls. If it doesn't, nothing will ever wake the
+/// task up and the stream will never make progress.
+///
+/// # Examples
+///
+/// ```
+/// use futures::Poll;
+/// use futures::stream::{self, Stream};
+///
+/// let mut count = 0;
+/// let stream = stream::poll_fn(move |_task| -> Poll<Option<i32>, ()> {
+///     count += 1;
+///     if count > 3 {
+///         Poll::Ok(None)
+///     } else {
+///         Poll::Ok(Some(count))
+///     }
+/// });
+/// assert_eq!(stream.wait().collect::<Vec<_>>(), [Ok(1), Ok(2), Ok(3)]);
+/// ```
+pub fn poll_fn<T, E, F>(f: F) -> PollFn<F>
+    where F: FnMut(&mut Task) -> Poll<Option<T>, E> + 'static,
+          T: 'static,
+          E: 'static,
+{
+    PollFn { inner: f }
+}
+
+impl<T, E, F> Stream for PollFn<F>
+    where F: FnMut(&mut Task) -> Poll<Option<T>, E> + 'static,
+          T: 'static,
+          E: 'static,
+{
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<T>, E> {
+        (self.inner)(task)
+    }
+
+    fn schedule(&mut self, _task: &mut Task) {
+        // The closure is responsible for arranging for the task to be
+        // notified, see the documentation of `poll_fn`.
+    }
+}
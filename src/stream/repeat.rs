@@ -0,0 +1,51 @@
+use std::marker;
+
+use {Task, Poll};
+use stream::Stream;
+
+/// A stream which yields the same element over and over again.
+///
+/// This stream is created by the `stream::repeat` function.
+pub struct Repeat<T, E> {
+    item: T,
+    _error: marker::PhantomData<fn() -> E>,
+}
+
+/// Creates a stream which endlessly yields clones of `item`.
+///
+/// The stream never ends, so it's typically combined with an adaptor like
+/// `take` or `zip`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::stream::{self, Stream};
+///
+/// let stream = stream::repeat::<_, u32>(5).take(3);
+/// assert_eq!(stream.wait().collect::<Vec<_>>(), [Ok(5), Ok(5), Ok(5)]);
+/// ```
+pub fn repeat<T, E>(item: T) -> Repeat<T, E>
+    where T: Clone + 'static,
+          E: 'static,
+{
+    Repeat {
+        item: item,
+        _error: marker::PhantomData,
+    }
+}
+
+impl<T, E> Stream for Repeat<T, E>
+    where T: Clone + 'static,
+          E: 'static,
+{
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<Option<T>, E> {
+        Poll::Ok(Some(self.item.clone()))
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        task.notify()
+    }
+}
@@ -0,0 +1,87 @@
+use {Task, Poll, IntoFuture, Future};
+use stream::Stream;
+
+/// A stream combinator which threads state through the elements of a stream,
+/// yielding new elements computed from it.
+///
+/// This structure is produced by the `Stream::scan` method.
+pub struct Scan<S, T, F, Fut> where Fut: IntoFuture {
+    stream: S,
+    state: T,
+    f: F,
+    pending: Option<Fut::Future>,
+    done: bool,
+}
+
+pub fn new<S, T, F, Fut, B>(s: S, initial_state: T, f: F) -> Scan<S, T, F, Fut>
+    where S: Stream,
+          F: FnMut(&mut T, S::Item) -> Fut + 'static,
+          Fut: IntoFuture<Item=Option<B>, Error=S::Error>,
+          T: 'static,
+          B: 'static,
+{
+    Scan {
+        stream: s,
+        state: initial_state,
+        f: f,
+        pending: None,
+        done: false,
+    }
+}
+
+impl<S, T, F, Fut, B> Stream for Scan<S, T, F, Fut>
+    where S: Stream,
+          F: FnMut(&mut T, S::Item) -> Fut + 'static,
+          Fut: IntoFuture<Item=Option<B>, Error=S::Error>,
+          T: 'static,
+          B: 'static,
+{
+    type Item = B;
+    type Error = S::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<B>, S::Error> {
+        if self.done {
+            return Poll::Ok(None)
+        }
+
+        if self.pending.is_none() {
+            let item = match try_poll!(self.stream.poll(task)) {
+                Ok(Some(e)) => e,
+                Ok(None) => return Poll::Ok(None),
+                Err(e) => return Poll::Err(e),
+            };
+            self.pending = Some((self.f)(&mut self.state, item).into_future());
+        }
+
+        let res = try_poll!(self.pending.as_mut().unwrap().poll(task));
+        self.pending = None;
+        match res {
+            Ok(Some(e)) => Poll::Ok(Some(e)),
+            Ok(None) => {
+                self.done = true;
+                Poll::Ok(None)
+            }
+            Err(e) => Poll::Err(e),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if self.done {
+            task.notify()
+        } else if let Some(ref mut fut) = self.pending {
+            fut.schedule(task)
+        } else {
+            self.stream.schedule(task)
+        }
+    }
+}
+
+impl<S, T, F, Fut> Scan<S, T, F, Fut> where Fut: IntoFuture {
+    /// Consume this adaptor, returning the underlying stream.
+    ///
+    /// Note that if a future is active computing the next element it will be
+    /// dropped as part of this operation.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
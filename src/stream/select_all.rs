@@ -0,0 +1,110 @@
+use {Task, Poll};
+use stream::Stream;
+
+/// An adapter for merging the output of any number of streams.
+///
+/// The streams are polled in a round-robin fashion, starting after the stream
+/// which produced the previous element, so that a stream which is always
+/// ready can't starve the others. The stream is finished once every
+/// underlying stream is finished.
+///
+/// This is created by the `stream::select_all` function.
+pub struct SelectAll<S> {
+    streams: Vec<S>,
+    next: usize,
+}
+
+/// Merges a list of streams into one stream yielding the elements of all of
+/// them as they become available.
+///
+/// Errors from any of the streams are passed through, and streams are removed
+/// from the list as they finish. See `SelectAll` for how the streams are
+/// polled.
+///
+/// # Examples
+///
+/// ```
+/// use futures::stream::{self, Stream};
+///
+/// let a = stream::iter(vec![Ok::<i32, u32>(1), Ok(2)].into_iter());
+/// let b = stream::iter(vec![Ok::<i32, u32>(3), Ok(4)].into_iter());
+/// let all = stream::select_all(vec![a, b]);
+///
+/// let items = all.wait().collect::<Vec<_>>();
+/// assert_eq!(items, [Ok(1), Ok(3), Ok(2), Ok(4)]);
+/// ```
+pub fn select_all<I>(streams: I) -> SelectAll<I::Item>
+    where I: IntoIterator,
+          I::Item: Stream,
+{
+    SelectAll {
+        streams: streams.into_iter().collect(),
+        next: 0,
+    }
+}
+
+impl<S: Stream> SelectAll<S> {
+    /// Adds a new stream to the set being selected over.
+    pub fn push(&mut self, stream: S) {
+        self.streams.push(stream);
+    }
+
+    /// Returns the number of streams which haven't finished yet.
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Returns `true` if every stream has finished.
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+}
+
+impl<S: Stream> Stream for SelectAll<S> {
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<S::Item>, S::Error> {
+        let mut polled = 0;
+        while polled < self.streams.len() {
+            if self.next >= self.streams.len() {
+                self.next = 0;
+            }
+            let idx = self.next;
+            match self.streams[idx].poll(task) {
+                Poll::Ok(Some(e)) => {
+                    self.next = idx + 1;
+                    return Poll::Ok(Some(e))
+                }
+                Poll::Err(e) => {
+                    self.next = idx + 1;
+                    return Poll::Err(e)
+                }
+                // The stream at `idx` is replaced by the next one in line, so
+                // look at the same index again.
+                Poll::Ok(None) => {
+                    self.streams.remove(idx);
+                }
+                Poll::NotReady => {
+                    self.next = idx + 1;
+                    polled += 1;
+                }
+            }
+        }
+
+        if self.streams.is_empty() {
+            Poll::Ok(None)
+        } else {
+            Poll::NotReady
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if self.streams.is_empty() {
+            return task.notify()
+        }
+        for stream in self.streams.iter_mut() {
+            stream.schedule(task);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use {Task, Poll, IntoFuture, Future};
+use stream::Stream;
+
+/// A stream combinator which takes elements from a stream while a predicate
+/// holds.
+///
+/// This structure is produced by the `Stream::take_while` method.
+pub struct TakeWhile<S, P, R> where S: Stream, R: IntoFuture {
+    stream: S,
+    pred: P,
+    pending: Option<(R::Future, S::Item)>,
+    done_taking: bool,
+}
+
+pub fn new<S, P, R>(s: S, p: P) -> TakeWhile<S, P, R>
+    where S: Stream,
+          P: FnMut(&S::Item) -> R + 'static,
+          R: IntoFuture<Item=bool, Error=S::Error>,
+{
+    TakeWhile {
+        stream: s,
+        pred: p,
+        pending: None,
+        done_taking: false,
+    }
+}
+
+impl<S, P, R> Stream for TakeWhile<S, P, R>
+    where S: Stream,
+          P: FnMut(&S::Item) -> R + 'static,
+          R: IntoFuture<Item=bool, Error=S::Error>,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<S::Item>, S::Error> {
+        if self.done_taking {
+            return Poll::Ok(None)
+        }
+
+        if self.pending.is_none() {
+            let item = match try_poll!(self.stream.poll(task)) {
+                Ok(Some(e)) => e,
+                Ok(None) => return Poll::Ok(None),
+                Err(e) => return Poll::Err(e),
+            };
+            self.pending = Some(((self.pred)(&item).into_future(), item));
+        }
+
+        assert!(self.pending.is_some());
+        match try_poll!(self.pending.as_mut().unwrap().0.poll(task)) {
+            Ok(true) => {
+                let (_, item) = self.pending.take().unwrap();
+                Poll::Ok(Some(item))
+            }
+            Ok(false) => {
+                self.pending = None;
+                self.done_taking = true;
+                Poll::Ok(None)
+            }
+            Err(e) => {
+                self.pending = None;
+                Poll::Err(e)
+            }
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if self.done_taking {
+            task.notify()
+        } else if let Some((ref mut fut, _)) = self.pending {
+            fut.schedule(task)
+        } else {
+            self.stream.schedule(task)
+        }
+    }
+}
+
+impl<S, P, R> TakeWhile<S, P, R>
+    where S: Stream,
+          P: FnMut(&S::Item) -> R + 'static,
+          R: IntoFuture<Item=bool, Error=S::Error>,
+{
+    /// Consume this adaptor, returning the underlying stream.
+    ///
+    /// Note that if an element is buffered or a future is active determining
+    /// whether that element should be yielded they will both be dropped as part
+    /// of this operation.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
@@ -0,0 +1,106 @@
+use std::mem;
+
+use {Task, Future, Poll, IntoFuture};
+use stream::Stream;
+
+/// A stream which creates its elements by repeatedly running a closure over
+/// some state.
+///
+/// This stream is created by the `stream::unfold` function.
+pub struct Unfold<T, F, Fut> where Fut: IntoFuture {
+    f: F,
+    state: State<T, Fut::Future>,
+}
+
+enum State<T, F> {
+    /// Placeholder state when doing work, or once the stream is done
+    Empty,
+
+    /// Ready to create the next future from the state `T`
+    Ready(T),
+
+    /// Working on a future which resolves to the next element and state
+    Processing(F),
+}
+
+/// Creates a `Stream` from a seed and a closure returning a future.
+///
+/// The closure is called with the current state and returns `None` once the
+/// stream should end. Otherwise it returns a future which resolves to the
+/// next element of the stream along with the state for the following call.
+///
+/// An error from the future is passed through, after which the stream ends as
+/// there's no state left to continue with.
+///
+/// # Examples
+///
+/// ```
+/// use futures::stream::{self, Stream};
+///
+/// let stream = stream::unfold(0, |state| {
+///     if state <= 2 {
+///         Some(Ok::<_, u32>((state * 2, state + 1)))
+///     } else {
+///         None
+///     }
+/// });
+/// let items = stream.wait().collect::<Vec<_>>();
+/// assert_eq!(items, [Ok(0), Ok(2), Ok(4)]);
+/// ```
+pub fn unfold<T, F, Fut, It>(init: T, f: F) -> Unfold<T, F, Fut>
+    where F: FnMut(T) -> Option<Fut> + 'static,
+          Fut: IntoFuture<Item = (It, T)>,
+          T: 'static,
+          It: 'static,
+{
+    Unfold {
+        f: f,
+        state: State::Ready(init),
+    }
+}
+
+impl<T, F, Fut, It> Stream for Unfold<T, F, Fut>
+    where F: FnMut(T) -> Option<Fut> + 'static,
+          Fut: IntoFuture<Item = (It, T)>,
+          T: 'static,
+          It: 'static,
+{
+    type Item = It;
+    type Error = Fut::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<It>, Fut::Error> {
+        loop {
+            match mem::replace(&mut self.state, State::Empty) {
+                State::Empty => return Poll::Ok(None),
+                State::Ready(state) => {
+                    match (self.f)(state) {
+                        Some(fut) => {
+                            self.state = State::Processing(fut.into_future());
+                        }
+                        None => return Poll::Ok(None),
+                    }
+                }
+                State::Processing(mut fut) => {
+                    match fut.poll(task) {
+                        Poll::Ok((item, next)) => {
+                            self.state = State::Ready(next);
+                            return Poll::Ok(Some(item))
+                        }
+                        Poll::Err(e) => return Poll::Err(e),
+                        Poll::NotReady => {
+                            self.state = State::Processing(fut);
+                            return Poll::NotReady
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match self.state {
+            State::Processing(ref mut fut) => fut.schedule(task),
+            _ => task.notify(),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use Poll;
+use stream::Stream;
+use wait::ThreadTask;
+
+/// A stream combinator which converts an asynchronous stream to a **blocking
+/// iterator**.
+///
+/// Created by the `Stream::wait` method, this function transforms any stream
+/// into a standard iterator. This is implemented by blocking the current thread
+/// while items on the underlying stream aren't ready yet.
+pub struct Wait<S> {
+    stream: S,
+    task: ThreadTask,
+}
+
+pub fn new<S: Stream>(s: S) -> Wait<S> {
+    Wait {
+        stream: s,
+        task: ThreadTask::new(),
+    }
+}
+
+impl<S: Stream> Iterator for Wait<S> {
+    type Item = Result<S::Item, S::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stream = &mut self.stream;
+        loop {
+            match self.task.enter(|task| stream.poll(task)) {
+                Poll::Ok(Some(e)) => return Some(Ok(e)),
+                Poll::Ok(None) => return None,
+                Poll::Err(e) => return Some(Err(e)),
+                Poll::NotReady => {}
+            }
+            self.task.enter(|task| stream.schedule(task));
+            self.task.park();
+        }
+    }
+}
+
+impl<S> Wait<S> {
+    /// Consume this adaptor, returning the underlying stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
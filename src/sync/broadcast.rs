@@ -0,0 +1,255 @@
+//! A multi-producer, multi-consumer channel where every receiver sees every
+//! value.
+//!
+//! Values sent on a broadcast channel are stored in a ring buffer of fixed
+//! capacity, and each `Receiver` keeps track of its own position in it.
+//! Sending never waits: once the buffer is full the oldest value is
+//! overwritten. A receiver which falls so far behind that values it hasn't
+//! seen yet were overwritten gets a `RecvError::Lagged` error telling it how
+//! many values it missed, after which it continues with the oldest value
+//! still available.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use {Task, TaskHandle, Poll};
+use stream::Stream;
+
+/// The sending half of a broadcast channel.
+///
+/// Senders can be cloned, and the channel is closed once every sender is
+/// dropped.
+///
+/// This is created by the `channel` function.
+pub struct Sender<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// The receiving half of a broadcast channel, which implements `Stream`.
+///
+/// Each receiver yields every value sent after it was created, unless it
+/// lagged behind. Cloning a receiver creates a new receiver at the same
+/// position in the channel.
+///
+/// This is created by the `channel` function or `Sender::subscribe`.
+pub struct Receiver<T> {
+    inner: Arc<Inner<T>>,
+    id: usize,
+    next: u64,
+}
+
+/// The error returned when sending on a broadcast channel with no receivers.
+///
+/// This contains the value which failed to be sent.
+pub struct SendError<T>(T);
+
+/// The error yielded by a broadcast `Receiver`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RecvError {
+    /// The receiver fell behind and this many values were overwritten before
+    /// it could see them.
+    ///
+    /// The next value received will be the oldest one still in the channel.
+    Lagged(u64),
+}
+
+struct Inner<T> {
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    buffer: VecDeque<T>,
+    cap: usize,
+    // The position of `buffer[0]` among all values ever sent.
+    head: u64,
+    senders: usize,
+    receivers: usize,
+    next_id: usize,
+    waiters: HashMap<usize, TaskHandle>,
+}
+
+fn _assert() {
+    fn _assert_send<T: Send>() {}
+    fn _assert_sync<T: Sync>() {}
+    _assert_send::<Sender<u32>>();
+    _assert_sync::<Sender<u32>>();
+    _assert_send::<Receiver<u32>>();
+    _assert_sync::<Receiver<u32>>();
+}
+
+/// Creates a broadcast channel whose buffer holds up to `capacity` values.
+///
+/// # Panics
+///
+/// This function panics if `capacity` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use futures::stream::Stream;
+/// use futures::sync::broadcast;
+///
+/// let (tx, rx1) = broadcast::channel::<i32>(16);
+/// let rx2 = tx.subscribe();
+/// tx.send(1).ok().unwrap();
+/// tx.send(2).ok().unwrap();
+/// drop(tx);
+///
+/// assert_eq!(rx1.wait().collect::<Vec<_>>(), [Ok(1), Ok(2)]);
+/// assert_eq!(rx2.wait().collect::<Vec<_>>(), [Ok(1), Ok(2)]);
+/// ```
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "the capacity of a broadcast channel must not be 0");
+    let inner = Arc::new(Inner {
+        state: Mutex::new(State {
+            buffer: VecDeque::with_capacity(capacity),
+            cap: capacity,
+            head: 0,
+            senders: 1,
+            receivers: 1,
+            next_id: 1,
+            waiters: HashMap::new(),
+        }),
+    });
+    let rx = Receiver {
+        inner: inner.clone(),
+        id: 0,
+        next: 0,
+    };
+    (Sender { inner: inner }, rx)
+}
+
+fn notify_all(waiters: HashMap<usize, TaskHandle>) {
+    for (_, task) in waiters {
+        task.notify();
+    }
+}
+
+impl<T: Clone> Sender<T> {
+    /// Sends a value to every receiver currently subscribed to this channel.
+    ///
+    /// This never waits. If the buffer is full the oldest value in it is
+    /// overwritten, and receivers which hadn't seen that value yet will lag.
+    /// An error is returned with the value if there are no receivers.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let waiters = {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.receivers == 0 {
+                return Err(SendError(value))
+            }
+            if state.buffer.len() == state.cap {
+                state.buffer.pop_front();
+                state.head += 1;
+            }
+            state.buffer.push_back(value);
+            state.waiters.drain().collect()
+        };
+        notify_all(waiters);
+        Ok(())
+    }
+
+    /// Creates a new receiver which will see every value sent after this
+    /// call.
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut state = self.inner.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.receivers += 1;
+        Receiver {
+            inner: self.inner.clone(),
+            id: id,
+            next: state.head + state.buffer.len() as u64,
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        self.inner.state.lock().unwrap().senders += 1;
+        Sender { inner: self.inner.clone() }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waiters = {
+            let mut state = self.inner.state.lock().unwrap();
+            state.senders -= 1;
+            if state.senders > 0 {
+                return
+            }
+            state.waiters.drain().collect()
+        };
+        notify_all(waiters);
+    }
+}
+
+impl<T: Clone + 'static> Stream for Receiver<T> {
+    type Item = T;
+    type Error = RecvError;
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<Option<T>, RecvError> {
+        let state = self.inner.state.lock().unwrap();
+        if self.next < state.head {
+            let missed = state.head - self.next;
+            self.next = state.head;
+            return Poll::Err(RecvError::Lagged(missed))
+        }
+        let idx = (self.next - state.head) as usize;
+        match state.buffer.get(idx) {
+            Some(value) => {
+                self.next += 1;
+                Poll::Ok(Some(value.clone()))
+            }
+            None if state.senders == 0 => Poll::Ok(None),
+            None => Poll::NotReady,
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        let mut state = self.inner.state.lock().unwrap();
+        let end = state.head + state.buffer.len() as u64;
+        if self.next < end || state.senders == 0 {
+            drop(state);
+            task.notify();
+        } else {
+            state.waiters.insert(self.id, task.handle().clone());
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Receiver<T> {
+        let mut state = self.inner.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.receivers += 1;
+        Receiver {
+            inner: self.inner.clone(),
+            id: id,
+            next: self.next,
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.receivers -= 1;
+        state.waiters.remove(&self.id);
+    }
+}
+
+impl<T> SendError<T> {
+    /// Returns the value which failed to be sent.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_tuple("SendError").field(&"...").finish()
+    }
+}
@@ -0,0 +1,23 @@
+//! Future-aware synchronization
+//!
+//! This module contains primitives for sending values between and
+//! synchronizing tasks. Unlike the primitives in the standard library, none of
+//! these ever block the current thread. Instead they hand out futures and
+//! streams which are notified once they're able to make progress.
+//!
+//! The `Mutex`, `RwLock` and `Semaphore` types are handles which can be cloned
+//! to share them between tasks. Acquiring them returns a future resolving to a
+//! guard which releases the lock when dropped, and waiting tasks are served in
+//! the order in which they started waiting.
+
+pub mod broadcast;
+pub mod mpsc;
+pub mod watch;
+
+mod mutex;
+mod rwlock;
+mod semaphore;
+pub use self::mutex::{Mutex, MutexLock, MutexGuard};
+pub use self::rwlock::{RwLock, RwLockRead, RwLockWrite};
+pub use self::rwlock::{RwLockReadGuard, RwLockWriteGuard};
+pub use self::semaphore::{Semaphore, Acquire, Permit};
@@ -0,0 +1,459 @@
+//! A multi-producer, single-consumer channel for sending values between tasks.
+//!
+//! A channel is created with either the `channel` function, which has a
+//! bounded buffer providing backpressure to senders, or with `unbounded`,
+//! whose senders never have to wait. In both cases the `Receiver` implements
+//! `Stream`, and it's finished once every sender has been dropped.
+//!
+//! # Backpressure
+//!
+//! When the buffer of a bounded channel is full, senders are parked until the
+//! receiver has made space. Parked senders are woken up in the order in which
+//! they tried to send, and the space freed by the receiver is reserved for the
+//! sender which was woken up. This means that a sender which keeps on sending
+//! can't starve other senders waiting on the same channel.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use {Task, TaskHandle, Poll};
+use sink::{Sink, StartSend, AsyncSink};
+use stream::Stream;
+
+/// The transmission end of a bounded channel.
+///
+/// Senders can be cloned to send values from any number of tasks, and they
+/// implement `Sink` to send values with backpressure. The channel is closed
+/// once every sender is dropped.
+///
+/// This is created by the `channel` function.
+pub struct Sender<T> {
+    inner: Arc<Inner<T>>,
+    id: usize,
+}
+
+/// The transmission end of an unbounded channel.
+///
+/// Sending on an unbounded channel never needs to wait, so values can be sent
+/// directly with `UnboundedSender::unbounded_send`. Senders can also be cloned
+/// and used as a `Sink`.
+///
+/// This is created by the `unbounded` function.
+pub struct UnboundedSender<T>(Sender<T>);
+
+/// The receiving end of a channel, which implements `Stream`.
+///
+/// The stream yields values in the order they were sent, and is finished once
+/// all senders have been dropped and every buffered value has been received.
+/// Dropping the receiver will make all further sends fail.
+///
+/// This is created by the `channel` and `unbounded` functions.
+pub struct Receiver<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// The error returned when sending on a channel whose `Receiver` has been
+/// dropped.
+///
+/// This contains the value which failed to be sent.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SendError<T>(T);
+
+/// The error returned by `Sender::try_send`.
+///
+/// This contains the value which failed to be sent, along with the reason the
+/// send failed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TrySendError<T> {
+    kind: TrySendErrorKind,
+    value: T,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TrySendErrorKind {
+    Full,
+    Disconnected,
+}
+
+struct Inner<T> {
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    buffer: VecDeque<T>,
+    // `None` for unbounded channels
+    cap: Option<usize>,
+    // The number of slots in `buffer` which have been set aside for senders
+    // that were woken up, which are tracked in `granted`.
+    reserved: usize,
+    granted: HashSet<usize>,
+    // Senders which are waiting for space, in the order they'll get it.
+    parked: VecDeque<usize>,
+    sender_tasks: HashMap<usize, TaskHandle>,
+    recv_task: Option<TaskHandle>,
+    senders: usize,
+    next_id: usize,
+    receiver_gone: bool,
+}
+
+fn _assert() {
+    fn _assert_send<T: Send>() {}
+    fn _assert_sync<T: Sync>() {}
+    _assert_send::<Sender<u32>>();
+    _assert_sync::<Sender<u32>>();
+    _assert_send::<Receiver<u32>>();
+    _assert_sync::<Receiver<u32>>();
+}
+
+/// Creates a bounded channel for communicating between tasks.
+///
+/// The channel's buffer can hold up to `buffer` values. Once it's full,
+/// senders will be parked until the receiver has taken values out of it, and
+/// `Sender::try_send` will fail.
+///
+/// # Panics
+///
+/// This function panics if `buffer` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use futures::Future;
+/// use futures::sink::Sink;
+/// use futures::stream::Stream;
+/// use futures::sync::mpsc;
+///
+/// let (tx, rx) = mpsc::channel::<i32>(1);
+/// let tx2 = tx.clone();
+/// let a = thread::spawn(move || { tx.send(1).wait().ok().unwrap(); });
+/// let b = thread::spawn(move || { tx2.send(2).wait().ok().unwrap(); });
+///
+/// let mut items = rx.collect().wait().unwrap();
+/// items.sort();
+/// assert_eq!(items, [1, 2]);
+/// # a.join().unwrap();
+/// # b.join().unwrap();
+/// ```
+pub fn channel<T>(buffer: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(buffer > 0, "the buffer of a bounded channel must not be empty");
+    new(Some(buffer))
+}
+
+/// Creates an unbounded channel for communicating between tasks.
+///
+/// Sends on an unbounded channel always succeed immediately as long as the
+/// receiver is still alive, so there's no backpressure on senders. Care must
+/// be taken that the receiver can keep up with the values being sent.
+pub fn unbounded<T>() -> (UnboundedSender<T>, Receiver<T>) {
+    let (tx, rx) = new(None);
+    (UnboundedSender(tx), rx)
+}
+
+fn new<T>(cap: Option<usize>) -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Inner {
+        state: Mutex::new(State {
+            buffer: VecDeque::new(),
+            cap: cap,
+            reserved: 0,
+            granted: HashSet::new(),
+            parked: VecDeque::new(),
+            sender_tasks: HashMap::new(),
+            recv_task: None,
+            senders: 1,
+            next_id: 1,
+            receiver_gone: false,
+        }),
+    });
+    let tx = Sender {
+        inner: inner.clone(),
+        id: 0,
+    };
+    let rx = Receiver {
+        inner: inner,
+    };
+    (tx, rx)
+}
+
+impl<T> State<T> {
+    fn has_space(&self) -> bool {
+        match self.cap {
+            Some(cap) => self.buffer.len() + self.reserved < cap,
+            None => true,
+        }
+    }
+
+    // Hands out any free space in the buffer to parked senders, in order,
+    // returning the tasks which need to be woken up.
+    fn grant_space(&mut self) -> Vec<TaskHandle> {
+        let mut wake = Vec::new();
+        while self.has_space() {
+            let id = match self.parked.pop_front() {
+                Some(id) => id,
+                None => break,
+            };
+            self.reserved += 1;
+            self.granted.insert(id);
+            if let Some(task) = self.sender_tasks.remove(&id) {
+                wake.push(task);
+            }
+        }
+        wake
+    }
+}
+
+fn notify_all(tasks: Vec<TaskHandle>) {
+    for task in tasks {
+        task.notify();
+    }
+}
+
+impl<T> Sender<T> {
+    /// Attempts to send a value on this channel without waiting.
+    ///
+    /// This will succeed if the buffer has space available for the value, or
+    /// if this sender was parked and space has since been reserved for it.
+    /// Otherwise the value is handed back in the returned error, which also
+    /// indicates whether the channel was full or the receiver is gone.
+    ///
+    /// Note that a failed `try_send` does not park this sender.
+    pub fn try_send(&mut self, value: T) -> Result<(), TrySendError<T>> {
+        match self.send_or_park(value, false) {
+            Ok(AsyncSink::Ready) => Ok(()),
+            Ok(AsyncSink::NotReady(value)) => {
+                Err(TrySendError { kind: TrySendErrorKind::Full, value: value })
+            }
+            Err(SendError(value)) => {
+                Err(TrySendError {
+                    kind: TrySendErrorKind::Disconnected,
+                    value: value,
+                })
+            }
+        }
+    }
+
+    fn send_or_park(&mut self, value: T, park: bool)
+                    -> StartSend<T, SendError<T>> {
+        let recv_task = {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.receiver_gone {
+                return Err(SendError(value))
+            }
+
+            // If we've been woken up we've already got a slot set aside for
+            // us. Otherwise we can only take free space if nobody is already
+            // waiting in line for it.
+            if state.granted.remove(&self.id) {
+                state.reserved -= 1;
+            } else if !state.has_space() || !state.parked.is_empty() {
+                if park && !state.parked.contains(&self.id) {
+                    state.parked.push_back(self.id);
+                }
+                return Ok(AsyncSink::NotReady(value))
+            }
+            state.buffer.push_back(value);
+            state.recv_task.take()
+        };
+        if let Some(task) = recv_task {
+            task.notify();
+        }
+        Ok(AsyncSink::Ready)
+    }
+}
+
+impl<T: 'static> Sink for Sender<T> {
+    type SinkItem = T;
+    type SinkError = SendError<T>;
+
+    fn start_send(&mut self, _task: &mut Task, value: T)
+                  -> StartSend<T, SendError<T>> {
+        self.send_or_park(value, true)
+    }
+
+    fn poll_complete(&mut self, _task: &mut Task) -> Poll<(), SendError<T>> {
+        // Values go straight into the buffer, there's nothing to flush.
+        Poll::Ok(())
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        let mut state = self.inner.state.lock().unwrap();
+        let ready = state.receiver_gone ||
+                    state.granted.contains(&self.id) ||
+                    !state.parked.contains(&self.id);
+        if ready {
+            drop(state);
+            task.notify();
+        } else {
+            state.sender_tasks.insert(self.id, task.handle().clone());
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        let mut state = self.inner.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.senders += 1;
+        Sender {
+            inner: self.inner.clone(),
+            id: id,
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let (wake, recv_task) = {
+            let mut state = self.inner.state.lock().unwrap();
+            state.senders -= 1;
+            state.sender_tasks.remove(&self.id);
+            state.parked.retain(|id| *id != self.id);
+
+            // Any space set aside for us needs to go to the next in line.
+            if state.granted.remove(&self.id) {
+                state.reserved -= 1;
+            }
+            let wake = state.grant_space();
+            let recv_task = if state.senders == 0 {
+                state.recv_task.take()
+            } else {
+                None
+            };
+            (wake, recv_task)
+        };
+        notify_all(wake);
+        if let Some(task) = recv_task {
+            task.notify();
+        }
+    }
+}
+
+impl<T> UnboundedSender<T> {
+    /// Sends a value on this channel.
+    ///
+    /// This never needs to wait, and only fails if the receiver has been
+    /// dropped, in which case the value is returned in the error. This is
+    /// named differently from `Sink::send` to avoid confusion between the two.
+    pub fn unbounded_send(&self, value: T) -> Result<(), SendError<T>> {
+        let recv_task = {
+            let mut state = self.0.inner.state.lock().unwrap();
+            if state.receiver_gone {
+                return Err(SendError(value))
+            }
+            state.buffer.push_back(value);
+            state.recv_task.take()
+        };
+        if let Some(task) = recv_task {
+            task.notify();
+        }
+        Ok(())
+    }
+}
+
+impl<T: 'static> Sink for UnboundedSender<T> {
+    type SinkItem = T;
+    type SinkError = SendError<T>;
+
+    fn start_send(&mut self, _task: &mut Task, value: T)
+                  -> StartSend<T, SendError<T>> {
+        self.unbounded_send(value).map(|()| AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self, _task: &mut Task) -> Poll<(), SendError<T>> {
+        Poll::Ok(())
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        task.notify()
+    }
+}
+
+impl<T> Clone for UnboundedSender<T> {
+    fn clone(&self) -> UnboundedSender<T> {
+        UnboundedSender(self.0.clone())
+    }
+}
+
+impl<T: 'static> Stream for Receiver<T> {
+    type Item = T;
+    type Error = ();
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<Option<T>, ()> {
+        let (value, wake) = {
+            let mut state = self.inner.state.lock().unwrap();
+            match state.buffer.pop_front() {
+                Some(value) => (value, state.grant_space()),
+                None if state.senders == 0 => return Poll::Ok(None),
+                None => return Poll::NotReady,
+            }
+        };
+        notify_all(wake);
+        Poll::Ok(Some(value))
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        let mut state = self.inner.state.lock().unwrap();
+        if !state.buffer.is_empty() || state.senders == 0 {
+            drop(state);
+            task.notify();
+        } else {
+            state.recv_task = Some(task.handle().clone());
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let (buffer, wake) = {
+            let mut state = self.inner.state.lock().unwrap();
+            state.receiver_gone = true;
+            state.parked.clear();
+            let wake = state.sender_tasks.drain().map(|(_, t)| t).collect();
+            (state.buffer.split_off(0), wake)
+        };
+        drop(buffer);
+        notify_all(wake);
+    }
+}
+
+impl<T> SendError<T> {
+    /// Returns the value which failed to be sent.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_tuple("SendError").field(&"...").finish()
+    }
+}
+
+impl<T> TrySendError<T> {
+    /// Returns whether the send failed because the channel was full.
+    pub fn is_full(&self) -> bool {
+        self.kind == TrySendErrorKind::Full
+    }
+
+    /// Returns whether the send failed because the receiver was dropped.
+    pub fn is_disconnected(&self) -> bool {
+        self.kind == TrySendErrorKind::Disconnected
+    }
+
+    /// Returns the value which failed to be sent.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.kind {
+            TrySendErrorKind::Full => "Full",
+            TrySendErrorKind::Disconnected => "Disconnected",
+        };
+        fmt.debug_struct("TrySendError").field("kind", &kind).finish()
+    }
+}
@@ -0,0 +1,175 @@
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use {Future, Task, Poll};
+use super::semaphore::{Core, Waiter};
+
+/// A mutual exclusion lock whose lock is acquired asynchronously.
+///
+/// Unlike `std::sync::Mutex`, locking never blocks the current thread.
+/// Instead `lock` returns a future which resolves to a guard once the lock is
+/// available, and tasks waiting for the lock are served in the order in which
+/// they started waiting. The lock is released when the guard is dropped.
+///
+/// Cloning a `Mutex` creates a new handle to the same lock and data, which
+/// makes it easy to share the data between tasks.
+///
+/// # Examples
+///
+/// ```
+/// use futures::Future;
+/// use futures::sync::Mutex;
+///
+/// let mutex = Mutex::new(vec![1]);
+/// let handle = mutex.clone();
+///
+/// handle.lock().wait().unwrap().push(2);
+/// assert_eq!(*mutex.lock().wait().unwrap(), [1, 2]);
+/// ```
+pub struct Mutex<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// A future which resolves to a `MutexGuard` once the lock is acquired.
+///
+/// This is created by the `Mutex::lock` method.
+pub struct MutexLock<T> {
+    inner: Option<Arc<Inner<T>>>,
+    waiter: Waiter,
+}
+
+/// A guard providing access to the data protected by a `Mutex`.
+///
+/// The lock is released when the guard is dropped.
+///
+/// This is created by the `MutexLock` future or `Mutex::try_lock`.
+pub struct MutexGuard<T> {
+    inner: Arc<Inner<T>>,
+    _marker: PhantomData<*mut T>,
+}
+
+struct Inner<T> {
+    sem: Core,
+    data: UnsafeCell<T>,
+}
+
+// These mirror the impls of the standard library's `Mutex<T>`.
+unsafe impl<T: Send> Send for Inner<T> {}
+unsafe impl<T: Send> Sync for Inner<T> {}
+
+// The guard hands out `&T` though, so sharing it requires `T: Sync`, just like
+// sharing the standard library's `MutexGuard<T>`.
+unsafe impl<T: Send> Send for MutexGuard<T> {}
+unsafe impl<T: Send + Sync> Sync for MutexGuard<T> {}
+
+fn _assert() {
+    fn _assert_send<T: Send>() {}
+    fn _assert_sync<T: Sync>() {}
+    _assert_send::<Mutex<u32>>();
+    _assert_sync::<Mutex<u32>>();
+    _assert_send::<MutexLock<u32>>();
+    _assert_send::<MutexGuard<u32>>();
+    _assert_sync::<MutexGuard<u32>>();
+}
+
+impl<T: 'static> Mutex<T> {
+    /// Creates a new, unlocked mutex around the given value.
+    pub fn new(t: T) -> Mutex<T> {
+        Mutex {
+            inner: Arc::new(Inner {
+                sem: Core::new(1),
+                data: UnsafeCell::new(t),
+            }),
+        }
+    }
+
+    /// Returns a future which resolves to a guard once this lock has been
+    /// acquired.
+    pub fn lock(&self) -> MutexLock<T> {
+        MutexLock {
+            inner: Some(self.inner.clone()),
+            waiter: Waiter::new(1),
+        }
+    }
+
+    /// Attempts to acquire this lock without waiting.
+    ///
+    /// This fails if the lock is held, or if other tasks are already waiting
+    /// for it.
+    pub fn try_lock(&self) -> Option<MutexGuard<T>> {
+        if self.inner.sem.try_acquire(1) {
+            Some(MutexGuard {
+                inner: self.inner.clone(),
+                _marker: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Clone for Mutex<T> {
+    fn clone(&self) -> Mutex<T> {
+        Mutex { inner: self.inner.clone() }
+    }
+}
+
+impl<T: 'static> Future for MutexLock<T> {
+    type Item = MutexGuard<T>;
+    type Error = ();
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<MutexGuard<T>, ()> {
+        let acquired = {
+            let inner = self.inner.as_ref().expect("cannot poll MutexLock twice");
+            inner.sem.poll_acquire(&mut self.waiter)
+        };
+        if acquired {
+            Poll::Ok(MutexGuard {
+                inner: self.inner.take().unwrap(),
+                _marker: PhantomData,
+            })
+        } else {
+            Poll::NotReady
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match self.inner {
+            Some(ref inner) => inner.sem.schedule_acquire(&mut self.waiter, task),
+            None => task.notify(),
+        }
+    }
+}
+
+impl<T> Drop for MutexLock<T> {
+    fn drop(&mut self) {
+        if let Some(ref inner) = self.inner {
+            inner.sem.cancel(&mut self.waiter);
+        }
+    }
+}
+
+impl<T> Deref for MutexGuard<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        // The existence of the guard means we hold the lock, so we can access
+        // the data.
+        unsafe { &*self.inner.data.get() }
+    }
+}
+
+impl<T> DerefMut for MutexGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // As above, and we're the only guard in existence so mutable access
+        // is fine too.
+        unsafe { &mut *self.inner.data.get() }
+    }
+}
+
+impl<T> Drop for MutexGuard<T> {
+    fn drop(&mut self) {
+        self.inner.sem.release(1);
+    }
+}
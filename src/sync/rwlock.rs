@@ -0,0 +1,248 @@
+use std::cell::UnsafeCell;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use {Future, Task, Poll};
+use super::semaphore::{Core, Waiter};
+
+// Readers take one permit each and writers take all of them, so the number of
+// concurrent readers is limited to this.
+const MAX_READERS: usize = !0 >> 3;
+
+/// A reader-writer lock whose lock is acquired asynchronously.
+///
+/// Any number of readers may hold the lock at the same time, while a writer
+/// has exclusive access. Locking returns a future which resolves to a guard,
+/// and tasks are served in the order in which they started waiting. A waiting
+/// writer therefore isn't starved by readers arriving after it.
+///
+/// Cloning a `RwLock` creates a new handle to the same lock and data.
+///
+/// # Examples
+///
+/// ```
+/// use futures::Future;
+/// use futures::sync::RwLock;
+///
+/// let lock = RwLock::new(5);
+/// {
+///     let r1 = lock.read().wait().unwrap();
+///     let r2 = lock.read().wait().unwrap();
+///     assert_eq!(*r1 + *r2, 10);
+///     assert!(lock.try_write().is_none());
+/// }
+/// *lock.write().wait().unwrap() += 1;
+/// assert_eq!(*lock.read().wait().unwrap(), 6);
+/// ```
+pub struct RwLock<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// A future which resolves to a `RwLockReadGuard` once shared access is
+/// acquired.
+///
+/// This is created by the `RwLock::read` method.
+pub struct RwLockRead<T> {
+    inner: Option<Arc<Inner<T>>>,
+    waiter: Waiter,
+}
+
+/// A future which resolves to a `RwLockWriteGuard` once exclusive access is
+/// acquired.
+///
+/// This is created by the `RwLock::write` method.
+pub struct RwLockWrite<T> {
+    inner: Option<Arc<Inner<T>>>,
+    waiter: Waiter,
+}
+
+/// A guard providing shared access to the data protected by a `RwLock`.
+///
+/// This is created by the `RwLockRead` future or `RwLock::try_read`.
+pub struct RwLockReadGuard<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// A guard providing exclusive access to the data protected by a `RwLock`.
+///
+/// This is created by the `RwLockWrite` future or `RwLock::try_write`.
+pub struct RwLockWriteGuard<T> {
+    inner: Arc<Inner<T>>,
+}
+
+struct Inner<T> {
+    sem: Core,
+    data: UnsafeCell<T>,
+}
+
+// These mirror the impls of the standard library's `RwLock<T>`.
+unsafe impl<T: Send + Sync> Send for Inner<T> {}
+unsafe impl<T: Send + Sync> Sync for Inner<T> {}
+
+fn _assert() {
+    fn _assert_send<T: Send>() {}
+    fn _assert_sync<T: Sync>() {}
+    _assert_send::<RwLock<u32>>();
+    _assert_sync::<RwLock<u32>>();
+    _assert_send::<RwLockRead<u32>>();
+    _assert_send::<RwLockWrite<u32>>();
+}
+
+impl<T: 'static> RwLock<T> {
+    /// Creates a new, unlocked reader-writer lock around the given value.
+    pub fn new(t: T) -> RwLock<T> {
+        RwLock {
+            inner: Arc::new(Inner {
+                sem: Core::new(MAX_READERS),
+                data: UnsafeCell::new(t),
+            }),
+        }
+    }
+
+    /// Returns a future which resolves to a guard once shared access has been
+    /// acquired.
+    pub fn read(&self) -> RwLockRead<T> {
+        RwLockRead {
+            inner: Some(self.inner.clone()),
+            waiter: Waiter::new(1),
+        }
+    }
+
+    /// Returns a future which resolves to a guard once exclusive access has
+    /// been acquired.
+    pub fn write(&self) -> RwLockWrite<T> {
+        RwLockWrite {
+            inner: Some(self.inner.clone()),
+            waiter: Waiter::new(MAX_READERS),
+        }
+    }
+
+    /// Attempts to acquire shared access without waiting.
+    ///
+    /// This fails if a writer holds the lock or if other tasks are already
+    /// waiting for it.
+    pub fn try_read(&self) -> Option<RwLockReadGuard<T>> {
+        if self.inner.sem.try_acquire(1) {
+            Some(RwLockReadGuard { inner: self.inner.clone() })
+        } else {
+            None
+        }
+    }
+
+    /// Attempts to acquire exclusive access without waiting.
+    ///
+    /// This fails if the lock is held at all or if other tasks are already
+    /// waiting for it.
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<T>> {
+        if self.inner.sem.try_acquire(MAX_READERS) {
+            Some(RwLockWriteGuard { inner: self.inner.clone() })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Clone for RwLock<T> {
+    fn clone(&self) -> RwLock<T> {
+        RwLock { inner: self.inner.clone() }
+    }
+}
+
+impl<T: 'static> Future for RwLockRead<T> {
+    type Item = RwLockReadGuard<T>;
+    type Error = ();
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<RwLockReadGuard<T>, ()> {
+        let acquired = {
+            let inner = self.inner.as_ref().expect("cannot poll RwLockRead twice");
+            inner.sem.poll_acquire(&mut self.waiter)
+        };
+        if acquired {
+            Poll::Ok(RwLockReadGuard { inner: self.inner.take().unwrap() })
+        } else {
+            Poll::NotReady
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match self.inner {
+            Some(ref inner) => inner.sem.schedule_acquire(&mut self.waiter, task),
+            None => task.notify(),
+        }
+    }
+}
+
+impl<T> Drop for RwLockRead<T> {
+    fn drop(&mut self) {
+        if let Some(ref inner) = self.inner {
+            inner.sem.cancel(&mut self.waiter);
+        }
+    }
+}
+
+impl<T: 'static> Future for RwLockWrite<T> {
+    type Item = RwLockWriteGuard<T>;
+    type Error = ();
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<RwLockWriteGuard<T>, ()> {
+        let acquired = {
+            let inner = self.inner.as_ref().expect("cannot poll RwLockWrite twice");
+            inner.sem.poll_acquire(&mut self.waiter)
+        };
+        if acquired {
+            Poll::Ok(RwLockWriteGuard { inner: self.inner.take().unwrap() })
+        } else {
+            Poll::NotReady
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match self.inner {
+            Some(ref inner) => inner.sem.schedule_acquire(&mut self.waiter, task),
+            None => task.notify(),
+        }
+    }
+}
+
+impl<T> Drop for RwLockWrite<T> {
+    fn drop(&mut self) {
+        if let Some(ref inner) = self.inner {
+            inner.sem.cancel(&mut self.waiter);
+        }
+    }
+}
+
+impl<T> Deref for RwLockReadGuard<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        // Writers can't hold the lock while a reader does, so shared access
+        // is fine.
+        unsafe { &*self.inner.data.get() }
+    }
+}
+
+impl<T> Drop for RwLockReadGuard<T> {
+    fn drop(&mut self) {
+        self.inner.sem.release(1);
+    }
+}
+
+impl<T> Deref for RwLockWriteGuard<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.inner.data.get() }
+    }
+}
+
+impl<T> DerefMut for RwLockWriteGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // A writer holds every permit, so we're the only guard in existence.
+        unsafe { &mut *self.inner.data.get() }
+    }
+}
+
+impl<T> Drop for RwLockWriteGuard<T> {
+    fn drop(&mut self) {
+        self.inner.sem.release(MAX_READERS);
+    }
+}
@@ -0,0 +1,346 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use {Future, Task, TaskHandle, Poll};
+
+/// A counting semaphore whose permits are acquired asynchronously.
+///
+/// A semaphore starts out with a number of permits, and `acquire` returns a
+/// future which resolves once the requested number of permits is available.
+/// Permits are handed out in the order they were requested, so a request for
+/// many permits is never starved by a stream of smaller requests that arrived
+/// after it. The permits are returned to the semaphore when the `Permit`
+/// guard is dropped.
+///
+/// Cloning a semaphore creates a new handle to the same set of permits.
+///
+/// # Examples
+///
+/// ```
+/// use futures::Future;
+/// use futures::sync::Semaphore;
+///
+/// let sem = Semaphore::new(3);
+/// let a = sem.acquire(2).wait().unwrap();
+/// assert_eq!(sem.available_permits(), 1);
+/// assert!(sem.try_acquire(2).is_none());
+/// drop(a);
+/// assert!(sem.try_acquire(2).is_some());
+/// ```
+#[derive(Clone)]
+pub struct Semaphore {
+    inner: Arc<Core>,
+}
+
+/// A future which resolves to a `Permit` once permits are available.
+///
+/// Dropping this future before it resolves gives up its place in the queue.
+///
+/// This is created by the `Semaphore::acquire` method.
+pub struct Acquire {
+    inner: Option<Arc<Core>>,
+    waiter: Waiter,
+}
+
+/// A guard holding permits acquired from a `Semaphore`.
+///
+/// The permits are released back to the semaphore when this is dropped.
+///
+/// This is created by the `Acquire` future or `Semaphore::try_acquire`.
+pub struct Permit {
+    inner: Arc<Core>,
+    amount: usize,
+}
+
+/// The fair, weighted semaphore which `Semaphore`, `Mutex` and `RwLock` are
+/// built on.
+pub struct Core {
+    state: Mutex<State>,
+}
+
+/// The state of one attempt to acquire permits from a `Core`.
+pub struct Waiter {
+    amount: usize,
+    // Set once the waiter has been queued up.
+    id: Option<usize>,
+}
+
+struct State {
+    permits: usize,
+    // The number of permits available when none are held.
+    total: usize,
+    queue: VecDeque<(usize, usize)>,
+    granted: HashSet<usize>,
+    tasks: HashMap<usize, TaskHandle>,
+    next_id: usize,
+}
+
+fn _assert() {
+    fn _assert_send<T: Send>() {}
+    fn _assert_sync<T: Sync>() {}
+    _assert_send::<Semaphore>();
+    _assert_sync::<Semaphore>();
+    _assert_send::<Acquire>();
+    _assert_send::<Permit>();
+}
+
+impl Semaphore {
+    /// Creates a new semaphore with the given number of permits.
+    pub fn new(permits: usize) -> Semaphore {
+        Semaphore { inner: Arc::new(Core::new(permits)) }
+    }
+
+    /// Returns a future which resolves once `amount` permits have been
+    /// acquired.
+    ///
+    /// Requests are served in order, so this will wait for all earlier
+    /// requests to be served first even if enough permits are available.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amount` is larger than the total number of permits this
+    /// semaphore has, including those added through `add_permits`. Such a
+    /// request could never be served, and it would hold up every request
+    /// behind it forever.
+    pub fn acquire(&self, amount: usize) -> Acquire {
+        let total = self.inner.state.lock().unwrap().total;
+        assert!(amount <= total,
+                "cannot acquire {} permits from a semaphore with {}",
+                amount, total);
+        Acquire {
+            inner: Some(self.inner.clone()),
+            waiter: Waiter::new(amount),
+        }
+    }
+
+    /// Attempts to acquire `amount` permits without waiting.
+    ///
+    /// This fails if there aren't enough permits available, or if other
+    /// requests are already waiting for permits.
+    pub fn try_acquire(&self, amount: usize) -> Option<Permit> {
+        if self.inner.try_acquire(amount) {
+            Some(Permit { inner: self.inner.clone(), amount: amount })
+        } else {
+            None
+        }
+    }
+
+    /// Adds `amount` new permits to this semaphore, waking up any requests
+    /// which can now be served.
+    pub fn add_permits(&self, amount: usize) {
+        self.inner.state.lock().unwrap().total += amount;
+        self.inner.release(amount);
+    }
+
+    /// Returns the number of permits which are currently available.
+    pub fn available_permits(&self) -> usize {
+        self.inner.state.lock().unwrap().permits
+    }
+}
+
+impl Future for Acquire {
+    type Item = Permit;
+    type Error = ();
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<Permit, ()> {
+        let acquired = {
+            let inner = self.inner.as_ref().expect("cannot poll Acquire twice");
+            inner.poll_acquire(&mut self.waiter)
+        };
+        if acquired {
+            Poll::Ok(Permit {
+                inner: self.inner.take().unwrap(),
+                amount: self.waiter.amount,
+            })
+        } else {
+            Poll::NotReady
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match self.inner {
+            Some(ref inner) => inner.schedule_acquire(&mut self.waiter, task),
+            None => task.notify(),
+        }
+    }
+}
+
+impl Drop for Acquire {
+    fn drop(&mut self) {
+        if let Some(ref inner) = self.inner {
+            inner.cancel(&mut self.waiter);
+        }
+    }
+}
+
+impl Permit {
+    /// Returns the number of permits held by this guard.
+    pub fn amount(&self) -> usize {
+        self.amount
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.inner.release(self.amount);
+    }
+}
+
+impl Waiter {
+    /// Creates a new attempt to acquire `amount` permits.
+    pub fn new(amount: usize) -> Waiter {
+        Waiter { amount: amount, id: None }
+    }
+}
+
+impl Core {
+    /// Creates a new semaphore core with the given number of permits.
+    pub fn new(permits: usize) -> Core {
+        Core {
+            state: Mutex::new(State {
+                permits: permits,
+                total: permits,
+                queue: VecDeque::new(),
+                granted: HashSet::new(),
+                tasks: HashMap::new(),
+                next_id: 0,
+            }),
+        }
+    }
+
+    /// Acquires `amount` permits if they're available and nobody is queued.
+    pub fn try_acquire(&self, amount: usize) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.try_take(amount)
+    }
+
+    /// Attempts to complete the acquisition for `waiter`, queueing it up if
+    /// the permits aren't available yet.
+    ///
+    /// Returns whether the permits were acquired, in which case the caller
+    /// owns them and must eventually `release` them.
+    pub fn poll_acquire(&self, waiter: &mut Waiter) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.poll_waiter(waiter)
+    }
+
+    /// Arranges for `task` to be notified once `waiter` can acquire its
+    /// permits.
+    pub fn schedule_acquire(&self, waiter: &mut Waiter, task: &mut Task) {
+        let mut state = self.state.lock().unwrap();
+        let ready = match waiter.id {
+            Some(id) => state.granted.contains(&id),
+            None => {
+                // Queue up now so the place in line is kept, but leave the
+                // permits for `poll_acquire` to take if it can.
+                if state.queue.is_empty() && state.permits >= waiter.amount {
+                    true
+                } else {
+                    state.enqueue(waiter);
+                    false
+                }
+            }
+        };
+        if ready {
+            drop(state);
+            task.notify();
+        } else {
+            state.tasks.insert(waiter.id.unwrap(), task.handle().clone());
+        }
+    }
+
+    /// Gives up on acquiring permits for `waiter`.
+    ///
+    /// If the permits were already granted they're released, and in any case
+    /// the waiter loses its place in the queue.
+    pub fn cancel(&self, waiter: &mut Waiter) {
+        let id = match waiter.id.take() {
+            Some(id) => id,
+            None => return,
+        };
+        let tasks = {
+            let mut state = self.state.lock().unwrap();
+            state.tasks.remove(&id);
+            if state.granted.remove(&id) {
+                state.permits += waiter.amount;
+            } else {
+                state.queue.retain(|&(other, _)| other != id);
+            }
+            state.grant()
+        };
+        notify(tasks);
+    }
+
+    /// Returns `amount` permits, handing them out to queued waiters.
+    pub fn release(&self, amount: usize) {
+        let tasks = {
+            let mut state = self.state.lock().unwrap();
+            state.permits += amount;
+            state.grant()
+        };
+        notify(tasks);
+    }
+}
+
+impl State {
+    fn try_take(&mut self, amount: usize) -> bool {
+        if self.queue.is_empty() && self.permits >= amount {
+            self.permits -= amount;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn poll_waiter(&mut self, waiter: &mut Waiter) -> bool {
+        match waiter.id {
+            Some(id) => {
+                if self.granted.remove(&id) {
+                    waiter.id = None;
+                    true
+                } else {
+                    false
+                }
+            }
+            None => {
+                if self.try_take(waiter.amount) {
+                    true
+                } else {
+                    self.enqueue(waiter);
+                    false
+                }
+            }
+        }
+    }
+
+    fn enqueue(&mut self, waiter: &mut Waiter) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.queue.push_back((id, waiter.amount));
+        waiter.id = Some(id);
+    }
+
+    // Hands out permits to the front of the queue for as long as there are
+    // enough of them, returning the tasks to notify once the lock is dropped.
+    fn grant(&mut self) -> Vec<TaskHandle> {
+        let mut tasks = Vec::new();
+        while let Some(&(id, amount)) = self.queue.front() {
+            if amount > self.permits {
+                break
+            }
+            self.queue.pop_front();
+            self.permits -= amount;
+            self.granted.insert(id);
+            if let Some(task) = self.tasks.remove(&id) {
+                tasks.push(task);
+            }
+        }
+        tasks
+    }
+}
+
+fn notify(tasks: Vec<TaskHandle>) {
+    for task in tasks {
+        task.notify();
+    }
+}
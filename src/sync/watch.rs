@@ -0,0 +1,222 @@
+//! A single-producer, multi-consumer channel which only retains the most
+//! recently sent value.
+//!
+//! This is useful for broadcasting things like configuration or shutdown
+//! state, where receivers only care about the latest value. Every `Receiver`
+//! yields the current value the first time it's polled, and then a new value
+//! each time the value changes. If several values are sent before a receiver
+//! gets to poll, it only sees the last one.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use {Task, TaskHandle, Poll};
+use stream::Stream;
+
+/// The sending half of a watch channel, used to change the value.
+///
+/// Receivers are finished once the sender is dropped.
+///
+/// This is created by the `channel` function.
+pub struct Sender<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// The receiving half of a watch channel, which implements `Stream`.
+///
+/// Cloning a receiver creates a new receiver which has seen the same values.
+///
+/// This is created by the `channel` function or `Sender::subscribe`.
+pub struct Receiver<T> {
+    inner: Arc<Inner<T>>,
+    id: usize,
+    // The version of the value this receiver has last yielded, if any.
+    seen: Option<u64>,
+}
+
+/// The error returned when sending on a watch channel with no receivers.
+///
+/// This contains the value which failed to be sent.
+pub struct SendError<T>(T);
+
+struct Inner<T> {
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    value: T,
+    version: u64,
+    sender_gone: bool,
+    receivers: usize,
+    next_id: usize,
+    waiters: HashMap<usize, TaskHandle>,
+}
+
+fn _assert() {
+    fn _assert_send<T: Send>() {}
+    fn _assert_sync<T: Sync>() {}
+    _assert_send::<Sender<u32>>();
+    _assert_sync::<Sender<u32>>();
+    _assert_send::<Receiver<u32>>();
+    _assert_sync::<Receiver<u32>>();
+}
+
+/// Creates a watch channel whose value starts out as `init`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::stream::Stream;
+/// use futures::sync::watch;
+///
+/// let (tx, rx) = watch::channel("starting");
+/// let mut rx = rx.wait();
+/// assert_eq!(rx.next(), Some(Ok("starting")));
+///
+/// tx.send("running").ok().unwrap();
+/// tx.send("stopping").ok().unwrap();
+/// assert_eq!(rx.next(), Some(Ok("stopping")));
+///
+/// drop(tx);
+/// assert_eq!(rx.next(), None);
+/// ```
+pub fn channel<T: Clone>(init: T) -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Inner {
+        state: Mutex::new(State {
+            value: init,
+            version: 0,
+            sender_gone: false,
+            receivers: 1,
+            next_id: 1,
+            waiters: HashMap::new(),
+        }),
+    });
+    let rx = Receiver {
+        inner: inner.clone(),
+        id: 0,
+        seen: None,
+    };
+    (Sender { inner: inner }, rx)
+}
+
+fn notify_all(waiters: HashMap<usize, TaskHandle>) {
+    for (_, task) in waiters {
+        task.notify();
+    }
+}
+
+impl<T: Clone> Sender<T> {
+    /// Replaces the value of this channel, notifying every receiver.
+    ///
+    /// An error is returned with the value if there are no receivers.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let waiters = {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.receivers == 0 {
+                return Err(SendError(value))
+            }
+            state.value = value;
+            state.version += 1;
+            state.waiters.drain().collect()
+        };
+        notify_all(waiters);
+        Ok(())
+    }
+
+    /// Creates a new receiver, which will first yield the current value.
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut state = self.inner.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.receivers += 1;
+        Receiver {
+            inner: self.inner.clone(),
+            id: id,
+            seen: None,
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waiters = {
+            let mut state = self.inner.state.lock().unwrap();
+            state.sender_gone = true;
+            state.waiters.drain().collect()
+        };
+        notify_all(waiters);
+    }
+}
+
+impl<T: Clone> Receiver<T> {
+    /// Returns a copy of the current value of the channel.
+    ///
+    /// This does not count as having seen the value, so it will still be
+    /// yielded from the stream if it hasn't been already.
+    pub fn get(&self) -> T {
+        self.inner.state.lock().unwrap().value.clone()
+    }
+}
+
+impl<T: Clone + 'static> Stream for Receiver<T> {
+    type Item = T;
+    type Error = ();
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<Option<T>, ()> {
+        let state = self.inner.state.lock().unwrap();
+        if self.seen != Some(state.version) {
+            self.seen = Some(state.version);
+            Poll::Ok(Some(state.value.clone()))
+        } else if state.sender_gone {
+            Poll::Ok(None)
+        } else {
+            Poll::NotReady
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        let mut state = self.inner.state.lock().unwrap();
+        if self.seen != Some(state.version) || state.sender_gone {
+            drop(state);
+            task.notify();
+        } else {
+            state.waiters.insert(self.id, task.handle().clone());
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Receiver<T> {
+        let mut state = self.inner.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.receivers += 1;
+        Receiver {
+            inner: self.inner.clone(),
+            id: id,
+            seen: self.seen,
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.receivers -= 1;
+        state.waiters.remove(&self.id);
+    }
+}
+
+impl<T> SendError<T> {
+    /// Returns the value which failed to be sent.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_tuple("SendError").field(&"...").finish()
+    }
+}
@@ -57,7 +57,9 @@
 // proving that you can get access to the data. So while weird, this case should
 // still be safe, as the data's not stored in the task itself.
 
+use std::any::Any;
 use std::cell::{UnsafeCell, Cell};
+use std::collections::HashMap;
 use std::marker;
 use std::panic;
 use std::sync::Arc;
@@ -84,13 +86,15 @@ use util::Collapsed;
 /// * They store task local data. That is, any task can contain any number of
 ///   pieces of arbitrary data which can be accessed at a later date. The data
 ///   is owned and carried in the task itself, and `TaskData` handles are used
-///   to access the internals.
+///   to access the internals. Keys declared with the `task_local!` macro can
+///   also be used to access data without passing handles around.
 ///
 /// This structure is likely to expand more customizable functionality over
 /// time! That is, it's not quite done yet...
 pub struct Task {
     handle: TaskHandle,
     poll_requests: Vec<Arc<Executor>>,
+    locals: HashMap<usize, Box<Any + Send>>,
 
     // A `Task` is not `Sync`, see the docs above.
     _marker: marker::PhantomData<Cell<()>>,
@@ -102,6 +106,7 @@ pub struct Task {
 #[derive(Clone)]
 pub struct TaskHandle {
     inner: Arc<Inner>,
+    events: Vec<UnparkEvent>,
 }
 
 struct Inner {
@@ -122,16 +127,35 @@ pub struct TaskData<A> {
 unsafe impl<A: Send> Send for TaskData<A> {}
 unsafe impl<A: Sync> Sync for TaskData<A> {}
 
+/// A set of events which can be recorded when a task is notified.
+///
+/// This is used along with `UnparkEvent` and `Task::with_unpark_event` to learn
+/// which part of a large computation generated a notification.
+pub trait EventSet: Send + Sync + 'static {
+    /// Records that the event `id` has happened.
+    fn insert(&self, id: usize);
+}
+
+/// An event which is fired whenever a task is notified through a handle which
+/// was created inside of `Task::with_unpark_event`.
+#[derive(Clone)]
+pub struct UnparkEvent {
+    set: Arc<EventSet>,
+    id: usize,
+}
+
 impl Task {
     /// Creates a new task ready to drive a future.
     pub fn new() -> Task {
         Task {
             poll_requests: Vec::new(),
+            locals: HashMap::new(),
             handle: TaskHandle {
                 inner: Arc::new(Inner {
                     slot: Slot::new(None),
                     registered: AtomicBool::new(false),
                 }),
+                events: Vec::new(),
             },
             _marker: marker::PhantomData,
         }
@@ -244,6 +268,26 @@ impl Task {
         &self.handle
     }
 
+    /// Runs the closure `f` with an extra event attached to this task.
+    ///
+    /// Any `TaskHandle` cloned from this task while `f` is running will carry
+    /// `event` along with it. When such a handle is notified the event will be
+    /// inserted into its set before the task itself is notified.
+    ///
+    /// This is useful for futures which manage a large number of sub-futures,
+    /// as they can learn precisely which of their children generated a
+    /// notification instead of polling all of them. Events nest, so a handle
+    /// created inside of multiple calls to this function will fire all of the
+    /// events when notified.
+    pub fn with_unpark_event<F, R>(&mut self, event: UnparkEvent, f: F) -> R
+        where F: FnOnce(&mut Task) -> R
+    {
+        self.handle.events.push(event);
+        let ret = f(self);
+        self.handle.events.pop();
+        ret
+    }
+
     /// Inform this task that to make progress, it should call `poll` on the
     /// specified executor.
     ///
@@ -344,6 +388,21 @@ impl Task {
     }
 }
 
+// Used by `wait`, which can only ever poll on the current thread.
+pub fn has_poll_requests(task: &Task) -> bool {
+    !task.poll_requests.is_empty()
+}
+
+// The storage for values of `LocalKey`s, keyed by the address of the key.
+pub fn locals(task: &mut Task) -> &mut HashMap<usize, Box<Any + Send>> {
+    &mut task.locals
+}
+
+// Used by `TestExecutor` to see whether any handles to a task are still alive.
+pub fn handle_count(task: &Task) -> usize {
+    Arc::strong_count(&task.handle.inner)
+}
+
 fn catch_unwind<F, U>(f: F) -> thread::Result<U>
     where F: FnOnce() -> U + Send + 'static,
 {
@@ -371,7 +430,13 @@ impl TaskHandle {
     /// already be running on another thread, but this will ensure that a poll
     /// happens again to receive this notification.
     pub fn notify(&self) {
-        // First, see if we can actually register an `on_full` callback. The
+        // Let everyone interested in this notification know about it before
+        // the task is actually woken up.
+        for event in self.events.iter() {
+            event.set.insert(event.id);
+        }
+
+        // Next, see if we can actually register an `on_full` callback. The
         // `Slot` requires that only one registration happens, and this flag
         // guards that.
         if self.inner.registered.swap(true, Ordering::SeqCst) {
@@ -390,6 +455,16 @@ impl TaskHandle {
     }
 }
 
+impl UnparkEvent {
+    /// Creates a new event which, when fired, will insert `id` into `set`.
+    pub fn new(set: Arc<EventSet>, id: usize) -> UnparkEvent {
+        UnparkEvent {
+            set: set,
+            id: id,
+        }
+    }
+}
+
 impl<A> Clone for TaskData<A> {
     fn clone(&self) -> TaskData<A> {
         TaskData {
@@ -0,0 +1,184 @@
+use {Future, Task, Poll};
+use task;
+use util::Collapsed;
+
+/// Declares a new task-local key of type `futures::LocalKey`.
+///
+/// The key is a `static` which can be used to access a value stored inside of
+/// whichever task is currently polling a future. The value is lazily
+/// initialized with the expression given the first time it's accessed from a
+/// particular task, and values are never shared between tasks.
+///
+/// See the documentation of `LocalKey` for how to access the value.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate futures;
+///
+/// use futures::Task;
+///
+/// task_local!(static REQUEST_ID: u64 = 0);
+///
+/// # fn main() {
+/// let mut task = Task::new();
+/// REQUEST_ID.with(&mut task, |id| *id += 1);
+/// REQUEST_ID.with(&mut task, |id| assert_eq!(*id, 1));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! task_local {
+    ($(#[$attr:meta])* static $name:ident: $t:ty = $init:expr) => (
+        $(#[$attr])*
+        static $name: $crate::LocalKey<$t> = {
+            fn __init() -> $t { $init }
+            $crate::LocalKey { __init: __init }
+        };
+    );
+    ($(#[$attr:meta])* pub static $name:ident: $t:ty = $init:expr) => (
+        $(#[$attr])*
+        pub static $name: $crate::LocalKey<$t> = {
+            fn __init() -> $t { $init }
+            $crate::LocalKey { __init: __init }
+        };
+    );
+}
+
+/// A key for task-local data, declared with the `task_local!` macro.
+///
+/// Unlike `TaskData`, which is a handle that has to be handed to every future
+/// which needs it, a `LocalKey` is a `static` which can be used from any
+/// future to access a value inside the `Task` currently polling it. This is
+/// useful for data such as request identifiers which should travel through a
+/// deep chain of combinators without being passed along explicitly.
+///
+/// Each task has its own copy of the value, which is created by the key's
+/// initialization expression the first time it's accessed through `with`.
+pub struct LocalKey<T> {
+    // This field is only public so it can be constructed by the `task_local!`
+    // macro, it's not part of the public API.
+    #[doc(hidden)]
+    pub __init: fn() -> T,
+}
+
+/// A future which sets the value of a task-local key while another future is
+/// being polled.
+///
+/// This is created by the `LocalKey::scope` method.
+pub struct LocalScope<T: 'static, F: Future> {
+    key: &'static LocalKey<T>,
+    value: Option<T>,
+    future: Collapsed<F>,
+}
+
+impl<T: Send + 'static> LocalKey<T> {
+    /// Acquires a mutable reference to this key's value in `task`.
+    ///
+    /// If the value hasn't been set for this task yet, then it's initialized
+    /// with the key's initialization expression before `f` is called.
+    pub fn with<F, R>(&'static self, task: &mut Task, f: F) -> R
+        where F: FnOnce(&mut T) -> R
+    {
+        let init = self.__init;
+        let value = task::locals(task).entry(self.id())
+                                      .or_insert_with(|| Box::new(init()));
+        f(value.downcast_mut::<T>().unwrap())
+    }
+
+    /// Sets the value of this key in `task`, returning the previous value if
+    /// there was one.
+    pub fn set(&'static self, task: &mut Task, value: T) -> Option<T> {
+        self.replace(task, Some(value))
+    }
+
+    /// Creates a future which sets this key to `value` while `future` is
+    /// being polled.
+    ///
+    /// Whenever the returned future is polled or scheduled, `value` is put in
+    /// place for the current task, and the previous value (if any) is put
+    /// back afterwards. Any changes made to the value by `future` are kept
+    /// for the next time it's polled. This allows a value to be visible only
+    /// to one part of a computation, even if other parts of the same task use
+    /// the same key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate futures;
+    ///
+    /// use futures::*;
+    ///
+    /// task_local!(static DEADLINE: Option<u32> = None);
+    ///
+    /// # fn main() {
+    /// let f = DEADLINE.scope(Some(5), lazy(|| {
+    ///     Ok::<(), ()>(())
+    /// }));
+    /// assert_eq!(f.wait(), Ok(()));
+    /// # }
+    /// ```
+    pub fn scope<F>(&'static self, value: T, future: F) -> LocalScope<T, F>
+        where F: Future,
+    {
+        LocalScope {
+            key: self,
+            value: Some(value),
+            future: Collapsed::Start(future),
+        }
+    }
+
+    fn replace(&'static self, task: &mut Task, value: Option<T>) -> Option<T> {
+        let locals = task::locals(task);
+        let prev = match value {
+            Some(value) => locals.insert(self.id(), Box::new(value)),
+            None => locals.remove(&self.id()),
+        };
+        prev.map(|prev| *prev.downcast::<T>().ok().unwrap())
+    }
+
+    // Keys are only ever declared as statics, so their address uniquely
+    // identifies them.
+    fn id(&'static self) -> usize {
+        self as *const LocalKey<T> as usize
+    }
+}
+
+impl<T, F> LocalScope<T, F>
+    where T: Send + 'static,
+          F: Future,
+{
+    fn enter<G, R>(&mut self, task: &mut Task, g: G) -> R
+        where G: FnOnce(&mut Collapsed<F>, &mut Task) -> R
+    {
+        let prev = self.key.replace(task, self.value.take());
+        let ret = g(&mut self.future, task);
+        self.value = self.key.replace(task, prev);
+        ret
+    }
+}
+
+impl<T, F> Future for LocalScope<T, F>
+    where T: Send + 'static,
+          F: Future,
+{
+    type Item = F::Item;
+    type Error = F::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<F::Item, F::Error> {
+        self.enter(task, |future, task| future.poll(task))
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.enter(task, |future, task| future.schedule(task))
+    }
+
+    unsafe fn tailcall(&mut self)
+                       -> Option<Box<Future<Item=F::Item, Error=F::Error>>> {
+        // We can't get collapsed away entirely as the value needs to stay
+        // scoped, but the future inside can still be collapsed.
+        self.future.collapse();
+        None
+    }
+}
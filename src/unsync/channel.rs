@@ -0,0 +1,242 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use {Future, Task, TaskHandle, Poll};
+use sink::{Sink, StartSend, AsyncSink};
+use stream::Stream;
+
+/// Creates a single-threaded in-memory channel implementation of the `Stream`
+/// trait.
+///
+/// This is the equivalent of the `stream::channel` function, except that
+/// neither half can be sent to another thread, and `T` and `E` don't need to
+/// be `Send`. Like that channel, only one value can be in flight at a time,
+/// and the next value can only be sent once the first was consumed.
+///
+/// # Examples
+///
+/// ```
+/// use futures::Future;
+/// use futures::stream::Stream;
+/// use futures::unsync;
+///
+/// let (tx, rx) = unsync::channel::<i32, u32>();
+/// let tx = tx.send(Ok(1)).wait().ok().unwrap();
+/// let mut rx = rx.wait();
+/// assert_eq!(rx.next(), Some(Ok(1)));
+///
+/// drop(tx);
+/// assert_eq!(rx.next(), None);
+/// ```
+pub fn channel<T, E>() -> (Sender<T, E>, Receiver<T, E>)
+    where T: 'static,
+          E: 'static,
+{
+    let inner = Rc::new(RefCell::new(Inner {
+        slot: None,
+        sender_gone: false,
+        receiver_gone: false,
+        recv_task: None,
+        send_task: None,
+    }));
+    let sender = Sender { inner: inner.clone() };
+    let receiver = Receiver { inner: inner };
+    (sender, receiver)
+}
+
+/// The transmission end of a single-threaded channel.
+///
+/// Values can either be sent one at a time with `Sender::send`, or through
+/// the `Sink` implementation, whose `SinkItem` is the `Result` to send.
+///
+/// This is created by the `unsync::channel` function.
+pub struct Sender<T, E> {
+    inner: Rc<RefCell<Inner<T, E>>>,
+}
+
+/// A future returned by the `Sender::send` method which will resolve to the
+/// sender once it's available to send another message.
+pub struct FutureSender<T, E> {
+    sender: Option<Sender<T, E>>,
+    data: Option<Result<T, E>>,
+}
+
+/// The receiving end of a single-threaded channel, which implements the
+/// `Stream` trait.
+///
+/// This is created by the `unsync::channel` function.
+pub struct Receiver<T, E> {
+    inner: Rc<RefCell<Inner<T, E>>>,
+}
+
+/// The error returned when sending a value into a channel whose `Receiver`
+/// has been dropped.
+///
+/// This contains the value which failed to be sent.
+pub struct SendError<T, E>(Result<T, E>);
+
+struct Inner<T, E> {
+    slot: Option<Result<T, E>>,
+    sender_gone: bool,
+    receiver_gone: bool,
+    recv_task: Option<TaskHandle>,
+    send_task: Option<TaskHandle>,
+}
+
+impl<T, E> SendError<T, E> {
+    /// Returns the value which failed to be sent.
+    pub fn into_inner(self) -> Result<T, E> {
+        self.0
+    }
+}
+
+fn notify(task: Option<TaskHandle>) {
+    if let Some(task) = task {
+        task.notify();
+    }
+}
+
+impl<T: 'static, E: 'static> Stream for Receiver<T, E> {
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<Option<T>, E> {
+        let (msg, task) = {
+            let mut inner = self.inner.borrow_mut();
+            match inner.slot.take() {
+                Some(msg) => (msg, inner.send_task.take()),
+                None if inner.sender_gone => return Poll::Ok(None),
+                None => return Poll::NotReady,
+            }
+        };
+        notify(task);
+        match msg {
+            Ok(e) => Poll::Ok(Some(e)),
+            Err(e) => Poll::Err(e),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.slot.is_some() || inner.sender_gone {
+            task.notify();
+        } else {
+            inner.recv_task = Some(task.handle().clone());
+        }
+    }
+}
+
+impl<T, E> Drop for Receiver<T, E> {
+    fn drop(&mut self) {
+        let task = {
+            let mut inner = self.inner.borrow_mut();
+            inner.receiver_gone = true;
+            inner.recv_task = None;
+            inner.slot = None;
+            inner.send_task.take()
+        };
+        notify(task);
+    }
+}
+
+impl<T: 'static, E: 'static> Sender<T, E> {
+    /// Sends a new value along this channel to the receiver.
+    ///
+    /// This method consumes the sender and returns a future which will resolve
+    /// to the sender again when the value sent has been consumed.
+    pub fn send(self, t: Result<T, E>) -> FutureSender<T, E> {
+        FutureSender {
+            sender: Some(self),
+            data: Some(t),
+        }
+    }
+
+    // Attempts to place `t` in the channel, handing it back if the channel is
+    // full or failing if the receiver is gone.
+    fn try_produce(&self, t: Result<T, E>)
+                   -> Result<(), Result<Result<T, E>, SendError<T, E>>> {
+        let task = {
+            let mut inner = self.inner.borrow_mut();
+            if inner.receiver_gone {
+                return Err(Err(SendError(t)))
+            }
+            if inner.slot.is_some() {
+                return Err(Ok(t))
+            }
+            inner.slot = Some(t);
+            inner.recv_task.take()
+        };
+        notify(task);
+        Ok(())
+    }
+
+    fn schedule_empty(&mut self, task: &mut Task) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.slot.is_none() || inner.receiver_gone {
+            task.notify();
+        } else {
+            inner.send_task = Some(task.handle().clone());
+        }
+    }
+}
+
+impl<T: 'static, E: 'static> Sink for Sender<T, E> {
+    type SinkItem = Result<T, E>;
+    type SinkError = SendError<T, E>;
+
+    fn start_send(&mut self, _task: &mut Task, t: Result<T, E>)
+                  -> StartSend<Result<T, E>, SendError<T, E>> {
+        match self.try_produce(t) {
+            Ok(()) => Ok(AsyncSink::Ready),
+            Err(Ok(t)) => Ok(AsyncSink::NotReady(t)),
+            Err(Err(e)) => Err(e),
+        }
+    }
+
+    fn poll_complete(&mut self, _task: &mut Task) -> Poll<(), SendError<T, E>> {
+        // Values go straight into the channel, there's nothing to flush.
+        Poll::Ok(())
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.schedule_empty(task)
+    }
+}
+
+impl<T, E> Drop for Sender<T, E> {
+    fn drop(&mut self) {
+        let task = {
+            let mut inner = self.inner.borrow_mut();
+            inner.sender_gone = true;
+            inner.send_task = None;
+            inner.recv_task.take()
+        };
+        notify(task);
+    }
+}
+
+impl<T: 'static, E: 'static> Future for FutureSender<T, E> {
+    type Item = Sender<T, E>;
+    type Error = SendError<T, E>;
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<Self::Item, Self::Error> {
+        let data = self.data.take().expect("cannot poll FutureSender twice");
+        let sender = self.sender.take().expect("cannot poll FutureSender twice");
+        match sender.try_produce(data) {
+            Ok(()) => Poll::Ok(sender),
+            Err(Ok(data)) => {
+                self.data = Some(data);
+                self.sender = Some(sender);
+                Poll::NotReady
+            }
+            Err(Err(e)) => Poll::Err(e),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match self.sender {
+            Some(ref mut s) => s.schedule_empty(task),
+            None => task.notify(),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+//! Single-threaded versions of the channel primitives
+//!
+//! The `oneshot` and `stream::channel` primitives at the top of this crate can
+//! be used to communicate across threads, which means they pay for atomic
+//! operations and require their values to be `Send`. The versions in this
+//! module are instead built on `Rc<RefCell<..>>`. They can only be used
+//! between tasks running on the same thread, such as on one event loop, but
+//! in return they're cheaper and accept values which aren't `Send`.
+//!
+//! Apart from that they have the same APIs as their thread-safe counterparts.
+
+mod channel;
+mod oneshot;
+pub use self::channel::{channel, Sender, FutureSender, Receiver, SendError};
+pub use self::oneshot::{oneshot, Oneshot, Complete};
@@ -0,0 +1,101 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use {Future, Task, TaskHandle, Poll, Canceled};
+
+/// A single-threaded future representing the completion of a computation
+/// happening elsewhere.
+///
+/// This is created by the `unsync::oneshot` function.
+pub struct Oneshot<T> {
+    inner: Rc<RefCell<Inner<T>>>,
+}
+
+/// The completion half of a single-threaded oneshot, through which the result
+/// of a computation is signaled.
+///
+/// This is created by the `unsync::oneshot` function.
+pub struct Complete<T> {
+    inner: Rc<RefCell<Inner<T>>>,
+}
+
+struct Inner<T> {
+    value: Option<T>,
+    complete_gone: bool,
+    task: Option<TaskHandle>,
+}
+
+/// Creates a new single-threaded oneshot.
+///
+/// This is the equivalent of the `futures::oneshot` function, except that
+/// neither half can be sent to another thread, and `T` doesn't need to be
+/// `Send`.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use futures::Future;
+/// use futures::unsync;
+///
+/// let (c, p) = unsync::oneshot();
+/// c.complete(Rc::new(3));
+/// assert_eq!(*p.wait().unwrap(), 3);
+/// ```
+pub fn oneshot<T: 'static>() -> (Complete<T>, Oneshot<T>) {
+    let inner = Rc::new(RefCell::new(Inner {
+        value: None,
+        complete_gone: false,
+        task: None,
+    }));
+    let oneshot = Oneshot { inner: inner.clone() };
+    (Complete { inner: inner }, oneshot)
+}
+
+impl<T: 'static> Complete<T> {
+    /// Completes this oneshot with a successful result.
+    ///
+    /// This function will consume `self` and indicate to the other end, the
+    /// `Oneshot`, that the value provided is the result of the computation
+    /// this represents.
+    pub fn complete(self, t: T) {
+        // The `Oneshot` is notified when `self` is dropped below.
+        self.inner.borrow_mut().value = Some(t);
+    }
+}
+
+impl<T> Drop for Complete<T> {
+    fn drop(&mut self) {
+        let task = {
+            let mut inner = self.inner.borrow_mut();
+            inner.complete_gone = true;
+            inner.task.take()
+        };
+        if let Some(task) = task {
+            task.notify();
+        }
+    }
+}
+
+impl<T: 'static> Future for Oneshot<T> {
+    type Item = T;
+    type Error = Canceled;
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<T, Canceled> {
+        let mut inner = self.inner.borrow_mut();
+        match inner.value.take() {
+            Some(t) => Poll::Ok(t),
+            None if inner.complete_gone => Poll::Err(Canceled),
+            None => Poll::NotReady,
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.complete_gone {
+            task.notify();
+        } else {
+            inner.task = Some(task.handle().clone());
+        }
+    }
+}
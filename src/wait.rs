@@ -0,0 +1,75 @@
+//! Support for blocking the current thread on a future or stream.
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, Thread};
+
+use {Future, Task, Poll, EventSet, UnparkEvent};
+use task;
+
+/// A task which is driven on the current thread, parking the thread whenever
+/// it's waiting for a notification.
+pub struct ThreadTask {
+    task: Task,
+    unpark: Arc<ThreadUnpark>,
+}
+
+struct ThreadUnpark {
+    thread: Mutex<Thread>,
+    ready: AtomicBool,
+}
+
+pub fn wait<F: Future>(mut f: F) -> Result<F::Item, F::Error> {
+    let mut task = ThreadTask::new();
+    loop {
+        match task.enter(|task| f.poll(task)) {
+            Poll::Ok(e) => return Ok(e),
+            Poll::Err(e) => return Err(e),
+            Poll::NotReady => {}
+        }
+        task.enter(|task| f.schedule(task));
+        task.park();
+    }
+}
+
+impl ThreadTask {
+    pub fn new() -> ThreadTask {
+        ThreadTask {
+            task: Task::new(),
+            unpark: Arc::new(ThreadUnpark {
+                thread: Mutex::new(thread::current()),
+                ready: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Runs `f` with this task, arranging for any notifications generated
+    /// along the way to unpark the thread blocked in `park`.
+    pub fn enter<F, R>(&mut self, f: F) -> R
+        where F: FnOnce(&mut Task) -> R
+    {
+        let event = UnparkEvent::new(self.unpark.clone(), 0);
+        let ret = self.task.with_unpark_event(event, f);
+        assert!(!task::has_poll_requests(&self.task),
+                "cannot block on a future which needs to be polled on a \
+                 specific executor");
+        ret
+    }
+
+    /// Blocks the current thread until this task has been notified.
+    pub fn park(&self) {
+        // We may have been moved to a different thread since we were created
+        // or last parked, so make sure notifications wake up the right one.
+        *self.unpark.thread.lock().unwrap() = thread::current();
+        while !self.unpark.ready.swap(false, Ordering::SeqCst) {
+            thread::park();
+        }
+    }
+}
+
+impl EventSet for ThreadUnpark {
+    fn insert(&self, _id: usize) {
+        self.ready.store(true, Ordering::SeqCst);
+        self.thread.lock().unwrap().unpark();
+    }
+}
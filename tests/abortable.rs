@@ -0,0 +1,86 @@
+extern crate futures;
+
+use std::sync::mpsc::channel;
+use std::thread;
+
+use futures::*;
+
+mod support;
+use support::*;
+
+struct FutureData<F, T> {
+    _data: T,
+    future: F,
+}
+
+impl<F: Future, T: Send + 'static> Future for FutureData<F, T> {
+    type Item = F::Item;
+    type Error = F::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Self::Item, Self::Error> {
+        self.future.poll(task)
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.future.schedule(task)
+    }
+}
+
+#[test]
+fn smoke() {
+    assert_done(|| abortable(f_ok(1)).0, Ok(1));
+    assert_done(|| abortable(f_err(1)).0, Err(AbortError::Inner(1)));
+    assert_empty(|| abortable(empty::<i32, u32>()).0);
+    assert_done(|| {
+        let (f, handle) = abortable(f_ok(1));
+        handle.abort();
+        f
+    }, Err(AbortError::Aborted));
+}
+
+#[test]
+fn abort_drops_eagerly() {
+    let (tx, rx) = channel::<()>();
+    let (tx2, rx2) = channel();
+    let (f, handle) = abortable(FutureData { _data: tx, future: empty::<(), ()>() });
+    f.then(move |r| tx2.send(r)).forget();
+    assert!(rx2.try_recv().is_err());
+    assert!(!handle.is_aborted());
+
+    handle.abort();
+    assert!(handle.is_aborted());
+    assert!(rx.recv().is_err());
+    assert_eq!(rx2.recv().unwrap(), Err(AbortError::Aborted));
+}
+
+#[test]
+fn abort_drops_without_polling() {
+    let (tx, rx) = channel::<()>();
+    let (mut f, handle) = abortable(FutureData { _data: tx, future: empty::<(), ()>() });
+    let mut task = Task::new();
+    assert!(f.poll(&mut task).is_not_ready());
+
+    // The future is dropped by `abort` itself, without waiting to be polled.
+    handle.abort();
+    assert!(rx.recv().is_err());
+    assert_eq!(f.poll(&mut task), Poll::Err(AbortError::Aborted));
+}
+
+#[test]
+fn abort_from_other_thread() {
+    let (_c, p) = oneshot::<u32>();
+    let (tx, rx) = channel();
+    let (f, handle) = abortable(p);
+    f.then(move |r| tx.send(r)).forget();
+    thread::spawn(move || handle.abort()).join().unwrap();
+    assert_eq!(rx.recv().unwrap(), Err(AbortError::Aborted));
+}
+
+#[test]
+#[should_panic(expected = "cannot poll Abortable twice")]
+fn poll_twice() {
+    let (mut f, _handle) = abortable(f_ok(1));
+    let mut task = Task::new();
+    assert_eq!(f.poll(&mut task), Poll::Ok(1));
+    drop(f.poll(&mut task));
+}
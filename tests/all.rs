@@ -350,3 +350,20 @@ fn select2() {
         assert!(rx.recv().is_err());
     }
 }
+
+#[test]
+fn either() {
+    fn pick(a: bool) -> Either<Done<i32, u32>, Map<Done<i32, u32>, fn(i32) -> i32>> {
+        fn double(a: i32) -> i32 { a * 2 }
+        if a {
+            Either::A(f_ok(1))
+        } else {
+            Either::B(f_ok(2).map(double as fn(i32) -> i32))
+        }
+    }
+
+    assert_done(|| pick(true), ok(1));
+    assert_done(|| pick(false), ok(4));
+    assert_done(|| Either::A::<_, Empty<i32, u32>>(f_err(1)), err(1));
+    assert_empty(|| Either::B::<Done<i32, u32>, _>(empty()));
+}
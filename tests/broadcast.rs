@@ -0,0 +1,86 @@
+extern crate futures;
+
+use std::sync::{Arc, Mutex};
+
+use futures::*;
+use futures::executor::TestExecutor;
+use futures::stream::Stream;
+use futures::sync::broadcast::{self, RecvError};
+
+mod support;
+use support::*;
+
+#[test]
+fn every_receiver_sees_every_value() {
+    let (tx, mut rx1) = broadcast::channel::<i32>(4);
+    let mut rx2 = tx.subscribe();
+    sassert_empty(&mut rx1);
+    tx.send(1).ok().unwrap();
+    tx.send(2).ok().unwrap();
+    let mut rx3 = rx1.clone();
+    sassert_next(&mut rx1, 1);
+    sassert_next(&mut rx1, 2);
+    sassert_empty(&mut rx1);
+    sassert_next(&mut rx2, 1);
+    sassert_next(&mut rx3, 1);
+    sassert_next(&mut rx3, 2);
+
+    let mut late = tx.subscribe();
+    sassert_empty(&mut late);
+    tx.send(3).ok().unwrap();
+    drop(tx);
+    sassert_next(&mut late, 3);
+    sassert_done(&mut late);
+    sassert_next(&mut rx2, 2);
+    sassert_next(&mut rx2, 3);
+    sassert_done(&mut rx2);
+}
+
+#[test]
+fn lagging_receiver() {
+    let (tx, mut rx) = broadcast::channel::<i32>(2);
+    for i in 0..5 {
+        tx.send(i).ok().unwrap();
+    }
+    match rx.poll(&mut Task::new()) {
+        Poll::Err(e) => assert_eq!(e, RecvError::Lagged(3)),
+        _ => panic!("expected the receiver to lag"),
+    }
+    sassert_next(&mut rx, 3);
+    sassert_next(&mut rx, 4);
+    sassert_empty(&mut rx);
+}
+
+#[test]
+fn send_without_receivers() {
+    let (tx, rx) = broadcast::channel::<i32>(2);
+    drop(rx);
+    assert_eq!(tx.send(1).unwrap_err().into_inner(), 1);
+}
+
+#[test]
+fn receivers_notified() {
+    let (tx, rx1) = broadcast::channel::<i32>(4);
+    let rx2 = tx.subscribe();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let mut exec = TestExecutor::new();
+    let ids = [rx1, rx2].iter().cloned().map(|rx| {
+        let seen = seen.clone();
+        exec.spawn(rx.for_each(move |i| {
+            seen.lock().unwrap().push(i);
+            Ok(())
+        }).map_err(|_| ()))
+    }).collect::<Vec<_>>();
+    exec.run();
+    assert!(!ids.iter().any(|&id| exec.is_notified(id)));
+
+    tx.send(7).ok().unwrap();
+    assert!(ids.iter().all(|&id| exec.is_notified(id)));
+    exec.run();
+    assert_eq!(*seen.lock().unwrap(), [7, 7]);
+
+    drop(tx);
+    exec.run();
+    assert!(ids.iter().all(|&id| exec.is_done(id)));
+}
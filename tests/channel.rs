@@ -1,7 +1,8 @@
 extern crate futures;
 
-use futures::{done, Future};
+use futures::{done, Future, Task};
 use futures::stream::*;
+use futures::sink::Sink;
 
 mod support;
 use support::*;
@@ -37,3 +38,40 @@ fn drop_sender() {
     drop(tx);
     sassert_done(&mut rx);
 }
+
+#[test]
+fn poll_after_spurious_wakeup() {
+    let (tx, mut rx) = channel::<u32, u32>();
+    let mut task = Task::new();
+    rx.schedule(&mut task);
+    assert!(rx.poll(&mut task).is_not_ready());
+
+    // Wake the task up without sending anything, as a timer would, and make
+    // sure the receiver can be polled and scheduled again.
+    task.notify();
+    assert!(rx.poll(&mut task).is_not_ready());
+    rx.schedule(&mut task);
+
+    tx.send(Ok(1)).forget();
+    sassert_next(&mut rx, 1);
+}
+
+#[test]
+fn sink_send_after_spurious_wakeup() {
+    let (tx, mut rx) = channel::<u32, u32>();
+    let tx = Sink::send(tx, Ok(1)).wait().ok().unwrap();
+    let mut task = Task::new();
+    let mut send = Sink::send(tx, Ok(2));
+    assert!(send.poll(&mut task).is_not_ready());
+    send.schedule(&mut task);
+
+    // Poll again while the slot is still full, as `join` or `forward` would
+    // when the other half wakes the task.
+    task.notify();
+    assert!(send.poll(&mut task).is_not_ready());
+    send.schedule(&mut task);
+
+    sassert_next(&mut rx, 1);
+    assert!(send.poll(&mut task).is_ready());
+    sassert_next(&mut rx, 2);
+}
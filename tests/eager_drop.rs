@@ -79,3 +79,41 @@ fn and_then_drops_eagerly() {
 //     c2.finish(());
 //     rx2.recv().unwrap();
 // }
+
+#[test]
+fn select_ok_drops_losers() {
+    let (c1, p1) = oneshot::<i32>();
+    let (_c2, p2) = oneshot::<i32>();
+    let (tx1, _rx1) = channel::<()>();
+    let (tx2, rx2) = channel::<()>();
+    let (tx3, rx3) = channel();
+    select_ok(vec![
+        FutureData { _data: tx1, future: p1 },
+        FutureData { _data: tx2, future: p2 },
+    ]).map(move |_| {
+        assert!(rx2.recv().is_err());
+        tx3.send(()).unwrap()
+    }).forget();
+    assert!(rx3.try_recv().is_err());
+    c1.complete(1);
+    rx3.recv().unwrap();
+}
+
+#[test]
+fn join_all_drops_eagerly() {
+    let (c1, p1) = oneshot::<i32>();
+    let (_c2, p2) = oneshot::<i32>();
+    let (tx1, _rx1) = channel::<()>();
+    let (tx2, rx2) = channel::<()>();
+    let (tx3, rx3) = channel();
+    join_all(vec![
+        FutureData { _data: tx1, future: p1 },
+        FutureData { _data: tx2, future: p2 },
+    ]).map_err(move |_| {
+        assert!(rx2.recv().is_err());
+        tx3.send(()).unwrap()
+    }).forget();
+    assert!(rx3.try_recv().is_err());
+    drop(c1);
+    rx3.recv().unwrap();
+}
@@ -0,0 +1,98 @@
+extern crate futures;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+
+use futures::*;
+use futures::stream::*;
+
+mod support;
+use support::*;
+
+struct CountPolls<F> {
+    polls: Arc<AtomicUsize>,
+    future: F,
+}
+
+impl<F: Future> Future for CountPolls<F> {
+    type Item = F::Item;
+    type Error = F::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<F::Item, F::Error> {
+        self.polls.fetch_add(1, Ordering::SeqCst);
+        self.future.poll(task)
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.future.schedule(task)
+    }
+}
+
+#[test]
+fn smoke() {
+    let mut s = futures_unordered(vec![f_ok(1), f_err(2), f_ok(3)]);
+    sassert_next(&mut s, 1);
+    assert_eq!(s.poll(&mut Task::new()), Poll::Err(2));
+    sassert_next(&mut s, 3);
+    sassert_done(&mut s);
+
+    let mut s = FuturesUnordered::<Done<i32, u32>>::new();
+    sassert_done(&mut s);
+    s.push(f_ok(4));
+    assert_eq!(s.len(), 1);
+    sassert_next(&mut s, 4);
+    assert!(s.is_empty());
+}
+
+#[test]
+fn completion_order() {
+    let (a, b) = oneshot::<i32>();
+    let (c, d) = oneshot::<i32>();
+    let (e, f) = oneshot::<i32>();
+    let s = futures_unordered(vec![b, d, f]);
+
+    let (tx, rx) = channel();
+    s.for_each(move |i| {
+        tx.send(i).unwrap();
+        Ok(())
+    }).forget();
+
+    c.complete(2);
+    assert_eq!(rx.recv().unwrap(), 2);
+    e.complete(3);
+    assert_eq!(rx.recv().unwrap(), 3);
+    a.complete(1);
+    assert_eq!(rx.recv().unwrap(), 1);
+}
+
+#[test]
+fn only_polls_notified() {
+    let polls = (0..3).map(|_| Arc::new(AtomicUsize::new(0)))
+                      .collect::<Vec<_>>();
+    let mut completes = Vec::new();
+    let mut s = FuturesUnordered::new();
+    for p in polls.iter() {
+        let (c, o) = oneshot::<usize>();
+        completes.push(Some(c));
+        s.push(CountPolls { polls: p.clone(), future: o });
+    }
+
+    let mut task = Task::new();
+    assert!(s.poll(&mut task).is_not_ready());
+    s.schedule(&mut task);
+    for p in polls.iter() {
+        assert_eq!(p.load(Ordering::SeqCst), 1);
+    }
+
+    completes[1].take().unwrap().complete(1);
+    assert_eq!(s.poll(&mut task), Poll::Ok(Some(1)));
+    assert_eq!(polls[0].load(Ordering::SeqCst), 1);
+    assert_eq!(polls[1].load(Ordering::SeqCst), 2);
+    assert_eq!(polls[2].load(Ordering::SeqCst), 1);
+
+    s.schedule(&mut task);
+    assert!(s.poll(&mut task).is_not_ready());
+    assert_eq!(polls[0].load(Ordering::SeqCst), 1);
+    assert_eq!(polls[2].load(Ordering::SeqCst), 1);
+}
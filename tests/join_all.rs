@@ -0,0 +1,45 @@
+extern crate futures;
+
+use futures::*;
+
+mod support;
+use support::*;
+
+#[test]
+fn join_all_smoke() {
+    assert_done(|| join_all(vec![f_ok(1), f_ok(2), f_ok(3)]), Ok(vec![1, 2, 3]));
+    assert_done(|| join_all(vec![f_ok(1), f_err(2), f_err(3)]), Err(2));
+    assert_done(|| join_all(Vec::<Done<i32, u32>>::new()), Ok(vec![]));
+    assert_empty(|| join_all(vec![f_ok(1).boxed(), empty().boxed()]));
+    assert_done(|| join_all(vec![f_err(1).boxed(), empty().boxed()]), Err(1));
+}
+
+#[test]
+fn join_all_errors_smoke() {
+    assert_done(|| join_all_errors(vec![f_ok(1), f_ok(2)]), Ok(vec![1, 2]));
+    assert_done(|| join_all_errors(vec![f_err(1), f_ok(2), f_err(3)]),
+                Err(vec![1, 3]));
+    assert_empty(|| join_all_errors(vec![f_err(1).boxed(), empty().boxed()]));
+}
+
+#[test]
+fn join_all_concurrent() {
+    let (a, b) = oneshot::<i32>();
+    let (c, d) = oneshot::<i32>();
+    let mut f = join_all(vec![b, d]);
+    let mut task = Task::new();
+    assert!(f.poll(&mut task).is_not_ready());
+    c.complete(2);
+    assert!(f.poll(&mut task).is_not_ready());
+    a.complete(1);
+    assert_eq!(f.poll(&mut task).unwrap(), Ok(vec![1, 2]));
+}
+
+#[test]
+#[should_panic(expected = "cannot poll JoinAll twice")]
+fn join_all_poll_twice() {
+    let mut f = join_all(vec![f_ok(1)]);
+    let mut task = Task::new();
+    assert_eq!(f.poll(&mut task).unwrap(), Ok(vec![1]));
+    drop(f.poll(&mut task));
+}
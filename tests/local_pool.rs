@@ -0,0 +1,91 @@
+extern crate futures;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use futures::*;
+use futures::executor::LocalPool;
+
+mod support;
+use support::*;
+
+#[test]
+fn run_until_immediate() {
+    let mut pool = LocalPool::new();
+    assert_eq!(pool.run_until(f_ok(1)), Ok(1));
+    assert_eq!(pool.run_until(f_err(2)), Err(2));
+}
+
+#[test]
+fn run_not_send() {
+    let mut pool = LocalPool::new();
+    let cnt = Rc::new(Cell::new(0));
+    for _ in 0..10 {
+        let cnt = cnt.clone();
+        pool.spawn(lazy(move || {
+            cnt.set(cnt.get() + 1);
+            finished(())
+        }));
+    }
+    assert_eq!(cnt.get(), 0);
+    pool.run();
+    assert_eq!(cnt.get(), 10);
+}
+
+#[test]
+fn spawn_from_future() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let order = Rc::new(RefCell::new(Vec::new()));
+    let order2 = order.clone();
+    pool.spawn(lazy(move || {
+        order2.borrow_mut().push(1);
+        let order3 = order2.clone();
+        spawner.spawn(lazy(move || {
+            order3.borrow_mut().push(2);
+            finished(())
+        }));
+        finished(())
+    }));
+    pool.run();
+    assert_eq!(*order.borrow(), [1, 2]);
+}
+
+#[test]
+fn notified_from_other_thread() {
+    let mut pool = LocalPool::new();
+    let (c1, p1) = oneshot::<u32>();
+    let (c2, p2) = oneshot::<u32>();
+    let hit = Rc::new(Cell::new(false));
+    let hit2 = hit.clone();
+    pool.spawn(p1.map(move |v| {
+        assert_eq!(v, 1);
+        hit2.set(true);
+    }).map_err(|_| ()));
+
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        c1.complete(1);
+        thread::sleep(Duration::from_millis(50));
+        c2.complete(2);
+    });
+    assert_eq!(pool.run_until(p2), Ok(2));
+    assert!(hit.get());
+    t.join().unwrap();
+}
+
+#[test]
+fn run_until_leaves_pending() {
+    let mut pool = LocalPool::new();
+    let (c, p) = oneshot::<u32>();
+    let hit = Rc::new(Cell::new(false));
+    let hit2 = hit.clone();
+    pool.spawn(p.map(move |_| hit2.set(true)).map_err(|_| ()));
+    assert_eq!(pool.run_until(f_ok(1)), Ok(1));
+    assert!(!hit.get());
+    c.complete(1);
+    pool.run();
+    assert!(hit.get());
+}
@@ -0,0 +1,140 @@
+extern crate futures;
+
+use std::thread;
+
+use futures::*;
+use futures::executor::TestExecutor;
+use futures::sink::Sink;
+use futures::stream::Stream;
+use futures::sync::mpsc;
+
+mod support;
+use support::*;
+
+#[test]
+fn send_recv() {
+    let (tx, rx) = mpsc::channel::<i32>(16);
+    let tx = tx.send(1).wait().ok().unwrap();
+    let tx2 = tx.clone().send(2).wait().ok().unwrap();
+    drop(tx);
+    let mut rx = rx;
+    sassert_next(&mut rx, 1);
+    sassert_next(&mut rx, 2);
+    sassert_empty(&mut rx);
+    drop(tx2);
+    sassert_done(&mut rx);
+}
+
+#[test]
+fn try_send_full_and_disconnected() {
+    let (mut tx, mut rx) = mpsc::channel::<i32>(1);
+    assert!(tx.try_send(1).is_ok());
+    let err = tx.try_send(2).unwrap_err();
+    assert!(err.is_full());
+    assert_eq!(err.into_inner(), 2);
+
+    sassert_next(&mut rx, 1);
+    assert!(tx.try_send(3).is_ok());
+    drop(rx);
+    let err = tx.try_send(4).unwrap_err();
+    assert!(err.is_disconnected());
+    assert_eq!(err.into_inner(), 4);
+}
+
+#[test]
+fn senders_woken_in_order() {
+    let (mut tx, mut rx) = mpsc::channel::<i32>(1);
+    tx.try_send(0).unwrap();
+
+    let mut exec = TestExecutor::new();
+    let ids = (1..4).map(|i| {
+        exec.spawn(tx.clone().send(i).map(|_| ()).map_err(|_| ()))
+    }).collect::<Vec<_>>();
+    assert_eq!(exec.run(), 3);
+
+    // A sender which isn't parked yet has to wait its turn
+    assert!(tx.try_send(4).unwrap_err().is_full());
+
+    for i in 0..3 {
+        sassert_next(&mut rx, i);
+        assert!(exec.is_notified(ids[i as usize]));
+        assert_eq!(exec.run(), 1);
+        assert!(exec.is_done(ids[i as usize]));
+        assert!(tx.try_send(4).unwrap_err().is_full());
+    }
+    sassert_next(&mut rx, 3);
+    assert!(tx.try_send(4).is_ok());
+    sassert_next(&mut rx, 4);
+}
+
+#[test]
+fn dropping_woken_sender_passes_it_on() {
+    let (mut tx, mut rx) = mpsc::channel::<i32>(1);
+    tx.try_send(0).unwrap();
+
+    let mut exec = TestExecutor::new();
+    let (send, abort) = abortable(tx.clone().send(1));
+    let a = exec.spawn(send.map(|_| ()).map_err(|_| ()));
+    let b = exec.spawn(tx.clone().send(2).map(|_| ()).map_err(|_| ()));
+    exec.run();
+
+    // `a` gets woken up first, but is canceled instead of sending
+    sassert_next(&mut rx, 0);
+    assert!(exec.is_notified(a));
+    assert!(!exec.is_notified(b));
+    abort.abort();
+    exec.run();
+    assert!(exec.is_done(a));
+    assert!(exec.is_done(b));
+    sassert_next(&mut rx, 2);
+}
+
+#[test]
+fn receiver_drop_fails_parked_senders() {
+    let (mut tx, rx) = mpsc::channel::<i32>(1);
+    tx.try_send(0).unwrap();
+    let mut exec = TestExecutor::new();
+    let a = exec.spawn(tx.send(1).map(|_| panic!()).map_err(|e| {
+        assert_eq!(e.into_inner(), 1);
+    }));
+    exec.run();
+    drop(rx);
+    exec.run();
+    assert!(exec.is_done(a));
+}
+
+#[test]
+fn unbounded() {
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    for i in 0..100 {
+        tx.unbounded_send(i).unwrap();
+    }
+    let tx2 = tx.clone();
+    tx2.unbounded_send(100).unwrap();
+    drop((tx, tx2));
+    assert_eq!(rx.collect().wait(), Ok((0..101).collect::<Vec<_>>()));
+
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    drop(rx);
+    assert_eq!(tx.unbounded_send(1).unwrap_err().into_inner(), 1);
+}
+
+#[test]
+fn many_threads() {
+    let (tx, rx) = mpsc::channel::<u32>(2);
+    let threads = (0..4).map(|_| {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            let mut tx = tx;
+            for i in 0..200 {
+                tx = tx.send(i).wait().ok().unwrap();
+            }
+        })
+    }).collect::<Vec<_>>();
+    drop(tx);
+    let sum = rx.fold(0, |a, b| Ok::<u32, ()>(a + b)).wait().unwrap();
+    assert_eq!(sum, 4 * (0..200).sum::<u32>());
+    for t in threads {
+        t.join().unwrap();
+    }
+}
@@ -18,3 +18,32 @@ fn lots() {
     doit(1_000).map(move |_| tx.send(()).unwrap()).forget();
     rx.recv().unwrap();
 }
+
+#[test]
+fn loop_fn_lots() {
+    let (tx, rx) = channel();
+    loop_fn(0, |n| {
+        if n == 1_000_000 {
+            Ok::<_, ()>(Loop::Break(n))
+        } else {
+            Ok(Loop::Continue(n + 1))
+        }
+    }).map(move |n| tx.send(n).unwrap()).forget();
+    assert_eq!(rx.recv().unwrap(), 1_000_000);
+}
+
+#[test]
+fn loop_fn_waits() {
+    let (c, p) = oneshot::<u32>();
+    let mut p = Some(p);
+    let mut f = loop_fn(0, move |n| {
+        match p.take() {
+            Some(p) => p.map(Loop::Continue).boxed(),
+            None => finished(Loop::Break(n)).boxed(),
+        }
+    });
+    let mut task = Task::new();
+    assert!(f.poll(&mut task).is_not_ready());
+    c.complete(5);
+    assert_eq!(f.poll(&mut task), Poll::Ok(5));
+}
@@ -24,3 +24,32 @@ fn smoke() {
 
     assert!(v.len() == 0);
 }
+
+#[test]
+fn select_ok_smoke() {
+    let v = vec![
+        failed(1).boxed(),
+        finished(2).boxed(),
+        finished(3).boxed(),
+    ];
+    let mut task = Task::new();
+    assert_eq!(select_ok(v).poll(&mut task).unwrap(), Ok::<i32, i32>(2));
+
+    let v = vec![
+        failed(1).boxed(),
+        failed(2).boxed(),
+        failed(3).boxed(),
+    ];
+    assert_eq!(select_ok(v).poll(&mut task).unwrap(), Err::<i32, i32>(3));
+
+    let (c, p) = oneshot::<i32>();
+    let v = vec![
+        failed(Canceled).boxed(),
+        p.boxed(),
+        empty().boxed(),
+    ];
+    let mut f = select_ok(v);
+    assert!(f.poll(&mut task).is_not_ready());
+    c.complete(4);
+    assert_eq!(f.poll(&mut task).unwrap(), Ok(4));
+}
@@ -0,0 +1,64 @@
+extern crate futures;
+
+use std::sync::mpsc::channel;
+use std::thread;
+
+use futures::*;
+
+mod support;
+use support::*;
+
+#[test]
+fn smoke() {
+    assert_done(|| f_ok(1).shared().map(|a| *a), Ok(1));
+    assert_done(|| f_err(1).shared().map_err(|a| *a), Err(1));
+    assert_empty(|| empty::<i32, u32>().shared());
+}
+
+#[test]
+fn many_clones() {
+    let (c, p) = oneshot::<u32>();
+    let f = p.shared();
+    let (tx, rx) = channel();
+    for _ in 0..10 {
+        let tx = tx.clone();
+        f.clone().map(move |a| tx.send(*a).unwrap()).forget();
+    }
+    drop(f);
+    assert!(rx.try_recv().is_err());
+    c.complete(6);
+    for _ in 0..10 {
+        assert_eq!(rx.recv().unwrap(), 6);
+    }
+}
+
+#[test]
+fn complete_on_other_thread() {
+    let (c, p) = oneshot::<u32>();
+    let f = p.shared();
+    let (tx, rx) = channel();
+    for _ in 0..4 {
+        let tx = tx.clone();
+        f.clone().then(move |a| tx.send(a.map(|a| *a))).forget();
+    }
+    thread::spawn(move || c.complete(3)).join().unwrap();
+    for _ in 0..4 {
+        assert_eq!(rx.recv().unwrap(), Ok(3));
+    }
+}
+
+#[test]
+fn drop_scheduler() {
+    let (c, p) = oneshot::<u32>();
+    let f = p.shared();
+    let mut task = Task::new();
+    let mut a = f.clone();
+    assert!(a.poll(&mut task).is_not_ready());
+    a.schedule(&mut task);
+
+    let (tx, rx) = channel();
+    f.map(move |a| tx.send(*a).unwrap()).forget();
+    drop(a);
+    c.complete(2);
+    assert_eq!(rx.recv().unwrap(), 2);
+}
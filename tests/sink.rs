@@ -0,0 +1,141 @@
+extern crate futures;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+
+use futures::*;
+use futures::executor::TestExecutor;
+use futures::sink::{Sink, AsyncSink};
+use futures::stream::{self, Stream};
+
+#[test]
+fn sender_start_send() {
+    let (mut tx, rx) = stream::channel::<i32, u32>();
+    let mut task = Task::new();
+    assert_eq!(tx.start_send(&mut task, Ok(1)).ok().unwrap(),
+               AsyncSink::Ready);
+    assert_eq!(tx.start_send(&mut task, Ok(2)).ok().unwrap(),
+               AsyncSink::NotReady(Ok(2)));
+    assert!(tx.poll_complete(&mut task).is_ready());
+
+    let mut rx = rx.wait();
+    assert_eq!(rx.next(), Some(Ok(1)));
+    assert_eq!(tx.start_send(&mut task, Err(3)).ok().unwrap(),
+               AsyncSink::Ready);
+    assert_eq!(rx.next(), Some(Err(3)));
+    drop(rx);
+
+    match tx.start_send(&mut task, Ok(4)) {
+        Err(e) => assert_eq!(e.into_inner(), Ok(4)),
+        Ok(_) => panic!(),
+    }
+}
+
+#[test]
+fn send_across_threads() {
+    let (tx, rx) = stream::channel::<i32, u32>();
+    let t = thread::spawn(move || {
+        tx.send(Ok(1))
+          .and_then(|tx| tx.send(Ok(2)))
+          .and_then(|tx| Sink::send(tx, Ok(3)))
+          .wait()
+          .ok()
+          .unwrap();
+    });
+    assert_eq!(rx.collect().wait(), Ok(vec![1, 2, 3]));
+    t.join().unwrap();
+}
+
+#[test]
+fn forward() {
+    let (tx, rx) = stream::channel::<i32, u32>();
+    let items = (0..10).map(|i| Ok::<_, ()>(Ok(i))).collect::<Vec<_>>();
+    let t = thread::spawn(move || {
+        let (_items, _tx) = stream::iter(items.into_iter())
+            .forward(tx.sink_map_err(|_| ()))
+            .wait()
+            .ok()
+            .unwrap();
+    });
+    assert_eq!(rx.collect().wait(), Ok((0..10).collect::<Vec<_>>()));
+    t.join().unwrap();
+}
+
+#[test]
+fn forward_waits_on_both_sides() {
+    let (tx, rx) = stream::channel::<i32, u32>();
+    let (items_tx, items) = stream::channel::<Result<i32, u32>, ()>();
+    let out = Rc::new(RefCell::new(Vec::new()));
+    let out2 = out.clone();
+
+    let mut exec = TestExecutor::new();
+    let f = exec.spawn(items.forward(tx.sink_map_err(|_| ())).map(|_| ()));
+    let r = exec.spawn(rx.for_each(move |i| {
+        out2.borrow_mut().push(i);
+        Ok(())
+    }).map_err(|_| ()));
+    exec.run();
+
+    let items_tx = items_tx.send(Ok(Ok(1))).wait().ok().unwrap();
+    exec.run();
+    assert_eq!(*out.borrow(), [1]);
+    let items_tx = items_tx.send(Ok(Ok(2))).wait().ok().unwrap();
+    exec.run();
+    assert_eq!(*out.borrow(), [1, 2]);
+
+    drop(items_tx);
+    exec.run();
+    assert!(exec.is_done(f));
+    assert!(exec.is_done(r));
+}
+
+#[test]
+fn with() {
+    let (tx, rx) = stream::channel::<String, u32>();
+    let tx = tx.sink_map_err(|_| 0u32)
+               .with(|x: i32| Ok::<_, u32>(Ok(x.to_string())));
+    let sent = tx.send(1)
+                 .and_then(|tx| tx.send(2))
+                 .map(|_| ());
+    let recv = rx.take(2).collect();
+    assert_eq!(sent.join(recv).wait(),
+               Ok(((), vec!["1".to_string(), "2".to_string()])));
+}
+
+#[test]
+fn with_future() {
+    let (tx, rx) = stream::channel::<i32, u32>();
+    let (c, p) = oneshot::<i32>();
+    let mut p = Some(p);
+    let mut tx = tx.sink_map_err(|_| ()).with(move |x: i32| {
+        p.take().unwrap().map(move |y| Ok(x + y)).map_err(|_| ())
+    });
+
+    let mut task = Task::new();
+    assert!(tx.start_send(&mut task, 1).ok().unwrap().is_ready());
+    assert!(tx.poll_complete(&mut task).is_not_ready());
+    c.complete(2);
+    assert!(tx.poll_complete(&mut task).is_ready());
+    assert_eq!(rx.wait().next(), Some(Ok(3)));
+}
+
+#[test]
+fn buffer() {
+    let (tx, rx) = stream::channel::<i32, u32>();
+    let mut tx = tx.buffer(2);
+    let mut task = Task::new();
+    assert!(tx.start_send(&mut task, Ok(1)).ok().unwrap().is_ready());
+    assert!(tx.start_send(&mut task, Ok(2)).ok().unwrap().is_ready());
+    assert!(tx.start_send(&mut task, Ok(3)).ok().unwrap().is_ready());
+    assert_eq!(tx.start_send(&mut task, Ok(4)).ok().unwrap(),
+               AsyncSink::NotReady(Ok(4)));
+    assert!(tx.poll_complete(&mut task).is_not_ready());
+
+    let t = thread::spawn(move || {
+        rx.take(4).collect().wait()
+    });
+    let tx = Sink::send(tx, Ok(4)).wait().ok().unwrap();
+    assert_eq!(t.join().unwrap(), Ok(vec![1, 2, 3, 4]));
+    drop(tx);
+}
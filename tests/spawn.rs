@@ -0,0 +1,76 @@
+extern crate futures;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use futures::*;
+
+mod support;
+use support::*;
+
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn results() {
+    assert_eq!(spawn(f_ok(1)).wait().ok().unwrap(), 1);
+    match spawn(f_err(2)).wait() {
+        Err(JoinError::Inner(2)) => {}
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn panics_are_captured() {
+    let handle = spawn(lazy(|| -> Done<i32, u32> { panic!("boom") }));
+    match handle.wait() {
+        Err(JoinError::Panicked(payload)) => {
+            assert_eq!(*payload.downcast::<&str>().unwrap(), "boom");
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn wait_for_child() {
+    let (c, p) = oneshot::<u32>();
+    let handle = spawn(p);
+    assert!(!handle.is_complete());
+    let t = thread::spawn(move || c.complete(3));
+    assert_eq!(handle.wait().ok().unwrap(), 3);
+    t.join().unwrap();
+}
+
+#[test]
+fn drop_cancels() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let flag = DropFlag(dropped.clone());
+    let (c, p) = oneshot::<u32>();
+    let handle = spawn(p.map(move |_| drop(flag)));
+    assert!(!dropped.load(Ordering::SeqCst));
+    drop(handle);
+    assert!(dropped.load(Ordering::SeqCst));
+    drop(c);
+}
+
+#[test]
+fn detach_keeps_running() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let flag = DropFlag(dropped.clone());
+    let (c, p) = oneshot::<u32>();
+    let (c2, p2) = oneshot::<u32>();
+    spawn(p.map(move |v| {
+        drop(flag);
+        c2.complete(v);
+    })).detach();
+    assert!(!dropped.load(Ordering::SeqCst));
+    c.complete(4);
+    assert!(dropped.load(Ordering::SeqCst));
+    assert_eq!(p2.wait(), Ok(4));
+}
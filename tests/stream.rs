@@ -1,5 +1,8 @@
 extern crate futures;
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use futures::{failed, finished, Future, oneshot, Poll, Task};
 use futures::stream::*;
 
@@ -75,6 +78,17 @@ fn adapters() {
                 Ok(vec![2, 3]));
     assert_done(|| list().take(2).collect(), Ok(vec![1, 2]));
     assert_done(|| list().skip(2).collect(), Ok(vec![3]));
+    assert_done(|| list().take_while(|e| Ok(*e < 3)).collect(),
+                Ok(vec![1, 2]));
+    assert_done(|| err_list().take_while(|_| Ok(true)).collect(), Err(3));
+    assert_done(|| list().scan(0, |a, b| {
+        *a += b;
+        Ok(if *a < 6 { Some(*a) } else { None })
+    }).collect(), Ok(vec![1, 3]));
+    assert_done(|| list().chunks(2).collect(), Ok(vec![vec![1, 2], vec![3]]));
+    assert_done(|| list().map(|a| vec![a, a]).concat(),
+                Ok(vec![1, 1, 2, 2, 3, 3]));
+    assert_done(|| err_list().map(|a| vec![a]).concat(), Err(3));
 }
 
 #[test]
@@ -259,3 +273,109 @@ fn zip() {
     assert_done(|| list().zip(list().map(|x| x + 1)).collect(),
                 Ok(vec![(1, 2), (2, 3), (3, 4)]));
 }
+
+#[test]
+fn chunks_error() {
+    let mut s = err_list().chunks(5);
+    sassert_next(&mut s, vec![1, 2]);
+    assert_eq!(s.poll(&mut Task::new()), Poll::Err(3));
+    sassert_done(&mut s);
+}
+
+#[test]
+fn peekable() {
+    let mut s = list().peekable();
+    let mut task = Task::new();
+    assert_eq!(s.peek(&mut task), Poll::Ok(Some(&1)));
+    assert_eq!(s.peek(&mut task), Poll::Ok(Some(&1)));
+    sassert_next(&mut s, 1);
+    sassert_next(&mut s, 2);
+    assert_eq!(s.peek(&mut task), Poll::Ok(Some(&3)));
+    sassert_next(&mut s, 3);
+    assert_eq!(s.peek(&mut task), Poll::Ok(None));
+    sassert_done(&mut s);
+}
+
+#[test]
+fn inspect() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen2 = seen.clone();
+    assert_done(move || {
+        let seen2 = seen2.clone();
+        list().inspect(move |a| seen2.borrow_mut().push(*a)).collect()
+    }, Ok(vec![1, 2, 3]));
+    assert_eq!(*seen.borrow(), [1, 2, 3]);
+}
+
+#[test]
+fn constructors() {
+    assert_done(|| unfold(0, |a| {
+        if a < 3 {
+            Some(finished::<_, u32>((a, a + 1)))
+        } else {
+            None
+        }
+    }).collect(), Ok(vec![0, 1, 2]));
+    assert_done(|| once::<i32, u32>(Ok(1)).collect(), Ok(vec![1]));
+    assert_done(|| once::<i32, u32>(Err(2)).collect(), Err(2));
+    assert_done(|| repeat::<_, u32>(4).take(3).collect(), Ok(vec![4, 4, 4]));
+
+    let mut s = poll_fn(|_| Poll::<Option<i32>, u32>::NotReady);
+    sassert_empty(&mut s);
+}
+
+#[test]
+fn unfold_stops_after_error() {
+    let mut s = unfold(0, |a| {
+        Some(if a < 1 { Ok((a, a + 1)) } else { Err(7) })
+    });
+    sassert_next(&mut s, 0);
+    assert_eq!(s.poll(&mut Task::new()), Poll::Err(7));
+    sassert_done(&mut s);
+}
+
+#[test]
+fn buffer_unordered() {
+    let (tx, rx) = channel::<_, u32>();
+    let (a, b) = oneshot::<u32>();
+    let (c, d) = oneshot::<u32>();
+    let (e, f) = oneshot::<u32>();
+
+    tx.send(Ok(b.map_err(|_| 2).boxed()))
+      .and_then(|tx| tx.send(Ok(d.map_err(|_| 4).boxed())))
+      .and_then(|tx| tx.send(Ok(f.map_err(|_| 6).boxed())))
+      .forget();
+
+    let mut rx = rx.buffer_unordered(2);
+    let mut task = Task::new();
+    assert!(rx.poll(&mut task).is_not_ready());
+    rx.schedule(&mut task);
+
+    // The third future doesn't get started until one of the first two is
+    // done, and then it's not held up by the first.
+    e.complete(7);
+    assert!(rx.poll(&mut task).is_not_ready());
+    rx.schedule(&mut task);
+    c.complete(3);
+    assert_eq!(rx.poll(&mut task), Poll::Ok(Some(3)));
+    assert_eq!(rx.poll(&mut task), Poll::Ok(Some(7)));
+    assert!(rx.poll(&mut task).is_not_ready());
+    rx.schedule(&mut task);
+    drop(a);
+    assert_eq!(rx.poll(&mut task), Poll::Err(2));
+    sassert_done(&mut rx);
+}
+
+#[test]
+fn select_all_round_robin() {
+    assert_done(|| {
+        let streams = vec![repeat(1).boxed(), repeat(2).boxed(), list().boxed()];
+        select_all(streams).take(7).collect()
+    }, Ok(vec![1, 2, 1, 1, 2, 2, 1]));
+
+    assert_done(|| select_all(vec![list(), list()]).collect(),
+                Ok(vec![1, 1, 2, 2, 3, 3]));
+    assert_done(|| select_all(vec![list(), err_list()]).collect(), Err(3));
+    assert_done(|| select_all(Vec::<Receiver<i32, u32>>::new()).collect(),
+                Ok(vec![]));
+}
@@ -0,0 +1,149 @@
+extern crate futures;
+
+use std::sync::{Arc, Mutex as StdMutex};
+use std::thread;
+
+use futures::*;
+use futures::executor::TestExecutor;
+use futures::sync::{Mutex, RwLock, Semaphore};
+
+#[test]
+fn mutex_smoke() {
+    let mutex = Mutex::new(1);
+    let mut guard = mutex.try_lock().unwrap();
+    assert!(mutex.try_lock().is_none());
+    *guard = 2;
+    drop(guard);
+    assert_eq!(*mutex.lock().wait().unwrap(), 2);
+    assert_eq!(*mutex.try_lock().unwrap(), 2);
+}
+
+#[test]
+fn mutex_waiters_served_in_order() {
+    let mutex = Mutex::new(Vec::new());
+    let guard = mutex.try_lock().unwrap();
+
+    let mut exec = TestExecutor::new();
+    let ids = (0..3).map(|i| {
+        exec.spawn(mutex.lock().map(move |mut v| v.push(i)))
+    }).collect::<Vec<_>>();
+    exec.run();
+    assert!(!ids.iter().any(|&id| exec.is_done(id)));
+
+    // Someone showing up late doesn't get to jump the queue.
+    assert!(mutex.try_lock().is_none());
+
+    drop(guard);
+    assert!(exec.is_notified(ids[0]));
+    assert!(!exec.is_notified(ids[1]));
+    exec.run();
+    assert!(ids.iter().all(|&id| exec.is_done(id)));
+    assert_eq!(*mutex.try_lock().unwrap(), [0, 1, 2]);
+}
+
+#[test]
+fn dropped_waiter_passes_lock_on() {
+    let mutex = Mutex::new(());
+    let guard = mutex.try_lock().unwrap();
+
+    let mut exec = TestExecutor::new();
+    let a = exec.spawn(mutex.lock().map(|_| ()));
+    let (tx, rx) = oneshot::<()>();
+    let b = exec.spawn(mutex.lock().select(rx.then(|_| Err(())))
+                                   .map(|_| ())
+                                   .map_err(|_| ()));
+    let c = exec.spawn(mutex.lock().map(|_| ()));
+    exec.run();
+
+    drop(tx);
+    exec.run();
+    assert!(exec.is_done(b));
+
+    drop(guard);
+    exec.run();
+    assert!(exec.is_done(a));
+    assert!(exec.is_done(c));
+    assert!(mutex.try_lock().is_some());
+}
+
+#[test]
+fn mutex_many_threads() {
+    let mutex = Mutex::new(0);
+    let threads = (0..8).map(|_| {
+        let mutex = mutex.clone();
+        thread::spawn(move || {
+            for _ in 0..100 {
+                *mutex.lock().wait().unwrap() += 1;
+            }
+        })
+    }).collect::<Vec<_>>();
+    for t in threads {
+        t.join().unwrap();
+    }
+    assert_eq!(*mutex.try_lock().unwrap(), 800);
+}
+
+#[test]
+fn rwlock_writer_not_starved() {
+    let lock = RwLock::new(0);
+    let r1 = lock.try_read().unwrap();
+    let _r2 = lock.read().wait().unwrap();
+
+    let log = Arc::new(StdMutex::new(Vec::new()));
+    let mut exec = TestExecutor::new();
+    let log2 = log.clone();
+    let w = exec.spawn(lock.write().map(move |mut v| {
+        *v += 1;
+        log2.lock().unwrap().push("write");
+    }));
+    let log2 = log.clone();
+    let r = exec.spawn(lock.read().map(move |v| {
+        assert_eq!(*v, 1);
+        log2.lock().unwrap().push("read");
+    }));
+    exec.run();
+    assert!(!exec.is_done(w));
+    assert!(!exec.is_done(r));
+    assert!(lock.try_read().is_none());
+
+    drop(r1);
+    exec.run();
+    assert!(!exec.is_done(w));
+    drop(_r2);
+    exec.run();
+    assert_eq!(*log.lock().unwrap(), ["write", "read"]);
+}
+
+#[test]
+fn semaphore_weighted() {
+    let sem = Semaphore::new(4);
+    let a = sem.try_acquire(3).unwrap();
+    assert_eq!(a.amount(), 3);
+    assert_eq!(sem.available_permits(), 1);
+
+    let mut exec = TestExecutor::new();
+    let big = exec.spawn(sem.acquire(4).map(|_| ()));
+    let small = exec.spawn(sem.acquire(1).map(|_| ()));
+    exec.run();
+    // The small request has to wait behind the big one.
+    assert!(!exec.is_done(big));
+    assert!(!exec.is_done(small));
+
+    drop(a);
+    exec.run();
+    assert!(exec.is_done(big));
+    assert!(exec.is_done(small));
+    assert_eq!(sem.available_permits(), 4);
+
+    sem.add_permits(2);
+    assert_eq!(sem.available_permits(), 6);
+}
+
+#[test]
+#[should_panic(expected = "cannot acquire 5 permits from a semaphore with 4")]
+fn semaphore_acquire_too_many() {
+    let sem = Semaphore::new(2);
+    sem.add_permits(2);
+    drop(sem.acquire(4));
+    drop(sem.acquire(5));
+}
@@ -0,0 +1,78 @@
+#[macro_use]
+extern crate futures;
+
+use std::thread;
+
+use futures::*;
+
+task_local!(static COUNT: u32 = 0);
+task_local!(static NAME: Option<&'static str> = None);
+
+struct Bump;
+
+impl Future for Bump {
+    type Item = u32;
+    type Error = ();
+
+    fn poll(&mut self, task: &mut Task) -> Poll<u32, ()> {
+        Poll::Ok(COUNT.with(task, |c| {
+            *c += 1;
+            *c
+        }))
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        task.notify()
+    }
+}
+
+struct GetName;
+
+impl Future for GetName {
+    type Item = Option<&'static str>;
+    type Error = ();
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<&'static str>, ()> {
+        Poll::Ok(NAME.with(task, |n| *n))
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        task.notify()
+    }
+}
+
+#[test]
+fn lazy_init_per_task() {
+    let mut a = Task::new();
+    let mut b = Task::new();
+    assert_eq!(Bump.poll(&mut a), Poll::Ok(1));
+    assert_eq!(Bump.poll(&mut a), Poll::Ok(2));
+    assert_eq!(Bump.poll(&mut b), Poll::Ok(1));
+
+    assert_eq!(COUNT.set(&mut a, 10), Some(2));
+    assert_eq!(COUNT.set(&mut Task::new(), 10), None);
+    assert_eq!(Bump.poll(&mut a), Poll::Ok(11));
+}
+
+#[test]
+fn through_combinators() {
+    let f = Bump.and_then(|a| Bump.map(move |b| (a, b)))
+                .join(Bump);
+    assert_eq!(f.wait(), Ok(((1, 2), 3)));
+}
+
+#[test]
+fn scope() {
+    let f = GetName.join(NAME.scope(Some("inner"), GetName.join(GetName)))
+                   .join(GetName);
+    assert_eq!(f.wait(), Ok(((None, (Some("inner"), Some("inner"))), None)));
+
+    // Changes made inside the scope persist across polls of the scope.
+    let (c, p) = oneshot::<()>();
+    let f = COUNT.scope(5, Bump.and_then(|a| {
+        p.map_err(|_| ()).and_then(move |()| Bump.map(move |b| (a, b)))
+    }));
+    let t = thread::spawn(move || c.complete(()));
+    assert_eq!(f.join(Bump).wait(), Ok(((6, 7), 1)));
+    t.join().unwrap();
+}
@@ -0,0 +1,91 @@
+extern crate futures;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use futures::*;
+use futures::executor::TestExecutor;
+use futures::stream::{self, Stream};
+
+#[test]
+fn records_notifications() {
+    let mut exec = TestExecutor::new();
+    let (c, p) = oneshot::<u32>();
+    let a = exec.spawn(p.map(|v| assert_eq!(v, 1)).map_err(|_| ()));
+    let b = exec.spawn(finished(()));
+
+    assert_eq!(exec.step(), Some(a));
+    assert!(!exec.is_done(a));
+    assert_eq!(exec.step(), Some(b));
+    assert!(exec.is_done(b));
+    assert_eq!(exec.step(), None);
+
+    c.complete(1);
+    assert!(exec.is_notified(a));
+    assert_eq!(exec.notifications(a), 1);
+    assert_eq!(exec.run(), 1);
+    assert!(exec.is_done(a));
+    assert_eq!(exec.polls(a), 2);
+    assert_eq!(exec.polls(b), 1);
+}
+
+#[test]
+fn manual_notify() {
+    let mut exec = TestExecutor::new();
+    let (c, p) = oneshot::<u32>();
+    let a = exec.spawn(p.map(|_| ()).map_err(|_| ()));
+    assert_eq!(exec.run(), 1);
+
+    // A spurious wakeup is just another poll
+    exec.notify(a);
+    assert_eq!(exec.run(), 1);
+    assert_eq!(exec.polls(a), 2);
+    assert_eq!(exec.notifications(a), 0);
+    assert!(!exec.is_done(a));
+    drop(c);
+}
+
+#[test]
+fn stream_poll_counts() {
+    let mut exec = TestExecutor::new();
+    let (tx, rx) = stream::channel::<u32, u32>();
+    let sum = Rc::new(Cell::new(0));
+    let sum2 = sum.clone();
+    let a = exec.spawn(rx.for_each(move |v| {
+        sum2.set(sum2.get() + v);
+        Ok(())
+    }).map_err(|_| ()));
+    assert_eq!(exec.run(), 1);
+
+    let tx = tx.send(Ok(1)).wait().ok().unwrap();
+    exec.run();
+    let tx = tx.send(Ok(2)).wait().ok().unwrap();
+    exec.run();
+    drop(tx);
+    exec.run();
+
+    assert!(exec.is_done(a));
+    assert_eq!(sum.get(), 3);
+    assert_eq!(exec.polls(a), 1 + exec.notifications(a));
+}
+
+struct Forgetful;
+
+impl Future for Forgetful {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self, _task: &mut Task) -> Poll<(), ()> {
+        Poll::NotReady
+    }
+
+    fn schedule(&mut self, _task: &mut Task) {}
+}
+
+#[test]
+#[should_panic(expected = "without arranging to be notified")]
+fn lost_wakeup() {
+    let mut exec = TestExecutor::new();
+    exec.spawn(Forgetful);
+    exec.run();
+}
@@ -0,0 +1,78 @@
+extern crate futures;
+
+use std::rc::Rc;
+
+use futures::*;
+use futures::executor::TestExecutor;
+use futures::sink::Sink;
+use futures::stream::Stream;
+use futures::unsync;
+
+mod support;
+use support::*;
+
+#[test]
+fn oneshot_smoke() {
+    let (c, p) = unsync::oneshot::<Rc<i32>>();
+    c.complete(Rc::new(1));
+    assert_eq!(*p.wait().unwrap(), 1);
+
+    let (c, p) = unsync::oneshot::<Rc<i32>>();
+    drop(c);
+    assert_eq!(p.wait().unwrap_err(), Canceled);
+}
+
+#[test]
+fn oneshot_notifies() {
+    let (c, p) = unsync::oneshot::<Rc<i32>>();
+    let mut exec = TestExecutor::new();
+    let id = exec.spawn(p.map(|i| assert_eq!(*i, 2)).map_err(|_| ()));
+    exec.run();
+    assert!(!exec.is_notified(id));
+    c.complete(Rc::new(2));
+    assert!(exec.is_notified(id));
+    exec.run();
+    assert!(exec.is_done(id));
+}
+
+#[test]
+fn channel_smoke() {
+    let (tx, mut rx) = unsync::channel::<Rc<i32>, u32>();
+    sassert_empty(&mut rx);
+    let tx = tx.send(Ok(Rc::new(1))).wait().ok().unwrap();
+    match rx.poll(&mut Task::new()) {
+        Poll::Ok(Some(i)) => assert_eq!(*i, 1),
+        _ => panic!("expected a value"),
+    }
+    let tx = tx.send(Err(2)).wait().ok().unwrap();
+    match rx.poll(&mut Task::new()) {
+        Poll::Err(2) => {}
+        _ => panic!("expected an error"),
+    }
+    sassert_empty(&mut rx);
+    drop(tx);
+    sassert_done(&mut rx);
+}
+
+#[test]
+fn channel_backpressure() {
+    let (tx, rx) = unsync::channel::<i32, u32>();
+    let mut exec = TestExecutor::new();
+    let tx = exec.spawn(stream::iter((0..5).map(|i| Ok::<_, u32>(Ok(i))))
+                              .forward(tx.sink_map_err(|_| 0u32))
+                              .map(|_| ())
+                              .map_err(|_| ()));
+    let rx = exec.spawn(rx.collect().map(|v| assert_eq!(v, [0, 1, 2, 3, 4]))
+                                    .map_err(|_| ()));
+    exec.run();
+    assert!(exec.is_done(tx));
+    assert!(exec.is_done(rx));
+}
+
+#[test]
+fn receiver_gone() {
+    let (tx, rx) = unsync::channel::<i32, u32>();
+    drop(rx);
+    let err = tx.send(Ok(1)).wait().err().unwrap();
+    assert_eq!(err.into_inner(), Ok(1));
+}
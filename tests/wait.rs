@@ -0,0 +1,47 @@
+extern crate futures;
+
+use std::thread;
+use std::time::Duration;
+
+use futures::*;
+use futures::stream::{self, Stream};
+
+mod support;
+use support::*;
+
+#[test]
+fn immediate() {
+    assert_eq!(f_ok(1).wait(), Ok(1));
+    assert_eq!(f_err(2).wait(), Err(2));
+    assert_eq!(f_ok(1).join(f_ok(2)).wait(), Ok((1, 2)));
+}
+
+#[test]
+fn other_thread() {
+    let (c, p) = oneshot::<u32>();
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        c.complete(2);
+    });
+    assert_eq!(p.wait(), Ok(2));
+    t.join().unwrap();
+
+    let (c, p) = oneshot::<u32>();
+    drop(c);
+    assert_eq!(p.wait(), Err(Canceled));
+}
+
+#[test]
+fn stream() {
+    let (tx, rx) = stream::channel::<u32, u32>();
+    let t = thread::spawn(move || {
+        tx.send(Ok(1))
+          .and_then(|tx| tx.send(Err(2)))
+          .and_then(|tx| tx.send(Ok(3)))
+          .wait()
+          .ok()
+          .unwrap();
+    });
+    assert_eq!(rx.wait().collect::<Vec<_>>(), [Ok(1), Err(2), Ok(3)]);
+    t.join().unwrap();
+}
@@ -0,0 +1,63 @@
+extern crate futures;
+
+use futures::executor::TestExecutor;
+use futures::stream::Stream;
+use futures::sync::watch;
+
+mod support;
+use support::*;
+
+#[test]
+fn yields_latest_value() {
+    let (tx, mut rx) = watch::channel(0);
+    sassert_next(&mut rx, 0);
+    sassert_empty(&mut rx);
+
+    tx.send(1).ok().unwrap();
+    tx.send(2).ok().unwrap();
+    assert_eq!(rx.get(), 2);
+    sassert_next(&mut rx, 2);
+    sassert_empty(&mut rx);
+
+    let mut rx2 = tx.subscribe();
+    sassert_next(&mut rx2, 2);
+    let mut rx3 = rx2.clone();
+    sassert_empty(&mut rx3);
+
+    tx.send(3).ok().unwrap();
+    drop(tx);
+    sassert_next(&mut rx, 3);
+    sassert_done(&mut rx);
+    sassert_next(&mut rx3, 3);
+    sassert_done(&mut rx3);
+}
+
+#[test]
+fn send_without_receivers() {
+    let (tx, rx) = watch::channel(0);
+    drop(rx);
+    assert_eq!(tx.send(1).unwrap_err().into_inner(), 1);
+}
+
+#[test]
+fn receivers_notified() {
+    let (tx, rx1) = watch::channel(0);
+    let rx2 = rx1.clone();
+
+    let mut exec = TestExecutor::new();
+    let a = exec.spawn(rx1.for_each(|_| Ok(())));
+    let b = exec.spawn(rx2.for_each(|_| Ok(())));
+    exec.run();
+    assert!(!exec.is_notified(a));
+    assert!(!exec.is_notified(b));
+
+    tx.send(1).ok().unwrap();
+    assert!(exec.is_notified(a));
+    assert!(exec.is_notified(b));
+    exec.run();
+
+    drop(tx);
+    exec.run();
+    assert!(exec.is_done(a));
+    assert!(exec.is_done(b));
+}